@@ -0,0 +1 @@
+DELETE FROM ingredient_synonyms WHERE id = ?;
@@ -0,0 +1 @@
+DELETE FROM pantry_items WHERE id = ?;
@@ -84,7 +84,13 @@ SELECT
     i.id,
     i.amount,
     i.unit,
-    i.name
+    i.amount_max,
+    i.to_taste,
+    i.name,
+    r.id AS recipe_id,
+    r.title AS recipe_title,
+    asd.scheduled_date AS "scheduled_date: NaiveDate"
 FROM ingredients i
 JOIN all_scheduled_dates asd ON i.recipe_id = asd.recipe_id
+JOIN recipes r ON r.id = asd.recipe_id
 WHERE asd.scheduled_date BETWEEN date(?) AND date(?);
@@ -0,0 +1 @@
+SELECT id, name, canonical_name FROM ingredient_synonyms ORDER BY name;
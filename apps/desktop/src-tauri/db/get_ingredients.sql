@@ -1 +1 @@
-SELECT i.id, i.name, i.amount, i.unit FROM ingredients i WHERE recipe_id = ?;
+SELECT i.id, i.name, i.amount, i.unit, i.amount_max, i.to_taste FROM ingredients i WHERE recipe_id = ?;
@@ -0,0 +1 @@
+SELECT id, name, amount, unit FROM pantry_items ORDER BY name;
@@ -0,0 +1 @@
+SELECT id, name, price, amount, unit FROM price_book ORDER BY name;
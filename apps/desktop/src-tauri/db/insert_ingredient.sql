@@ -1 +1 @@
-INSERT INTO ingredients (recipe_id, name, amount, unit, sequence) VALUES (?, ?, ?, ?, ?) RETURNING id;
+INSERT INTO ingredients (recipe_id, name, amount, unit, amount_max, to_taste, sequence) VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING id;
@@ -0,0 +1 @@
+INSERT INTO ingredient_synonyms (name, canonical_name) VALUES (?, ?) RETURNING id;
@@ -0,0 +1 @@
+INSERT INTO pantry_items (name, amount, unit) VALUES (?, ?, ?) RETURNING id;
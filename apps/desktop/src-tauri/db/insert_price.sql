@@ -0,0 +1 @@
+INSERT INTO price_book (name, price, amount, unit) VALUES (?, ?, ?, ?) RETURNING id;
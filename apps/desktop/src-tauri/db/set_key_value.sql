@@ -0,0 +1,3 @@
+INSERT INTO key_value (key, value)
+VALUES (?, ?)
+ON CONFLICT (key) DO UPDATE SET value = excluded.value;
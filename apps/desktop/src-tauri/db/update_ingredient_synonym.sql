@@ -0,0 +1,4 @@
+UPDATE ingredient_synonyms
+    SET name = ?, canonical_name = ?
+    WHERE id = ?
+    RETURNING id;
@@ -0,0 +1,4 @@
+UPDATE pantry_items
+    SET name = ?, amount = ?, unit = ?
+    WHERE id = ?
+    RETURNING id;
@@ -0,0 +1,4 @@
+UPDATE price_book
+    SET name = ?, price = ?, amount = ?, unit = ?
+    WHERE id = ?
+    RETURNING id;
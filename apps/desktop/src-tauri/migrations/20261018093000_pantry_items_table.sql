@@ -0,0 +1,7 @@
+-- Add migration script here
+CREATE TABLE pantry_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    amount REAL NOT NULL,
+    unit TEXT NOT NULL
+);
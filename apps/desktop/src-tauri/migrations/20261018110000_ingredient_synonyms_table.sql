@@ -0,0 +1,6 @@
+-- Add migration script here
+CREATE TABLE ingredient_synonyms (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    canonical_name TEXT NOT NULL
+);
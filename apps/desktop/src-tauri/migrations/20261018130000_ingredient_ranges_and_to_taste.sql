@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE ingredients ADD COLUMN amount_max REAL;
+ALTER TABLE ingredients ADD COLUMN to_taste BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- Add migration script here
+CREATE TABLE price_book (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    price REAL NOT NULL,
+    amount REAL NOT NULL,
+    unit TEXT NOT NULL
+);
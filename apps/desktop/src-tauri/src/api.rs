@@ -1,9 +1,13 @@
 pub mod archive;
 pub mod auth;
 pub mod groceries;
+pub mod grocery_list;
+pub mod pantry;
+pub mod prices;
 pub mod recipe;
 pub mod recipes;
 pub mod sync_data;
+pub mod synonyms;
 pub mod tags;
 
 use serde::{Deserialize, Serialize};
@@ -12,6 +16,18 @@ use tauri_plugin_opener::OpenerExt;
 
 use crate::AppState;
 
+/// Reads a flag the frontend passes as a string, "true" or "false". Any other value is an
+/// error naming the parameter, rather than being read as false.
+pub fn parse_flag(name: &str, value: Option<String>) -> Result<Option<bool>, String> {
+    value
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| format!("{name} must be \"true\" or \"false\", not \"{value}\""))
+        })
+        .transpose()
+}
+
 /// Represents a generic API response with a data field.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GenericResponse<T> {
@@ -6,6 +6,8 @@ use tauri_plugin_dialog::{DialogExt, FilePath};
 use walkdir::WalkDir;
 use zip::{write::FileOptions, ZipArchive, ZipWriter};
 
+use crate::AppState;
+
 /// Recursively add a directory's contents to a ZipWriter.
 fn zip_dir(
     writer: &mut ZipWriter<File>,
@@ -154,5 +156,8 @@ pub async fn unzip_data(app: AppHandle) -> Result<(), String> {
         }
     }
 
+    // The restored data has its own ingredient synonyms
+    app.state::<AppState>().forget_mergers().await;
+
     Ok(())
 }
@@ -1,36 +1,226 @@
+use std::{collections::HashMap, sync::Arc};
+
 use chrono::NaiveDate;
+use serde::Serialize;
 use tauri::State;
 
 use crate::{
-    api::{ErrorResponseWithMessage, GenericResponse},
-    crud::recipe_data::get_groceries,
+    api::{parse_flag, ErrorResponseWithMessage},
+    crud::{pantry::get_pantry_items, prices::get_prices, recipe_data::get_groceries},
+    types::response_bodies::{GroceryLine, GrocerySource},
     AppState,
 };
 
-use groceryify::{ingredient::Ingredient, merge};
+use groceryify::{
+    category,
+    cost::Price,
+    diagnostics::MergeReport,
+    formatting::{format_items, FormatOptions},
+    ingredient::Ingredient,
+    options::{AmbiguousUnits, MergeOptions, UnitSystem},
+    pantry::CoveredItems,
+    Merger,
+};
+
+pub mod save;
+
+/// A grocery list with its estimated cost.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroceriesResponse {
+    pub data: Vec<GroceryLine>,
+    /// The estimated cost of the lines the price book has a price for, if there are any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_cost: Option<f64>,
+}
+
+/// A recipe on the schedule that ingredients of a grocery list came from.
+pub(crate) struct ScheduledRecipe {
+    pub recipe_id: i64,
+    pub recipe_title: String,
+    pub scheduled_date: Option<NaiveDate>,
+}
+
+/// The tag the sources of grocery items carry for the scheduled recipe they came from, e.g.
+/// "12@2024-05-01". It names the same recipe and day however the schedule is queried.
+pub(crate) fn source_key(recipe_id: i64, scheduled_date: Option<NaiveDate>) -> String {
+    match scheduled_date {
+        Some(date) => format!("{recipe_id}@{date}"),
+        None => recipe_id.to_string(),
+    }
+}
+
+/// The scheduled ingredients merged into a grocery list, with what they were merged from.
+pub(crate) struct MergedGroceries {
+    /// The scheduled recipes the ingredients came from, by [`source_key`].
+    pub recipes: HashMap<String, ScheduledRecipe>,
+    pub merger: Arc<Merger>,
+    pub report: MergeReport,
+}
+
+/// Merges the ingredients of the recipes scheduled between two dates into a grocery list,
+/// optionally subtracting the pantry and rounding to package sizes. The user's ingredient
+/// synonyms are added to `merge_options`.
+pub(crate) async fn merge_groceries(
+    state: &AppState,
+    from_date: &str,
+    to_date: &str,
+    merge_options: MergeOptions,
+    subtract_pantry_items: bool,
+    round_to_packages: bool,
+) -> Result<MergedGroceries, ErrorResponseWithMessage> {
+    let start_date = NaiveDate::parse_from_str(from_date, "%Y-%m-%d")
+        .map_err(|e| ErrorResponseWithMessage::new(e.to_string()))?;
+    let end_date = NaiveDate::parse_from_str(to_date, "%Y-%m-%d")
+        .map_err(|e| ErrorResponseWithMessage::new(e.to_string()))?;
+    let rows = get_groceries(&state.db, start_date, end_date)
+        .await
+        .map_err(|e| e.to_string())?;
+    let raw_groceries = rows
+        .iter()
+        .map(|i| Ingredient::from(i).with_source(&source_key(i.recipe_id, i.scheduled_date)))
+        .collect::<Vec<Ingredient>>();
+    let recipes = rows
+        .into_iter()
+        .map(|row| {
+            (
+                source_key(row.recipe_id, row.scheduled_date),
+                ScheduledRecipe {
+                    recipe_id: row.recipe_id,
+                    recipe_title: row.recipe_title,
+                    scheduled_date: row.scheduled_date,
+                },
+            )
+        })
+        .collect();
+    let merger = state.merger(merge_options).await?;
+    let mut report = merger.merge_with_diagnostics(&raw_groceries);
+    if subtract_pantry_items {
+        let pantry = get_pantry_items(&state.db)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|i| Ingredient::new(&i.name, i.amount, &i.unit))
+            .collect::<Vec<Ingredient>>();
+        report.items = merger.subtract_pantry(report.items, &pantry, CoveredItems::Drop);
+    }
+    if round_to_packages {
+        report.items = merger.round_to_packages(report.items);
+    }
+    Ok(MergedGroceries {
+        recipes,
+        merger,
+        report,
+    })
+}
 
 #[tauri::command]
 pub async fn api_groceries(
     state: State<'_, AppState>,
     from_date: String,
     to_date: String,
-) -> Result<GenericResponse<Vec<Ingredient>>, ErrorResponseWithMessage> {
-    let start_date = NaiveDate::parse_from_str(&from_date, "%Y-%m-%d")
-        .map_err(|e| ErrorResponseWithMessage::new(e.to_string()))?;
-    let end_date = NaiveDate::parse_from_str(&to_date, "%Y-%m-%d")
-        .map_err(|e| ErrorResponseWithMessage::new(e.to_string()))?;
-    let raw_groceries = get_groceries(&state.db, start_date, end_date)
+    format_options: Option<FormatOptions>,
+    unit_system: Option<UnitSystem>,
+    ambiguous_units: Option<AmbiguousUnits>,
+    group_by_category: Option<String>,
+    subtract_pantry_items: Option<String>,
+    round_to_packages: Option<String>,
+    locale: Option<String>,
+) -> Result<GroceriesResponse, ErrorResponseWithMessage> {
+    let group_by_category = parse_flag("groupByCategory", group_by_category)?;
+    let subtract_pantry_items = parse_flag("subtractPantryItems", subtract_pantry_items)?;
+    let round_to_packages = parse_flag("roundToPackages", round_to_packages)?;
+    let options = MergeOptions {
+        unit_system: unit_system.unwrap_or_default(),
+        ambiguous_units: ambiguous_units.unwrap_or_default(),
+        locale: locale.clone(),
+        ..Default::default()
+    };
+    options.locale().map_err(|e| e.to_string())?;
+    let mut format_options = format_options.unwrap_or_default();
+    format_options.locale = format_options.locale.or(locale);
+    format_options.locale().map_err(|e| e.to_string())?;
+    let MergedGroceries {
+        recipes,
+        merger,
+        report,
+    } = merge_groceries(
+        &state,
+        &from_date,
+        &to_date,
+        options,
+        subtract_pantry_items.unwrap_or_default(),
+        round_to_packages.unwrap_or_default(),
+    )
+    .await?;
+    let formatted_groceries = format_items(&report.items, &format_options);
+    // Grouped lists stay flat for the frontend, but are ordered section by section
+    let formatted_groceries = if group_by_category.unwrap_or_default() {
+        category::group_by_category(formatted_groceries)
+            .into_iter()
+            .flat_map(|group| group.items)
+            .collect()
+    } else {
+        formatted_groceries
+    };
+    let prices = get_prices(&state.db)
         .await
         .map_err(|e| e.to_string())?
         .into_iter()
-        .map(|i| Ingredient {
-            name: i.name,
-            amount: i.amount,
-            unit: i.unit,
+        .map(Price::from)
+        .collect::<Vec<Price>>();
+    let listed = formatted_groceries
+        .iter()
+        .map(|item| item.item.clone())
+        .collect::<Vec<_>>();
+    let estimate = merger.estimate_cost(&listed, &prices);
+    let total_cost = estimate
+        .items
+        .iter()
+        .any(|item| item.cost.is_some())
+        .then_some(estimate.total);
+    let data = formatted_groceries
+        .into_iter()
+        .zip(estimate.items)
+        .map(|(item, item_cost)| {
+            let recipes = item
+                .item
+                .sources
+                .iter()
+                .filter_map(|source| {
+                    let recipe = recipes.get(source.source.as_deref()?)?;
+                    Some(GrocerySource {
+                        recipe_id: recipe.recipe_id,
+                        recipe_title: recipe.recipe_title.clone(),
+                        scheduled_date: recipe.scheduled_date,
+                        amount: source.amount,
+                        unit: source.unit.clone(),
+                        amount_max: source.amount_max,
+                        to_taste: source.to_taste,
+                        interpreted_as: source.interpreted_as.clone(),
+                    })
+                })
+                .collect();
+            // Explains why this ingredient also appears on other lines
+            let hints = report
+                .diagnostics
+                .iter()
+                .filter(|d| d.name == item.item.ingredient.name)
+                .flat_map(|d| d.reasons.iter().map(|reason| reason.to_string()))
+                .collect();
+            let buy = item
+                .item
+                .purchase
+                .as_ref()
+                .map(|purchase| purchase.to_string());
+            GroceryLine {
+                item,
+                recipes,
+                hints,
+                buy,
+                cost: item_cost.cost,
+            }
         })
-        .collect::<Vec<Ingredient>>();
-    let merged_groceries = merge(&raw_groceries);
-    Ok(GenericResponse {
-        data: merged_groceries,
-    })
+        .collect();
+    Ok(GroceriesResponse { data, total_cost })
 }
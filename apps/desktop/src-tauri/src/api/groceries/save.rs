@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::{DialogExt, FilePath};
+
+use crate::{
+    api::{
+        groceries::{merge_groceries, MergedGroceries},
+        parse_flag, ErrorResponseWithMessage, GenericResponse, SuccessResponse,
+    },
+    AppState,
+};
+
+use groceryify::{
+    formatting::FormatOptions,
+    grocery_item::{GroceryItem, SourceQuantity},
+    options::{AmbiguousUnits, MergeOptions, UnitSystem},
+    render::{render, RenderFormat, RenderOptions},
+};
+
+/// Render the grocery list for a date range and prompt the user where to save it.
+#[tauri::command]
+pub async fn api_groceries_save(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    from_date: String,
+    to_date: String,
+    format: Option<RenderFormat>,
+    format_options: Option<FormatOptions>,
+    unit_system: Option<UnitSystem>,
+    ambiguous_units: Option<AmbiguousUnits>,
+    group_by_category: Option<String>,
+    provenance: Option<String>,
+    subtract_pantry_items: Option<String>,
+    round_to_packages: Option<String>,
+    locale: Option<String>,
+) -> Result<GenericResponse<SuccessResponse>, ErrorResponseWithMessage> {
+    let group_by_category = parse_flag("groupByCategory", group_by_category)?;
+    let provenance = parse_flag("provenance", provenance)?;
+    let subtract_pantry_items = parse_flag("subtractPantryItems", subtract_pantry_items)?;
+    let round_to_packages = parse_flag("roundToPackages", round_to_packages)?;
+    let options = MergeOptions {
+        unit_system: unit_system.unwrap_or_default(),
+        ambiguous_units: ambiguous_units.unwrap_or_default(),
+        locale: locale.clone(),
+        ..Default::default()
+    };
+    options.locale().map_err(|e| e.to_string())?;
+    let mut format_options = format_options.unwrap_or_default();
+    format_options.locale = format_options.locale.or(locale);
+    format_options.locale().map_err(|e| e.to_string())?;
+    let MergedGroceries {
+        recipes, report, ..
+    } = merge_groceries(
+        &state,
+        &from_date,
+        &to_date,
+        options,
+        subtract_pantry_items.unwrap_or_default(),
+        round_to_packages.unwrap_or_default(),
+    )
+    .await?;
+    // Name sources after their recipes for the file
+    let items = report
+        .items
+        .into_iter()
+        .map(|item| GroceryItem {
+            sources: item
+                .sources
+                .into_iter()
+                .map(|source| SourceQuantity {
+                    source: source
+                        .source
+                        .as_deref()
+                        .and_then(|key| recipes.get(key))
+                        .map(|recipe| recipe.recipe_title.clone()),
+                    ..source
+                })
+                .collect(),
+            ..item
+        })
+        .collect::<Vec<_>>();
+    let format = format.unwrap_or_default();
+    let text = render(
+        &items,
+        &RenderOptions {
+            format,
+            group_by_category: group_by_category.unwrap_or_default(),
+            provenance: provenance.unwrap_or_default(),
+            format_options,
+        },
+    );
+
+    let extension = format.extension();
+    let save_path: Option<PathBuf> = app
+        .dialog()
+        .file()
+        .set_title("Save grocery list")
+        .add_filter("Grocery List", &[extension])
+        .set_file_name(format!("groceries.{extension}"))
+        .blocking_save_file()
+        .and_then(|fp: FilePath| fp.into_path().ok());
+
+    let dest = match save_path {
+        Some(p) => p,
+        None => {
+            // User cancelled – not an error.
+            return Ok(GenericResponse {
+                data: SuccessResponse::new("Save cancelled".to_string()),
+            });
+        }
+    };
+
+    std::fs::write(&dest, text)
+        .map_err(|e| ErrorResponseWithMessage::new(format!("Could not save grocery list: {e}")))?;
+
+    Ok(GenericResponse {
+        data: SuccessResponse::new(format!("Saved grocery list to {}", dest.display())),
+    })
+}
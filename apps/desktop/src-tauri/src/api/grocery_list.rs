@@ -0,0 +1,99 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponseWithMessage, GenericResponse},
+    crud::key_value::{get_key_value, set_key_value},
+    AppState,
+};
+
+use groceryify::{
+    formatting::{format_items, Formatted},
+    grocery_list::{Batch, GroceryList, ItemEdit, ListItem, SavedGroceryList},
+    options::MergeOptions,
+};
+
+pub mod add;
+pub mod clear;
+pub mod edit;
+pub mod remove;
+
+/// The key the grocery list is saved under in the key-value store.
+const GROCERY_LIST_KEY: &str = "grocery_list";
+
+/// What is saved of the grocery list. Its options aren't: they only hold the user's synonyms,
+/// which the merger it is loaded with already has.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedList<'a> {
+    batches: &'a [Batch],
+    edits: &'a BTreeMap<String, ItemEdit>,
+}
+
+/// The saved grocery list, ready to display.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroceryListResponse {
+    pub items: Vec<Formatted<ListItem>>,
+    /// The recipes on the list, in the order they were added.
+    pub recipe_ids: Vec<i64>,
+}
+
+/// Loads the saved grocery list, or an empty one, merged with the user's current synonyms.
+///
+/// Commands that change the list should hold `state.grocery_list` from loading it until it is
+/// saved, so changes made at the same time aren't lost.
+pub(crate) async fn load_grocery_list(
+    state: &AppState,
+) -> Result<GroceryList, ErrorResponseWithMessage> {
+    let merger = state.merger(MergeOptions::default()).await?;
+    let saved = get_key_value(&state.db, GROCERY_LIST_KEY)
+        .await
+        .map_err(|e| e.to_string())?;
+    let saved = match saved {
+        Some(json) => serde_json::from_str::<SavedGroceryList>(&json).map_err(|e| e.to_string())?,
+        None => SavedGroceryList::default(),
+    };
+    Ok(GroceryList::with_merger(merger, saved.batches, saved.edits))
+}
+
+/// Saves the grocery list, returning it ready to display.
+pub(crate) async fn save_grocery_list(
+    state: &AppState,
+    list: &GroceryList,
+) -> Result<GenericResponse<GroceryListResponse>, ErrorResponseWithMessage> {
+    let saved = SavedList {
+        batches: list.batches(),
+        edits: list.edits(),
+    };
+    let json = serde_json::to_string(&saved).map_err(|e| e.to_string())?;
+    set_key_value(&state.db, GROCERY_LIST_KEY, &json)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(GenericResponse {
+        data: grocery_list_response(list),
+    })
+}
+
+fn grocery_list_response(list: &GroceryList) -> GroceryListResponse {
+    GroceryListResponse {
+        items: format_items(list.items(), &Default::default()),
+        recipe_ids: list
+            .batches()
+            .iter()
+            .filter_map(|batch| batch.source.parse().ok())
+            .collect(),
+    }
+}
+
+#[tauri::command]
+pub async fn api_grocery_list(
+    state: State<'_, AppState>,
+) -> Result<GenericResponse<GroceryListResponse>, ErrorResponseWithMessage> {
+    let list = load_grocery_list(&state).await?;
+    Ok(GenericResponse {
+        data: grocery_list_response(&list),
+    })
+}
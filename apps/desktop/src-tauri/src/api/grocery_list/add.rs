@@ -0,0 +1,32 @@
+use tauri::State;
+
+use crate::{
+    api::{
+        grocery_list::{load_grocery_list, save_grocery_list, GroceryListResponse},
+        ErrorResponseWithMessage, GenericResponse,
+    },
+    crud::recipe::get_recipe,
+    AppState,
+};
+
+use groceryify::ingredient::Ingredient;
+
+/// Adds a recipe's ingredients to the saved grocery list, replacing any it added before.
+#[tauri::command]
+pub async fn api_grocery_list_add(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<GenericResponse<GroceryListResponse>, ErrorResponseWithMessage> {
+    let _saving = state.grocery_list.lock().await;
+    let mut list = load_grocery_list(&state).await?;
+    let recipe = get_recipe(state.clone(), id)
+        .await
+        .map_err(|e| e.to_string())?;
+    let ingredients = recipe
+        .ingredients
+        .iter()
+        .map(Ingredient::from)
+        .collect::<Vec<_>>();
+    list.add(&id.to_string(), &ingredients);
+    save_grocery_list(&state, &list).await
+}
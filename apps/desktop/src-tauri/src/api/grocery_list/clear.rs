@@ -0,0 +1,20 @@
+use tauri::State;
+
+use crate::{
+    api::{
+        grocery_list::{load_grocery_list, save_grocery_list, GroceryListResponse},
+        ErrorResponseWithMessage, GenericResponse,
+    },
+    AppState,
+};
+
+/// Empties the saved grocery list, forgetting its recipes and edits.
+#[tauri::command]
+pub async fn api_grocery_list_clear(
+    state: State<'_, AppState>,
+) -> Result<GenericResponse<GroceryListResponse>, ErrorResponseWithMessage> {
+    let _saving = state.grocery_list.lock().await;
+    let mut list = load_grocery_list(&state).await?;
+    list.clear();
+    save_grocery_list(&state, &list).await
+}
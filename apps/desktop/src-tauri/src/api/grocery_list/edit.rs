@@ -0,0 +1,31 @@
+use tauri::State;
+
+use crate::{
+    api::{
+        grocery_list::{load_grocery_list, save_grocery_list, GroceryListResponse},
+        parse_flag, ErrorResponseWithMessage, GenericResponse,
+    },
+    AppState,
+};
+
+/// Checks an item of the saved grocery list off or takes it off the list, or undoes either.
+/// Items are named by their `key`, and the edit is kept as recipes are added and removed.
+#[tauri::command]
+pub async fn api_grocery_list_edit(
+    state: State<'_, AppState>,
+    key: String,
+    checked: Option<String>,
+    removed: Option<String>,
+) -> Result<GenericResponse<GroceryListResponse>, ErrorResponseWithMessage> {
+    let checked = parse_flag("checked", checked)?;
+    let removed = parse_flag("removed", removed)?;
+    let _saving = state.grocery_list.lock().await;
+    let mut list = load_grocery_list(&state).await?;
+    if let Some(checked) = checked {
+        list.set_checked(&key, checked);
+    }
+    if let Some(removed) = removed {
+        list.set_removed(&key, removed);
+    }
+    save_grocery_list(&state, &list).await
+}
@@ -0,0 +1,25 @@
+use tauri::State;
+
+use crate::{
+    api::{
+        grocery_list::{load_grocery_list, save_grocery_list, GroceryListResponse},
+        ErrorResponseWithMessage, GenericResponse,
+    },
+    AppState,
+};
+
+/// Removes a recipe's ingredients from the saved grocery list.
+#[tauri::command]
+pub async fn api_grocery_list_remove(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<GenericResponse<GroceryListResponse>, ErrorResponseWithMessage> {
+    let _saving = state.grocery_list.lock().await;
+    let mut list = load_grocery_list(&state).await?;
+    if !list.remove(&id.to_string()) {
+        return Err(ErrorResponseWithMessage::new(
+            "Recipe is not on the grocery list".to_string(),
+        ));
+    }
+    save_grocery_list(&state, &list).await
+}
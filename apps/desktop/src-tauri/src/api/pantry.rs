@@ -0,0 +1,22 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::pantry::get_pantry_items,
+    types::response_bodies::PantryItem,
+    AppState,
+};
+
+pub mod delete;
+pub mod new;
+pub mod update;
+
+#[tauri::command]
+pub async fn api_pantry(
+    state: State<'_, AppState>,
+) -> Result<GenericResponse<Vec<PantryItem>>, ErrorResponse> {
+    let data = get_pantry_items(&state.db)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    Ok(GenericResponse { data })
+}
@@ -0,0 +1,27 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse, SuccessResponse},
+    crud::pantry::delete_pantry_item,
+    types::response_bodies::PantryItem,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_pantry_delete(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<GenericResponse<SuccessResponse>, ErrorResponse> {
+    let item = PantryItem {
+        id: Some(id),
+        name: String::new(),
+        amount: 0.0,
+        unit: String::new(),
+    };
+    delete_pantry_item(&state.db, &item)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    Ok(GenericResponse {
+        data: SuccessResponse::new("Pantry item deleted".to_string()),
+    })
+}
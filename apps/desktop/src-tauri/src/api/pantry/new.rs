@@ -0,0 +1,28 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::pantry::create_pantry_item,
+    types::response_bodies::PantryItem,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_pantry_new(
+    state: State<'_, AppState>,
+    name: String,
+    amount: f64,
+    unit: String,
+) -> Result<GenericResponse<PantryItem>, ErrorResponse> {
+    let mut item = PantryItem {
+        id: None,
+        name,
+        amount,
+        unit,
+    };
+    let id = create_pantry_item(&state.db, &item)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    item.id = Some(id);
+    Ok(GenericResponse { data: item })
+}
@@ -0,0 +1,28 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::pantry::update_pantry_item,
+    types::response_bodies::PantryItem,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_pantry_update(
+    state: State<'_, AppState>,
+    id: i64,
+    name: String,
+    amount: f64,
+    unit: String,
+) -> Result<GenericResponse<PantryItem>, ErrorResponse> {
+    let item = PantryItem {
+        id: Some(id),
+        name,
+        amount,
+        unit,
+    };
+    update_pantry_item(&state.db, &item)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    Ok(GenericResponse { data: item })
+}
@@ -0,0 +1,22 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::prices::get_prices,
+    types::response_bodies::PriceBookEntry,
+    AppState,
+};
+
+pub mod delete;
+pub mod new;
+pub mod update;
+
+#[tauri::command]
+pub async fn api_prices(
+    state: State<'_, AppState>,
+) -> Result<GenericResponse<Vec<PriceBookEntry>>, ErrorResponse> {
+    let data = get_prices(&state.db)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    Ok(GenericResponse { data })
+}
@@ -0,0 +1,28 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse, SuccessResponse},
+    crud::prices::delete_price,
+    types::response_bodies::PriceBookEntry,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_prices_delete(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<GenericResponse<SuccessResponse>, ErrorResponse> {
+    let entry = PriceBookEntry {
+        id: Some(id),
+        name: String::new(),
+        price: 0.0,
+        amount: 0.0,
+        unit: String::new(),
+    };
+    delete_price(&state.db, &entry)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    Ok(GenericResponse {
+        data: SuccessResponse::new("Price deleted".to_string()),
+    })
+}
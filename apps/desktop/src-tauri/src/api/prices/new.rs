@@ -0,0 +1,30 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::prices::create_price,
+    types::response_bodies::PriceBookEntry,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_prices_new(
+    state: State<'_, AppState>,
+    name: String,
+    price: f64,
+    amount: f64,
+    unit: String,
+) -> Result<GenericResponse<PriceBookEntry>, ErrorResponse> {
+    let mut entry = PriceBookEntry {
+        id: None,
+        name,
+        price,
+        amount,
+        unit,
+    };
+    let id = create_price(&state.db, &entry)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    entry.id = Some(id);
+    Ok(GenericResponse { data: entry })
+}
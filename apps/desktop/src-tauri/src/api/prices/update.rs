@@ -0,0 +1,30 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::prices::update_price,
+    types::response_bodies::PriceBookEntry,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_prices_update(
+    state: State<'_, AppState>,
+    id: i64,
+    name: String,
+    price: f64,
+    amount: f64,
+    unit: String,
+) -> Result<GenericResponse<PriceBookEntry>, ErrorResponse> {
+    let entry = PriceBookEntry {
+        id: Some(id),
+        name,
+        price,
+        amount,
+        unit,
+    };
+    update_price(&state.db, &entry)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    Ok(GenericResponse { data: entry })
+}
@@ -8,8 +8,11 @@ use crate::{
     AppState,
 };
 
+pub mod cost;
 pub mod delete;
 pub mod new;
+pub mod nutrition;
+pub mod scaled;
 pub mod schedules;
 pub mod update;
 
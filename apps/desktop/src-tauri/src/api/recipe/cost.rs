@@ -0,0 +1,57 @@
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::{prices::get_prices, recipe::get_recipe},
+    AppState,
+};
+
+use groceryify::{cost::Price, ingredient::Ingredient, options::MergeOptions};
+
+/// What a recipe is estimated to cost, from the price book.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeCost {
+    pub total: f64,
+    pub per_serving: Option<f64>,
+    /// Ingredients without a price, which the estimate leaves out.
+    pub unpriced: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn api_recipe_cost(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<GenericResponse<RecipeCost>, ErrorResponse> {
+    let prices = get_prices(&state.db)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?
+        .into_iter()
+        .map(Price::from)
+        .collect::<Vec<Price>>();
+    let merger = state
+        .merger(MergeOptions::default())
+        .await
+        .map_err(ErrorResponse::new)?;
+    let recipe = get_recipe(state, id)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+
+    let ingredients = recipe
+        .ingredients
+        .iter()
+        .map(Ingredient::from)
+        .collect::<Vec<_>>();
+    let items = merger.merge(&ingredients);
+    let estimate = merger.estimate_cost(&items, &prices);
+    let unpriced = estimate.unpriced().map(String::from).collect();
+
+    Ok(GenericResponse {
+        data: RecipeCost {
+            total: estimate.total,
+            per_serving: (recipe.servings > 0).then(|| estimate.total / recipe.servings as f64),
+            unpriced,
+        },
+    })
+}
@@ -0,0 +1,34 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::recipe::get_recipe,
+    AppState,
+};
+
+use groceryify::{ingredient::Ingredient, nutrition::NutritionReport, options::MergeOptions};
+
+#[tauri::command]
+pub async fn api_recipe_nutrition(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<GenericResponse<NutritionReport>, ErrorResponse> {
+    let merger = state
+        .merger(MergeOptions::default())
+        .await
+        .map_err(ErrorResponse::new)?;
+    let recipe = get_recipe(state, id)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+
+    let ingredients = recipe
+        .ingredients
+        .iter()
+        .map(Ingredient::from)
+        .collect::<Vec<_>>();
+    // Recipes without a yield are reported as a whole
+    let servings = (recipe.servings > 0).then_some(recipe.servings as f64);
+    Ok(GenericResponse {
+        data: merger.estimate_nutrition(&ingredients, servings),
+    })
+}
@@ -0,0 +1,54 @@
+use tauri::State;
+
+use crate::{
+    api::{recipe::RecipeResponse, ErrorResponse, GenericResponse},
+    crud::recipe::get_recipe,
+    types::response_bodies::Ingredient,
+    AppState,
+};
+
+use groceryify::{scale_ingredients, Scale};
+
+#[tauri::command]
+pub async fn api_recipe_scaled(
+    state: State<'_, AppState>,
+    id: i64,
+    servings: String,
+) -> Result<GenericResponse<RecipeResponse>, ErrorResponse> {
+    let servings = servings
+        .parse::<i64>()
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    let mut recipe = get_recipe(state, id)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+
+    let ingredients = recipe
+        .ingredients
+        .iter()
+        .map(groceryify::ingredient::Ingredient::from)
+        .collect::<Vec<_>>();
+    let scale = Scale::Servings {
+        original: recipe.servings as f64,
+        target: servings as f64,
+    };
+    let scaled =
+        scale_ingredients(&ingredients, scale).map_err(|e| ErrorResponse::new(e.to_string()))?;
+
+    // Scaling keeps the order, so each scaled ingredient lines up with its stored row
+    recipe.ingredients = recipe
+        .ingredients
+        .into_iter()
+        .zip(scaled)
+        .map(|(original, scaled)| Ingredient {
+            amount: scaled.amount,
+            unit: scaled.unit,
+            amount_max: scaled.amount_max,
+            ..original
+        })
+        .collect();
+    recipe.servings = servings;
+
+    Ok(GenericResponse {
+        data: RecipeResponse { recipe },
+    })
+}
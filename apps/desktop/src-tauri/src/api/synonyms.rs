@@ -0,0 +1,22 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::synonyms::get_ingredient_synonyms,
+    types::response_bodies::IngredientSynonym,
+    AppState,
+};
+
+pub mod delete;
+pub mod new;
+pub mod update;
+
+#[tauri::command]
+pub async fn api_synonyms(
+    state: State<'_, AppState>,
+) -> Result<GenericResponse<Vec<IngredientSynonym>>, ErrorResponse> {
+    let data = get_ingredient_synonyms(&state.db)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    Ok(GenericResponse { data })
+}
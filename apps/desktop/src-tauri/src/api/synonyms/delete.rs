@@ -0,0 +1,27 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse, SuccessResponse},
+    crud::synonyms::delete_ingredient_synonym,
+    types::response_bodies::IngredientSynonym,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_synonyms_delete(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<GenericResponse<SuccessResponse>, ErrorResponse> {
+    let synonym = IngredientSynonym {
+        id: Some(id),
+        name: String::new(),
+        canonical_name: String::new(),
+    };
+    delete_ingredient_synonym(&state.db, &synonym)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    state.forget_mergers().await;
+    Ok(GenericResponse {
+        data: SuccessResponse::new("Ingredient synonym deleted".to_string()),
+    })
+}
@@ -0,0 +1,27 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::synonyms::create_ingredient_synonym,
+    types::response_bodies::IngredientSynonym,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_synonyms_new(
+    state: State<'_, AppState>,
+    name: String,
+    canonical_name: String,
+) -> Result<GenericResponse<IngredientSynonym>, ErrorResponse> {
+    let mut synonym = IngredientSynonym {
+        id: None,
+        name,
+        canonical_name,
+    };
+    let id = create_ingredient_synonym(&state.db, &synonym)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    state.forget_mergers().await;
+    synonym.id = Some(id);
+    Ok(GenericResponse { data: synonym })
+}
@@ -0,0 +1,27 @@
+use tauri::State;
+
+use crate::{
+    api::{ErrorResponse, GenericResponse},
+    crud::synonyms::update_ingredient_synonym,
+    types::response_bodies::IngredientSynonym,
+    AppState,
+};
+
+#[tauri::command]
+pub async fn api_synonyms_update(
+    state: State<'_, AppState>,
+    id: i64,
+    name: String,
+    canonical_name: String,
+) -> Result<GenericResponse<IngredientSynonym>, ErrorResponse> {
+    let synonym = IngredientSynonym {
+        id: Some(id),
+        name,
+        canonical_name,
+    };
+    update_ingredient_synonym(&state.db, &synonym)
+        .await
+        .map_err(|e| ErrorResponse::new(e.to_string()))?;
+    state.forget_mergers().await;
+    Ok(GenericResponse { data: synonym })
+}
@@ -1,11 +1,15 @@
 use tauri::AppHandle;
 
 pub mod cloud_id;
+pub mod key_value;
+pub mod pantry;
+pub mod prices;
 pub mod recipe;
 pub mod recipe_data;
 pub mod recipes;
 pub mod schedule_cloud_id;
 pub mod schedules;
+pub mod synonyms;
 pub mod tag;
 pub mod tag_cloud_id;
 pub mod tags;
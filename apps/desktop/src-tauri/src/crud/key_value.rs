@@ -0,0 +1,52 @@
+use sqlx::{Pool, Sqlite, Transaction};
+
+/// Reads a value from the key-value store in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `key` - The key the value was stored under.
+///
+/// # Returns
+///
+/// A `Result` containing the value, or `None` if nothing is stored under the key.
+pub async fn get_key_value(
+    db: &Pool<Sqlite>,
+    key: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let row = sqlx::query_file!("db/get_key_value.sql", key)
+            .fetch_optional(&mut **tx)
+            .await?;
+        Ok::<Option<String>, Box<dyn std::error::Error>>(row.and_then(|r| r.value))
+    }))
+}
+
+/// Stores a value in the key-value store in a transaction, replacing any stored under the key.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `key` - The key to store the value under.
+/// * `value` - The value to store.
+///
+/// # Returns
+///
+/// * `Ok(())` - The value was stored.
+/// * `Err` - An error occurred while storing the value.
+pub async fn set_key_value(
+    db: &Pool<Sqlite>,
+    key: &str,
+    value: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_tx_with_error!(db, async |tx: &mut Transaction<'_, Sqlite>| {
+        sqlx::query_file!("db/set_key_value.sql", key, value)
+            .execute(&mut **tx)
+            .await?;
+        Ok::<(), Box<dyn std::error::Error>>(())
+    });
+    Ok(())
+}
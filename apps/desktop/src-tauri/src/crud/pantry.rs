@@ -0,0 +1,187 @@
+use sqlx::{Pool, Sqlite, Transaction};
+
+use crate::{
+    crud::{BatchReadable, Creatable, Deletable, Updatable},
+    types::response_bodies::PantryItem,
+};
+
+/// Wraps the `get_pantry_items` query in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+///
+/// # Returns
+///
+/// A `Result` containing every pantry item if successful, or an error if one occurred.
+pub async fn get_pantry_items(
+    db: &Pool<Sqlite>,
+) -> Result<Vec<PantryItem>, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let items = Vec::<PantryItem>::read(tx).await?;
+        Ok::<Vec<PantryItem>, Box<dyn std::error::Error>>(items)
+    }))
+}
+
+/// Wraps the create operation for a pantry item in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `item` - The pantry item to create.
+///
+/// # Returns
+///
+/// A `Result` containing the ID of the new pantry item, or an error if one occurred.
+pub async fn create_pantry_item(
+    db: &Pool<Sqlite>,
+    item: &PantryItem,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let id = item.create(tx).await?;
+        Ok::<i64, Box<dyn std::error::Error>>(id)
+    }))
+}
+
+/// Wraps the update operation for a pantry item in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `item` - The pantry item to update, identified by its ID.
+///
+/// # Returns
+///
+/// A `Result` containing the ID of the updated pantry item, or an error if one occurred.
+pub async fn update_pantry_item(
+    db: &Pool<Sqlite>,
+    item: &PantryItem,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let id = item.update(tx).await?;
+        Ok::<i64, Box<dyn std::error::Error>>(id)
+    }))
+}
+
+/// Wraps the delete operation for a pantry item in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `item` - The pantry item to delete, identified by its ID.
+///
+/// # Returns
+///
+/// * `Ok(())` - The pantry item was successfully deleted.
+/// * `Err` - An error occurred while deleting the pantry item.
+pub async fn delete_pantry_item(
+    db: &Pool<Sqlite>,
+    item: &PantryItem,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_tx_with_error!(db, async |tx: &mut Transaction<'_, Sqlite>| {
+        item.delete(tx).await?;
+        Ok::<(), Box<dyn std::error::Error>>(())
+    });
+    Ok(())
+}
+
+impl BatchReadable for Vec<PantryItem> {
+    /// Get all pantry items from the database, ordered by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - A mutable reference to the SQLite transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a vector of `PantryItem` if successful, or an error if one occurred.
+    async fn read(tx: &mut Transaction<'_, Sqlite>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(sqlx::query_file_as!(PantryItem, "db/get_pantry_items.sql")
+            .fetch_all(&mut **tx)
+            .await?)
+    }
+}
+
+impl Creatable for PantryItem {
+    /// Inserts a new pantry item into the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the insert.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the inserted pantry item.
+    async fn create(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let row = sqlx::query_file!(
+            "db/insert_pantry_item.sql",
+            self.name,
+            self.amount,
+            self.unit
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(row.id)
+    }
+}
+
+impl Updatable for PantryItem {
+    /// Updates the pantry item's name, amount and unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the update.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the updated pantry item.
+    async fn update(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let row = sqlx::query_file!(
+            "db/update_pantry_item.sql",
+            self.name,
+            self.amount,
+            self.unit,
+            self.id
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(row.id)
+    }
+}
+
+impl Deletable for PantryItem {
+    /// Deletes the pantry item from the database by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the delete operation.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The pantry item was successfully deleted.
+    /// * `Err` - An error occurred while deleting the pantry item.
+    async fn delete(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query_file!("db/delete_pantry_item.sql", self.id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use sqlx::{Pool, Sqlite, Transaction};
+
+use crate::{
+    crud::{BatchReadable, Creatable, Deletable, Updatable},
+    types::response_bodies::PriceBookEntry,
+};
+
+/// Wraps the `get_prices` query in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+///
+/// # Returns
+///
+/// A `Result` containing every price book entry if successful, or an error if one occurred.
+pub async fn get_prices(
+    db: &Pool<Sqlite>,
+) -> Result<Vec<PriceBookEntry>, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let entries = Vec::<PriceBookEntry>::read(tx).await?;
+        Ok::<Vec<PriceBookEntry>, Box<dyn std::error::Error>>(entries)
+    }))
+}
+
+/// Wraps the create operation for a price book entry in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `entry` - The price book entry to create.
+///
+/// # Returns
+///
+/// A `Result` containing the ID of the new price book entry, or an error if one occurred.
+pub async fn create_price(
+    db: &Pool<Sqlite>,
+    entry: &PriceBookEntry,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let id = entry.create(tx).await?;
+        Ok::<i64, Box<dyn std::error::Error>>(id)
+    }))
+}
+
+/// Wraps the update operation for a price book entry in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `entry` - The price book entry to update, identified by its ID.
+///
+/// # Returns
+///
+/// A `Result` containing the ID of the updated price book entry, or an error if one occurred.
+pub async fn update_price(
+    db: &Pool<Sqlite>,
+    entry: &PriceBookEntry,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let id = entry.update(tx).await?;
+        Ok::<i64, Box<dyn std::error::Error>>(id)
+    }))
+}
+
+/// Wraps the delete operation for a price book entry in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `entry` - The price book entry to delete, identified by its ID.
+///
+/// # Returns
+///
+/// * `Ok(())` - The price book entry was successfully deleted.
+/// * `Err` - An error occurred while deleting the price book entry.
+pub async fn delete_price(
+    db: &Pool<Sqlite>,
+    entry: &PriceBookEntry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_tx_with_error!(db, async |tx: &mut Transaction<'_, Sqlite>| {
+        entry.delete(tx).await?;
+        Ok::<(), Box<dyn std::error::Error>>(())
+    });
+    Ok(())
+}
+
+impl BatchReadable for Vec<PriceBookEntry> {
+    /// Get all price book entries from the database, ordered by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - A mutable reference to the SQLite transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a vector of `PriceBookEntry` if successful, or an error if one occurred.
+    async fn read(tx: &mut Transaction<'_, Sqlite>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(sqlx::query_file_as!(PriceBookEntry, "db/get_prices.sql")
+            .fetch_all(&mut **tx)
+            .await?)
+    }
+}
+
+impl Creatable for PriceBookEntry {
+    /// Inserts a new price book entry into the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the insert.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the inserted price book entry.
+    async fn create(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let row = sqlx::query_file!(
+            "db/insert_price.sql",
+            self.name,
+            self.price,
+            self.amount,
+            self.unit
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(row.id)
+    }
+}
+
+impl Updatable for PriceBookEntry {
+    /// Updates the price book entry's name, price, amount and unit.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the update.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the updated price book entry.
+    async fn update(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let row = sqlx::query_file!(
+            "db/update_price.sql",
+            self.name,
+            self.price,
+            self.amount,
+            self.unit,
+            self.id
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(row.id)
+    }
+}
+
+impl Deletable for PriceBookEntry {
+    /// Deletes the price book entry from the database by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the delete operation.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The price book entry was successfully deleted.
+    /// * `Err` - An error occurred while deleting the price book entry.
+    async fn delete(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query_file!("db/delete_price.sql", self.id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     crud::{BatchReadableWith, Creatable, Deletable, ReadableWith},
     types::{
         db_params::{DateFilter, ImagesLibPath},
-        raw_db::RecipeContext,
+        raw_db::{RawGroceryIngredient, RecipeContext},
         response_bodies::{Direction, Ingredient, RecipeTag},
     },
 };
@@ -14,18 +14,20 @@ pub async fn get_groceries(
     db: &Pool<Sqlite>,
     start_date: NaiveDate,
     end_date: NaiveDate,
-) -> Result<Vec<Ingredient>, Box<dyn std::error::Error>> {
+) -> Result<Vec<RawGroceryIngredient>, Box<dyn std::error::Error>> {
     Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
         '_,
         Sqlite,
-    >| Vec::<Ingredient>::read_with(
-        tx,
-        DateFilter {
-            start_date: &start_date,
-            end_date: &end_date,
-        }
-    )
-    .await))
+    >| {
+        Vec::<RawGroceryIngredient>::read_with(
+            tx,
+            DateFilter {
+                start_date: &start_date,
+                end_date: &end_date,
+            },
+        )
+        .await
+    }))
 }
 
 impl Creatable for RecipeContext {
@@ -51,6 +53,8 @@ impl Creatable for RecipeContext {
                 ingredient.name,
                 ingredient.amount,
                 ingredient.unit,
+                ingredient.amount_max,
+                ingredient.to_taste,
                 sequence
             )
             .fetch_one(&mut **tx)
@@ -145,17 +149,21 @@ impl ReadableWith<ImagesLibPath<'_>> for RecipeContext {
     }
 }
 
-impl BatchReadableWith<DateFilter<'_>> for Vec<Ingredient> {
+impl BatchReadableWith<DateFilter<'_>> for Vec<RawGroceryIngredient> {
     async fn read_with(
         tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
         addl_params: DateFilter<'_>,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         let start_str = addl_params.start_date.to_string();
         let end_str = addl_params.end_date.to_string();
-        let groceries =
-            sqlx::query_file_as!(Ingredient, "db/get_groceries.sql", start_str, end_str)
-                .fetch_all(&mut **tx)
-                .await;
+        let groceries = sqlx::query_file_as!(
+            RawGroceryIngredient,
+            "db/get_groceries.sql",
+            start_str,
+            end_str
+        )
+        .fetch_all(&mut **tx)
+        .await;
         Ok(groceries?)
     }
 }
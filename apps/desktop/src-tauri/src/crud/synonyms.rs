@@ -0,0 +1,187 @@
+use sqlx::{Pool, Sqlite, Transaction};
+
+use crate::{
+    crud::{BatchReadable, Creatable, Deletable, Updatable},
+    types::response_bodies::IngredientSynonym,
+};
+
+/// Wraps the `get_ingredient_synonyms` query in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+///
+/// # Returns
+///
+/// A `Result` containing every ingredient synonym if successful, or an error if one occurred.
+pub async fn get_ingredient_synonyms(
+    db: &Pool<Sqlite>,
+) -> Result<Vec<IngredientSynonym>, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let synonyms = Vec::<IngredientSynonym>::read(tx).await?;
+        Ok::<Vec<IngredientSynonym>, Box<dyn std::error::Error>>(synonyms)
+    }))
+}
+
+/// Wraps the create operation for an ingredient synonym in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `synonym` - The ingredient synonym to create.
+///
+/// # Returns
+///
+/// A `Result` containing the ID of the new ingredient synonym, or an error if one occurred.
+pub async fn create_ingredient_synonym(
+    db: &Pool<Sqlite>,
+    synonym: &IngredientSynonym,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let id = synonym.create(tx).await?;
+        Ok::<i64, Box<dyn std::error::Error>>(id)
+    }))
+}
+
+/// Wraps the update operation for an ingredient synonym in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `synonym` - The ingredient synonym to update, identified by its ID.
+///
+/// # Returns
+///
+/// A `Result` containing the ID of the updated ingredient synonym, or an error if one occurred.
+pub async fn update_ingredient_synonym(
+    db: &Pool<Sqlite>,
+    synonym: &IngredientSynonym,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    Ok(run_tx_with_error!(db, async |tx: &mut Transaction<
+        '_,
+        Sqlite,
+    >| {
+        let id = synonym.update(tx).await?;
+        Ok::<i64, Box<dyn std::error::Error>>(id)
+    }))
+}
+
+/// Wraps the delete operation for an ingredient synonym in a transaction.
+///
+/// # Arguments
+///
+/// * `db` - A reference to the SQLite database pool.
+/// * `synonym` - The ingredient synonym to delete, identified by its ID.
+///
+/// # Returns
+///
+/// * `Ok(())` - The ingredient synonym was successfully deleted.
+/// * `Err` - An error occurred while deleting the ingredient synonym.
+pub async fn delete_ingredient_synonym(
+    db: &Pool<Sqlite>,
+    synonym: &IngredientSynonym,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_tx_with_error!(db, async |tx: &mut Transaction<'_, Sqlite>| {
+        synonym.delete(tx).await?;
+        Ok::<(), Box<dyn std::error::Error>>(())
+    });
+    Ok(())
+}
+
+impl BatchReadable for Vec<IngredientSynonym> {
+    /// Get all ingredient synonyms from the database, ordered by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - A mutable reference to the SQLite transaction.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a vector of `IngredientSynonym` if successful, or an error if one occurred.
+    async fn read(tx: &mut Transaction<'_, Sqlite>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(
+            sqlx::query_file_as!(IngredientSynonym, "db/get_ingredient_synonyms.sql")
+                .fetch_all(&mut **tx)
+                .await?,
+        )
+    }
+}
+
+impl Creatable for IngredientSynonym {
+    /// Inserts a new ingredient synonym into the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the insert.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the inserted ingredient synonym.
+    async fn create(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let row = sqlx::query_file!(
+            "db/insert_ingredient_synonym.sql",
+            self.name,
+            self.canonical_name
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(row.id)
+    }
+}
+
+impl Updatable for IngredientSynonym {
+    /// Updates the ingredient synonym's name and canonical name.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the update.
+    ///
+    /// # Returns
+    ///
+    /// The ID of the updated ingredient synonym.
+    async fn update(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<i64, Box<dyn std::error::Error>> {
+        let row = sqlx::query_file!(
+            "db/update_ingredient_synonym.sql",
+            self.name,
+            self.canonical_name,
+            self.id
+        )
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(row.id)
+    }
+}
+
+impl Deletable for IngredientSynonym {
+    /// Deletes the ingredient synonym from the database by ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to use for the delete operation.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - The ingredient synonym was successfully deleted.
+    /// * `Err` - An error occurred while deleting the ingredient synonym.
+    async fn delete(
+        &self,
+        tx: &mut Transaction<'_, Sqlite>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query_file!("db/delete_ingredient_synonym.sql", self.id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+}
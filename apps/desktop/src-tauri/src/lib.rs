@@ -12,9 +12,11 @@ mod types;
 
 use crate::{
     api::{auth::check_auth::get_username, sync_data::sync_all},
+    crud::synonyms::get_ingredient_synonyms,
     database::create::setup_db,
 };
-use std::path::PathBuf;
+use groceryify::{options::MergeOptions, Merger};
+use std::{path::PathBuf, sync::Arc};
 use tauri::{
     generate_handler, Emitter, LogicalPosition, Manager, TitleBarStyle, WebviewUrl,
     WebviewWindowBuilder,
@@ -29,6 +31,77 @@ struct AppState {
     db: database::Db,
     access_token: Mutex<Option<String>>,
     images_lib_path: PathBuf,
+    mergers: Mutex<Mergers>,
+    /// Held by the commands that change the saved grocery list, from loading it until it is
+    /// saved again.
+    grocery_list: Mutex<()>,
+}
+
+/// Mergers built with the user's ingredient synonyms, one per set of options used, kept between
+/// commands because building one is slow.
+#[derive(Default)]
+struct Mergers {
+    /// Counts the changes to the synonyms, so a merger built while they changed isn't kept.
+    generation: u64,
+    kept: Vec<Arc<Merger>>,
+}
+
+impl Mergers {
+    fn find(&self, options: &MergeOptions) -> Option<Arc<Merger>> {
+        self.kept
+            .iter()
+            .find(|merger| merger.options() == options)
+            .cloned()
+    }
+}
+
+impl AppState {
+    /// A merger for `options` plus the user's ingredient synonyms, only built the first time
+    /// these options are used since the synonyms last changed.
+    async fn merger(&self, options: MergeOptions) -> Result<Arc<Merger>, String> {
+        let (generation, synonyms) = {
+            let mergers = self.mergers.lock().await;
+            // The kept mergers are cleared whenever the synonyms change, so theirs are current
+            let synonyms = mergers
+                .kept
+                .first()
+                .map(|merger| merger.options().synonyms.clone());
+            (mergers.generation, synonyms)
+        };
+        let synonyms = match synonyms {
+            Some(synonyms) => synonyms,
+            None => get_ingredient_synonyms(&self.db)
+                .await
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|s| (s.name, s.canonical_name))
+                .collect(),
+        };
+        let options = MergeOptions {
+            synonyms,
+            ..options
+        };
+        if let Some(merger) = self.mergers.lock().await.find(&options) {
+            return Ok(merger);
+        }
+        // Built without holding the lock, so commands don't wait on each other's tables
+        let merger = Arc::new(Merger::new(options));
+        let mut mergers = self.mergers.lock().await;
+        if mergers.generation == generation {
+            if let Some(merger) = mergers.find(merger.options()) {
+                return Ok(merger);
+            }
+            mergers.kept.push(merger.clone());
+        }
+        Ok(merger)
+    }
+
+    /// Drops the kept mergers, for when the user's ingredient synonyms change.
+    async fn forget_mergers(&self) {
+        let mut mergers = self.mergers.lock().await;
+        mergers.generation += 1;
+        mergers.kept.clear();
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -45,6 +118,9 @@ pub fn run() {
             api::open_url,
             api::recipe::new::api_recipe_new,
             api::recipe::api_recipe,
+            api::recipe::scaled::api_recipe_scaled,
+            api::recipe::cost::api_recipe_cost,
+            api::recipe::nutrition::api_recipe_nutrition,
             api::recipe::delete::api_recipe_delete,
             api::recipe::update::api_recipe_update,
             api::recipe::schedules::api_recipe_schedules,
@@ -57,7 +133,25 @@ pub fn run() {
             api::sync_data::sync_data,
             api::tags::api_tags,
             api::tags::delete::api_tags_delete,
-            api::groceries::api_groceries
+            api::groceries::api_groceries,
+            api::groceries::save::api_groceries_save,
+            api::grocery_list::api_grocery_list,
+            api::grocery_list::add::api_grocery_list_add,
+            api::grocery_list::remove::api_grocery_list_remove,
+            api::grocery_list::edit::api_grocery_list_edit,
+            api::grocery_list::clear::api_grocery_list_clear,
+            api::pantry::api_pantry,
+            api::pantry::new::api_pantry_new,
+            api::pantry::update::api_pantry_update,
+            api::pantry::delete::api_pantry_delete,
+            api::prices::api_prices,
+            api::prices::new::api_prices_new,
+            api::prices::update::api_prices_update,
+            api::prices::delete::api_prices_delete,
+            api::synonyms::api_synonyms,
+            api::synonyms::new::api_synonyms_new,
+            api::synonyms::update::api_synonyms_update,
+            api::synonyms::delete::api_synonyms_delete
         ])
         .setup(|app| {
             let db = tauri::async_runtime::block_on(async { setup_db(app).await });
@@ -65,6 +159,8 @@ pub fn run() {
                 db,
                 access_token: Mutex::new(None),
                 images_lib_path: app.path().app_data_dir().unwrap().join("images"),
+                mergers: Mutex::default(),
+                grocery_list: Mutex::new(()),
             });
 
             let win_builder = WebviewWindowBuilder::new(app, "main", WebviewUrl::default())
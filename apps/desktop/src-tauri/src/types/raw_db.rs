@@ -546,6 +546,33 @@ impl RawSchedule {
     }
 }
 
+/// Represents an ingredient of a recipe scheduled in a date range, with the schedule it came from
+pub struct RawGroceryIngredient {
+    pub id: Option<i64>,
+    pub amount: f64,
+    pub unit: String,
+    pub amount_max: Option<f64>,
+    pub to_taste: bool,
+    pub name: String,
+    pub recipe_id: i64,
+    pub recipe_title: String,
+    pub scheduled_date: Option<NaiveDate>,
+}
+
+impl From<&RawGroceryIngredient> for groceryify::ingredient::Ingredient {
+    fn from(ingredient: &RawGroceryIngredient) -> Self {
+        groceryify::ingredient::Ingredient {
+            amount_max: ingredient.amount_max,
+            to_taste: ingredient.to_taste,
+            ..groceryify::ingredient::Ingredient::new(
+                &ingredient.name,
+                ingredient.amount,
+                &ingredient.unit,
+            )
+        }
+    }
+}
+
 pub struct RawScheduleWithDisplayInfo {
     pub id: i64,
     pub date: NaiveDate,
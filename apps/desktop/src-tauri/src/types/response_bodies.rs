@@ -1,5 +1,6 @@
 use crate::types::raw_db::RawRecipeCommon;
 use chrono::NaiveDate;
+use groceryify::{cost::Price, formatting::Formatted, grocery_item::GroceryItem};
 use serde::{Deserialize, Serialize, Serializer};
 
 fn serialize_date_with_time<S>(date: &NaiveDate, s: S) -> Result<S::Ok, S::Error>
@@ -12,13 +13,106 @@ where
 
 /// Represents an ingredient.
 #[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Ingredient {
     pub name: String,
     pub amount: f64,
     pub unit: String,
+    /// Upper end of a range such as "2-3 cloves", whose lower end is `amount`.
+    #[serde(default)]
+    pub amount_max: Option<f64>,
+    /// Set for ingredients without a measured amount, such as "salt to taste".
+    #[serde(default)]
+    pub to_taste: bool,
     pub id: Option<i64>,
 }
 
+impl From<&Ingredient> for groceryify::ingredient::Ingredient {
+    fn from(ingredient: &Ingredient) -> Self {
+        groceryify::ingredient::Ingredient {
+            amount_max: ingredient.amount_max,
+            to_taste: ingredient.to_taste,
+            ..groceryify::ingredient::Ingredient::new(
+                &ingredient.name,
+                ingredient.amount,
+                &ingredient.unit,
+            )
+        }
+    }
+}
+
+/// Represents a scheduled recipe that contributed to a grocery list line.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GrocerySource {
+    pub recipe_id: i64,
+    pub recipe_title: String,
+    pub scheduled_date: Option<NaiveDate>,
+    pub amount: f64,
+    pub unit: String,
+    pub amount_max: Option<f64>,
+    pub to_taste: bool,
+    /// The unit an ambiguous one such as "oz" was read as.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interpreted_as: Option<String>,
+}
+
+/// Represents a line of the grocery list, with the scheduled recipes it came from.
+#[derive(Serialize, Debug)]
+pub struct GroceryLine {
+    #[serde(flatten)]
+    pub item: Formatted<GroceryItem>,
+    pub recipes: Vec<GrocerySource>,
+    /// Why the ingredient couldn't be merged with its other lines, if it has any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<String>,
+    /// The packages to buy, e.g. "1 dozen", if the list was rounded to package sizes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buy: Option<String>,
+    /// The estimated cost, if the price book has a price for the ingredient.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+}
+
+/// Represents something already at home, subtracted from grocery lists.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PantryItem {
+    pub id: Option<i64>,
+    pub name: String,
+    pub amount: f64,
+    pub unit: String,
+}
+
+/// Represents what an amount of an ingredient costs, e.g. 3.49 per 1 lb, for estimating what a
+/// grocery list or recipe costs.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PriceBookEntry {
+    pub id: Option<i64>,
+    pub name: String,
+    pub price: f64,
+    pub amount: f64,
+    pub unit: String,
+}
+
+impl From<PriceBookEntry> for Price {
+    fn from(entry: PriceBookEntry) -> Self {
+        Price {
+            name: entry.name,
+            price: entry.price,
+            amount: entry.amount,
+            unit: entry.unit,
+        }
+    }
+}
+
+/// Represents another name for an ingredient, merged under its canonical name in grocery lists.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct IngredientSynonym {
+    pub id: Option<i64>,
+    pub name: String,
+    pub canonical_name: String,
+}
+
 /// Represents a direction.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Direction {
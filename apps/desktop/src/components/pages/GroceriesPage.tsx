@@ -2,6 +2,7 @@ import {
   Groceries,
   GroceryList,
   useGroceries,
+  useIsDark,
   useNotification
 } from 'cookycardz-shared';
 import { request } from '../../utils/fetchUtils';
@@ -9,22 +10,35 @@ import usePrinter from '../../hooks/usePrinter';
 
 export default function GroceriesPage() {
   const { addNotification } = useNotification();
+  const isDark = useIsDark();
 
-  const { groceries, handleGroceriesRequest } = useGroceries(
-    request,
-    addNotification
-  );
+  const {
+    groceries,
+    grouped,
+    totalCost,
+    handleGroceriesRequest,
+    handleSaveGroceries
+  } = useGroceries(request, addNotification);
 
   const handlePrint = usePrinter(
     addNotification,
-    <GroceryList groceries={groceries} />,
+    <GroceryList groceries={groceries} grouped={grouped} />,
     'Print Groceries'
   );
   return (
     <Groceries
       groceries={groceries}
+      grouped={grouped}
+      totalCost={totalCost}
       handleGroceriesRequest={handleGroceriesRequest}
       handlePrint={handlePrint}
+      handleSaveGroceries={handleSaveGroceries}
+      showUnitSystem
+      showGroupByCategory
+      showSubtractPantry
+      showRoundToPackages
+      showSave
+      isDark={isDark}
     />
   );
 }
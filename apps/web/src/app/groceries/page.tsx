@@ -16,7 +16,7 @@ const GroceriesPage = () => {
   useRequireAuth(request, router.replace);
   const { addNotification } = useNotification();
 
-  const { groceries, handleGroceriesRequest } = useGroceries(
+  const { groceries, grouped, handleGroceriesRequest } = useGroceries(
     request,
     addNotification
   );
@@ -30,6 +30,7 @@ const GroceriesPage = () => {
   return (
     <Groceries
       groceries={groceries}
+      grouped={grouped}
       handleGroceriesRequest={handleGroceriesRequest}
       handlePrint={handlePrint}
     />
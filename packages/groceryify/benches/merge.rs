@@ -0,0 +1,81 @@
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+use groceryify::{Merger, ingredient::Ingredient, merge_with_options, options::MergeOptions};
+
+const NAMES: &[&str] = &[
+    "all-purpose flour",
+    "finely chopped onions",
+    "garlic cloves, minced",
+    "unsalted butter (softened)",
+    "large eggs",
+    "whole milk",
+    "granulated sugar",
+    "fresh cilantro",
+    "green onions",
+    "olive oil",
+    "kosher salt",
+    "ground black pepper",
+    "diced tomatoes",
+    "chicken breasts",
+    "heavy cream",
+    "grated parmesan cheese",
+    "baking powder",
+    "vanilla extract",
+    "brown rice",
+    "red bell peppers",
+];
+
+const UNITS: &[(&str, f64)] = &[
+    ("cup", 1.5),
+    ("tbsp", 2.0),
+    ("tsp", 0.5),
+    ("g", 250.0),
+    ("oz", 8.0),
+    ("ml", 120.0),
+    ("lb", 1.0),
+    ("", 3.0),
+    ("clove", 2.0),
+    ("pinch", 1.0),
+];
+
+/// A month of meal plans, with names and units spread like a real recipe library.
+fn meal_plan(len: usize) -> Vec<Ingredient> {
+    (0..len)
+        .map(|i| {
+            let (unit, amount) = UNITS[(i * 7) % UNITS.len()];
+            Ingredient::new(NAMES[i % NAMES.len()], amount, unit)
+        })
+        .collect()
+}
+
+fn merge_benchmarks(c: &mut Criterion) {
+    let plan = meal_plan(2_400);
+    let options = MergeOptions::default();
+
+    c.bench_function("merge_with_options, meal plan", |b| {
+        b.iter(|| merge_with_options(black_box(&plan), &options))
+    });
+
+    let merger = Merger::new(options.clone());
+    c.bench_function("Merger::merge, meal plan", |b| {
+        b.iter(|| merger.merge(black_box(&plan)))
+    });
+
+    let single = [Ingredient::new("finely chopped onions", 2.0, "cup")];
+    c.bench_function("merge_with_options, one ingredient", |b| {
+        b.iter(|| merge_with_options(black_box(&single), &options))
+    });
+    c.bench_function("Merger::merge, one ingredient", |b| {
+        b.iter(|| merger.merge(black_box(&single)))
+    });
+
+    c.bench_function("Merger::new", |b| {
+        b.iter_batched(
+            || options.clone(),
+            |options| Merger::new(black_box(options)),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, merge_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+#ifndef GROCERYIFY_H
+#define GROCERYIFY_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The outcome of a call. Anything but `Ok` leaves an error message in `out`.
+typedef enum GroceryifyStatus {
+  GROCERYIFY_STATUS_OK = 0,
+  // A required pointer was null.
+  GROCERYIFY_STATUS_NULL_ARGUMENT = 1,
+  // A string wasn't UTF-8, or JSON didn't have the expected shape.
+  GROCERYIFY_STATUS_INVALID_INPUT = 2,
+  // An ingredient line couldn't be parsed.
+  GROCERYIFY_STATUS_PARSE_ERROR = 3,
+  GROCERYIFY_STATUS_UNKNOWN_LOCALE = 4,
+  // The library panicked. This is a bug; the message says where.
+  GROCERYIFY_STATUS_PANIC = 5,
+} GroceryifyStatus;
+
+// Merges like ingredients into a grocery list.
+//
+// `ingredients` is a JSON array of ingredients and `options` JSON merge options, or null for
+// the defaults. On success `out` receives a JSON array of grocery items.
+//
+// # Safety
+// `ingredients` and a non-null `options` must be NUL-terminated strings, and `out` must point
+// to writable storage for a pointer.
+enum GroceryifyStatus groceryify_merge(const char *ingredients, const char *options, char **out);
+
+// Parses an ingredient line such as "2 cups flour, sifted".
+//
+// `locale` is the code of the bundled locale the line is written in, or null for English. On
+// success `out` receives the ingredient as a JSON object.
+//
+// # Safety
+// `line` and a non-null `locale` must be NUL-terminated strings, and `out` must point to
+// writable storage for a pointer.
+enum GroceryifyStatus groceryify_parse(const char *line, const char *locale, char **out);
+
+// Renders ingredients' amounts and units for display.
+//
+// `ingredients` is a JSON array of ingredients and `options` JSON format options, or null for
+// the defaults. On success `out` receives a JSON array of the ingredients with their
+// `amountDisplay`, `unitDisplay` and `display` strings.
+//
+// # Safety
+// `ingredients` and a non-null `options` must be NUL-terminated strings, and `out` must point
+// to writable storage for a pointer.
+enum GroceryifyStatus groceryify_format(const char *ingredients, const char *options, char **out);
+
+// Frees a string returned through `out`. Null is ignored.
+//
+// # Safety
+// `string` must be null or a string from this library that hasn't been freed yet.
+void groceryify_string_free(char *string);
+
+#endif  /* GROCERYIFY_H */
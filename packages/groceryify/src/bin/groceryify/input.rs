@@ -0,0 +1,264 @@
+use std::{fmt, path::Path};
+
+use groceryify::{Locale, ingredient::Ingredient, parse_ingredient_in};
+
+/// The ways ingredients can be written in an input file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputFormat {
+    /// An array of ingredient objects, as the wasm and desktop APIs take them.
+    Json,
+    /// A header row naming the columns, then one ingredient per row.
+    Csv,
+    /// One ingredient line per line, such as "2 cups flour, sifted".
+    Text,
+}
+
+impl InputFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            "text" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// Guesses a format from a file's extension, or from its contents when the extension doesn't
+    /// say, so JSON piped to stdin is still read as JSON.
+    pub fn detect(path: Option<&Path>, contents: &str) -> Self {
+        let extension = path
+            .and_then(Path::extension)
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("json") => Self::Json,
+            Some("csv") => Self::Csv,
+            Some("txt") => Self::Text,
+            _ if contents.trim_start().starts_with('[') => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// An input that couldn't be read, with where in it the problem is.
+#[derive(Debug, PartialEq)]
+pub struct InputError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl InputError {
+    fn at(line: usize, message: impl ToString) -> Self {
+        Self {
+            line: Some(line),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Reads the ingredients in an input, tagging those without a source with `source`.
+pub fn read_ingredients(
+    contents: &str,
+    format: InputFormat,
+    locale: &Locale,
+    source: &str,
+) -> Result<Vec<Ingredient>, InputError> {
+    let ingredients = match format {
+        InputFormat::Json => serde_json::from_str(contents).map_err(|e| InputError {
+            line: Some(e.line()),
+            message: e.to_string(),
+        })?,
+        InputFormat::Csv => read_csv(contents)?,
+        InputFormat::Text => read_text(contents, locale)?,
+    };
+    Ok(ingredients
+        .into_iter()
+        .map(|i: Ingredient| match i.source {
+            Some(_) => i,
+            None => i.with_source(source),
+        })
+        .collect())
+}
+
+/// Parses one ingredient per line, skipping blank lines and "#" comments.
+fn read_text(contents: &str, locale: &Locale) -> Result<Vec<Ingredient>, InputError> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .map(|(index, line)| {
+            parse_ingredient_in(line, locale).map_err(|e| InputError::at(index + 1, e))
+        })
+        .collect()
+}
+
+/// Reads rows under a header with a "name" column and any of "amount", "amount_max", "unit",
+/// "note" and "source". Other columns are ignored, so the CSV `render` writes reads back in. A
+/// row with no amount is used to taste.
+fn read_csv(contents: &str) -> Result<Vec<Ingredient>, InputError> {
+    let mut rows = parse_csv(contents).into_iter();
+    let Some((_, header)) = rows.next() else {
+        return Ok(vec![]);
+    };
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.contains(&h.trim().to_lowercase().as_str()))
+    };
+    let name = column(&["name"]).ok_or_else(|| InputError::at(1, "no \"name\" column"))?;
+    let amount = column(&["amount"]);
+    let amount_max = column(&["amount_max", "amountmax"]);
+    let unit = column(&["unit"]);
+    let note = column(&["note"]);
+    let source = column(&["source"]);
+    rows.filter(|(_, row)| row.iter().any(|field| !field.trim().is_empty()))
+        .map(|(line, row)| {
+            let field = |index: Option<usize>| {
+                index
+                    .and_then(|i| row.get(i))
+                    .map(|f| f.trim())
+                    .filter(|f| !f.is_empty())
+            };
+            let number = |index: Option<usize>| {
+                field(index)
+                    .map(|f| {
+                        f.parse::<f64>()
+                            .map_err(|_| InputError::at(line, format!("\"{f}\" is not a number")))
+                    })
+                    .transpose()
+            };
+            let name = field(Some(name)).ok_or_else(|| InputError::at(line, "missing name"))?;
+            let ingredient = match number(amount)? {
+                Some(amount) => Ingredient {
+                    amount_max: number(amount_max)?,
+                    ..Ingredient::new(name, amount, field(unit).unwrap_or(""))
+                },
+                None => Ingredient::new_to_taste(name),
+            };
+            Ok(Ingredient {
+                note: field(note).map(str::to_string),
+                source: field(source).map(str::to_string),
+                ..ingredient
+            })
+        })
+        .collect()
+}
+
+/// Splits CSV into rows of fields, each with the line it starts on. Quoted fields may contain
+/// commas, line breaks and doubled quotes.
+fn parse_csv(contents: &str) -> Vec<(usize, Vec<String>)> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push((row_line, std::mem::take(&mut row)));
+                line += 1;
+                row_line = line;
+            }
+            ('\n', true) => {
+                field.push(c);
+                line += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push((row_line, row));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_lines_are_parsed_and_tagged() {
+        let result = read_ingredients(
+            "# pancakes\n2 cups flour\n\n1 egg\n",
+            InputFormat::Text,
+            Locale::english(),
+            "pancakes.txt",
+        )
+        .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].name, "flour");
+        assert_eq!(result[1].source.as_deref(), Some("pancakes.txt"));
+    }
+
+    #[test]
+    fn csv_reads_named_columns_and_quotes() {
+        let result = read_ingredients(
+            "unit,name,amount,note\ncup,flour,2,\"sifted, twice\"\n,salt,,\n",
+            InputFormat::Csv,
+            Locale::english(),
+            "stdin",
+        )
+        .unwrap();
+        assert_eq!(result[0].amount, 2.0);
+        assert_eq!(result[0].unit, "cup");
+        assert_eq!(result[0].note.as_deref(), Some("sifted, twice"));
+        assert!(result[1].to_taste);
+    }
+
+    #[test]
+    fn csv_errors_name_their_line() {
+        let error = read_ingredients(
+            "name,amount\nflour,two\n",
+            InputFormat::Csv,
+            Locale::english(),
+            "stdin",
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "line 2: \"two\" is not a number");
+    }
+
+    #[test]
+    fn json_keeps_its_own_sources() {
+        let result = read_ingredients(
+            r#"[{"name": "milk", "amount": 1, "unit": "cup", "source": "cake"}]"#,
+            InputFormat::Json,
+            Locale::english(),
+            "stdin",
+        )
+        .unwrap();
+        assert_eq!(result[0].source.as_deref(), Some("cake"));
+    }
+
+    #[test]
+    fn formats_are_detected_from_extensions_and_contents() {
+        assert_eq!(
+            InputFormat::detect(Some(Path::new("week.CSV")), ""),
+            InputFormat::Csv
+        );
+        assert_eq!(InputFormat::detect(None, "  [{}]"), InputFormat::Json);
+        assert_eq!(InputFormat::detect(None, "2 eggs"), InputFormat::Text);
+    }
+}
@@ -0,0 +1,393 @@
+//! Merges ingredients read from JSON, CSV or ingredient lines into a grocery list.
+//!
+//! ```text
+//! groceryify monday.txt tuesday.csv --unit-system metric --format markdown --provenance
+//! ```
+
+mod input;
+
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+    process::ExitCode,
+};
+
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use groceryify::{
+    Locale, Merger,
+    category::group_by_category,
+    formatting::{FormatOptions, format_items},
+    ingredient::Ingredient,
+    options::{AmbiguousUnits, DisplayUnit, MergeOptions, UnitSystem},
+    render::{RenderFormat, RenderOptions, render},
+};
+use serde::de::DeserializeOwned;
+
+use crate::input::{InputFormat, read_ingredients};
+
+fn command() -> Command {
+    Command::new("groceryify")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Merges recipe ingredients into a grocery list")
+        .arg(
+            Arg::new("files")
+                .value_name("FILE")
+                .num_args(0..)
+                .help("Files to read ingredients from; stdin when none are given or for \"-\""),
+        )
+        .arg(
+            Arg::new("input-format")
+                .long("input-format")
+                .short('i')
+                .value_parser(["json", "csv", "text"])
+                .help("How inputs are written; guessed from each file's extension otherwise"),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .short('f')
+                .value_parser(["text", "markdown", "json", "csv"])
+                .default_value("text")
+                .help("How the list is printed"),
+        )
+        .arg(
+            Arg::new("options")
+                .long("options")
+                .value_name("FILE")
+                .help("A JSON file of merge options; the flags below override it"),
+        )
+        .arg(Arg::new("unit-system").long("unit-system").value_parser([
+            "original",
+            "metric",
+            "usCustomary",
+        ]))
+        .arg(
+            Arg::new("display-unit")
+                .long("display-unit")
+                .value_name("UNIT")
+                .help("\"bestFit\", \"original\", or a unit to show every amount in"),
+        )
+        .arg(
+            Arg::new("ambiguous-units")
+                .long("ambiguous-units")
+                .value_parser(["byName", "weight", "volume", "keepApart"])
+                .help("How units such as \"oz\" that are both a weight and a volume are read"),
+        )
+        .arg(
+            Arg::new("locale")
+                .long("locale")
+                .help("Code of the locale ingredients are written and printed in, e.g. \"es\""),
+        )
+        .arg(
+            Arg::new("synonym")
+                .long("synonym")
+                .value_name("NAME=CANONICAL")
+                .action(ArgAction::Append)
+                .help("Merge NAME under CANONICAL; may be repeated"),
+        )
+        .arg(
+            Arg::new("density")
+                .long("density")
+                .value_name("NAME=GRAMS_PER_CUP")
+                .action(ArgAction::Append)
+                .help("Add or replace an ingredient density; may be repeated"),
+        )
+        .arg(
+            Arg::new("round-to-packages")
+                .long("round-to-packages")
+                .action(ArgAction::SetTrue)
+                .help("Say which packages to buy for each item"),
+        )
+        .arg(
+            Arg::new("group-by-category")
+                .long("group-by-category")
+                .action(ArgAction::SetTrue)
+                .help("Order items by store section"),
+        )
+        .arg(
+            Arg::new("provenance")
+                .long("provenance")
+                .action(ArgAction::SetTrue)
+                .help("List the ingredients merged into each item"),
+        )
+        .arg(
+            Arg::new("ascii-fractions")
+                .long("ascii-fractions")
+                .action(ArgAction::SetTrue)
+                .help("Write \"1/2\" instead of \"½\""),
+        )
+        .arg(
+            Arg::new("max-denominator")
+                .long("max-denominator")
+                .value_parser(value_parser!(u32))
+                .help("Largest fraction denominator to round to; below 2 writes decimals"),
+        )
+        .arg(
+            Arg::new("explain")
+                .long("explain")
+                .action(ArgAction::SetTrue)
+                .help("Explain on stderr why lines of the same ingredient were not merged"),
+        )
+}
+
+fn main() -> ExitCode {
+    match run(&command().get_matches()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("groceryify: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), String> {
+    let options = merge_options(matches)?;
+    let locale = options.locale().map_err(|e| e.to_string())?;
+    let input_format = matches
+        .get_one::<String>("input-format")
+        .and_then(|name| InputFormat::from_name(name));
+    let ingredients = read_inputs(matches, input_format, locale)?;
+
+    let merger = Merger::new(options);
+    let report = merger.merge_with_diagnostics(&ingredients);
+    let items = if matches.get_flag("round-to-packages") {
+        merger.round_to_packages(report.items)
+    } else {
+        report.items
+    };
+    if matches.get_flag("explain") {
+        for diagnostic in &report.diagnostics {
+            for reason in &diagnostic.reasons {
+                eprintln!("{}: {reason}", diagnostic.name);
+            }
+        }
+    }
+
+    let format_options = FormatOptions {
+        unicode_fractions: !matches.get_flag("ascii-fractions"),
+        max_denominator: matches
+            .get_one::<u32>("max-denominator")
+            .copied()
+            .unwrap_or(FormatOptions::default().max_denominator),
+        locale: merger.options().locale.clone(),
+        ..Default::default()
+    };
+    let group = matches.get_flag("group-by-category");
+    let output = match matches.get_one::<String>("format").map(String::as_str) {
+        Some("json") => {
+            let formatted = format_items(&items, &format_options);
+            let formatted = if group {
+                group_by_category(formatted)
+                    .into_iter()
+                    .flat_map(|group| group.items)
+                    .collect()
+            } else {
+                formatted
+            };
+            serde_json::to_string_pretty(&formatted).map_err(|e| e.to_string())? + "\n"
+        }
+        format => render(
+            &items,
+            &RenderOptions {
+                format: match format {
+                    Some("markdown") => RenderFormat::Markdown,
+                    Some("csv") => RenderFormat::Csv,
+                    _ => RenderFormat::PlainText,
+                },
+                group_by_category: group,
+                provenance: matches.get_flag("provenance"),
+                format_options,
+            },
+        ),
+    };
+    print!("{output}");
+    Ok(())
+}
+
+/// The options file, if any, with the flags applied over it.
+fn merge_options(matches: &ArgMatches) -> Result<MergeOptions, String> {
+    let mut options = match matches.get_one::<String>("options") {
+        Some(path) => {
+            let contents = fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+            serde_json::from_str(&contents).map_err(|e| format!("{path}: {e}"))?
+        }
+        None => MergeOptions::default(),
+    };
+    if let Some(system) = matches.get_one::<String>("unit-system") {
+        options.unit_system = from_name::<UnitSystem>(system)?;
+    }
+    if let Some(unit) = matches.get_one::<String>("display-unit") {
+        options.display_unit = match unit.as_str() {
+            "bestFit" => DisplayUnit::BestFit,
+            "original" => DisplayUnit::Original,
+            unit => DisplayUnit::Fixed(unit.to_string()),
+        };
+    }
+    if let Some(reading) = matches.get_one::<String>("ambiguous-units") {
+        options.ambiguous_units = from_name::<AmbiguousUnits>(reading)?;
+    }
+    if let Some(locale) = matches.get_one::<String>("locale") {
+        options.locale = Some(locale.clone());
+    }
+    for pair in matches.get_many::<String>("synonym").unwrap_or_default() {
+        let (name, canonical) = split_pair(pair)?;
+        options
+            .synonyms
+            .insert(name.to_string(), canonical.to_string());
+    }
+    for pair in matches.get_many::<String>("density").unwrap_or_default() {
+        let (name, grams) = split_pair(pair)?;
+        let grams = grams
+            .parse::<f64>()
+            .ok()
+            .filter(|grams| grams.is_finite() && *grams > 0.0)
+            .ok_or_else(|| format!("\"{grams}\" is not a positive number of grams"))?;
+        options.densities.insert(name.to_string(), grams);
+    }
+    Ok(options)
+}
+
+/// Reads a unit-only enum from the name serde gives its variant.
+fn from_name<T: DeserializeOwned>(name: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).map_err(|e| e.to_string())
+}
+
+fn split_pair(pair: &str) -> Result<(&str, &str), String> {
+    pair.split_once('=')
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+        .ok_or_else(|| format!("expected NAME=VALUE, got \"{pair}\""))
+}
+
+/// Reads every input in order, or stdin when no files are given.
+fn read_inputs(
+    matches: &ArgMatches,
+    format: Option<InputFormat>,
+    locale: &Locale,
+) -> Result<Vec<Ingredient>, String> {
+    let files = matches
+        .get_many::<String>("files")
+        .map(|files| files.map(String::as_str).collect::<Vec<_>>())
+        .unwrap_or_else(|| vec!["-"]);
+    let mut ingredients = vec![];
+    for file in files {
+        let (path, name, contents) = if file == "-" {
+            let mut contents = String::new();
+            io::stdin()
+                .read_to_string(&mut contents)
+                .map_err(|e| format!("stdin: {e}"))?;
+            (None, "stdin", contents)
+        } else {
+            let contents = fs::read_to_string(file).map_err(|e| format!("{file}: {e}"))?;
+            (Some(Path::new(file)), file, contents)
+        };
+        let format = format.unwrap_or_else(|| InputFormat::detect(path, &contents));
+        ingredients.extend(
+            read_ingredients(&contents, format, locale, name)
+                .map_err(|e| format!("{name}: {e}"))?,
+        );
+    }
+    Ok(ingredients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> Result<MergeOptions, String> {
+        let matches = command()
+            .try_get_matches_from(["groceryify"].iter().chain(args))
+            .unwrap();
+        merge_options(&matches)
+    }
+
+    #[test]
+    fn flags_set_merge_options() {
+        let result = options(&[
+            "--unit-system",
+            "metric",
+            "--ambiguous-units",
+            "weight",
+            "--locale",
+            "es",
+        ])
+        .unwrap();
+        assert_eq!(result.unit_system, UnitSystem::Metric);
+        assert_eq!(result.ambiguous_units, AmbiguousUnits::Weight);
+        assert_eq!(result.locale.as_deref(), Some("es"));
+        assert_eq!(options(&[]).unwrap(), MergeOptions::default());
+    }
+
+    #[test]
+    fn display_units_are_named_or_fixed() {
+        let display_unit = |unit| options(&["--display-unit", unit]).unwrap().display_unit;
+        assert_eq!(display_unit("bestFit"), DisplayUnit::BestFit);
+        assert_eq!(display_unit("original"), DisplayUnit::Original);
+        assert_eq!(display_unit("g"), DisplayUnit::Fixed("g".to_string()));
+    }
+
+    #[test]
+    fn synonyms_and_densities_may_be_repeated() {
+        let result = options(&[
+            "--synonym",
+            "scallion = green onion",
+            "--synonym",
+            "coriander=cilantro",
+            "--density",
+            "flour=125",
+            "--density",
+            "cocoa=85.5",
+        ])
+        .unwrap();
+        assert_eq!(result.synonyms["scallion"], "green onion");
+        assert_eq!(result.synonyms["coriander"], "cilantro");
+        assert_eq!(result.densities["flour"], 125.0);
+        assert_eq!(result.densities["cocoa"], 85.5);
+    }
+
+    #[test]
+    fn densities_must_be_positive_numbers() {
+        for grams in ["-1", "0", "inf", "NaN", "lots"] {
+            assert_eq!(
+                options(&["--density", &format!("flour={grams}")]).unwrap_err(),
+                format!("\"{grams}\" is not a positive number of grams")
+            );
+        }
+    }
+
+    #[test]
+    fn pairs_need_a_name_and_a_value() {
+        assert_eq!(split_pair(" flour = 120 "), Ok(("flour", "120")));
+        assert_eq!(split_pair("a=b=c"), Ok(("a", "b=c")));
+        for pair in ["flour", "=120", "flour=", " = "] {
+            assert_eq!(
+                split_pair(pair),
+                Err(format!("expected NAME=VALUE, got \"{pair}\""))
+            );
+        }
+        assert!(options(&["--synonym", "scallion"]).is_err());
+    }
+
+    #[test]
+    fn enums_are_read_by_their_serde_names() {
+        assert_eq!(
+            from_name::<UnitSystem>("usCustomary"),
+            Ok(UnitSystem::UsCustomary)
+        );
+        assert_eq!(
+            from_name::<AmbiguousUnits>("keepApart"),
+            Ok(AmbiguousUnits::KeepApart)
+        );
+        assert!(from_name::<UnitSystem>("imperial").is_err());
+    }
+
+    #[test]
+    fn unknown_locales_are_rejected() {
+        let result = options(&["--locale", "xx"]).unwrap();
+        assert_eq!(
+            result.locale().unwrap_err().to_string(),
+            "Unknown locale: xx"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{grocery_item::GroceryItem, name_table::NameTable};
+
+/// Category given to items that no table entry matches.
+pub const OTHER_CATEGORY: &str = "other";
+
+#[derive(Deserialize)]
+struct Section {
+    name: String,
+    items: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Sections {
+    section: Vec<Section>,
+}
+
+/// Store sections, keyed by normalized ingredient name.
+#[derive(Clone, Debug)]
+pub struct CategoryTable {
+    categories: NameTable<String>,
+    /// Bundled sections in store walking order, used to order groups.
+    order: Vec<String>,
+}
+
+impl CategoryTable {
+    pub fn new() -> Self {
+        let data = include_str!("../resources/categories.toml");
+        let sections: Sections = toml::from_str(data).unwrap();
+        let order = sections.section.iter().map(|s| s.name.clone()).collect();
+        let categories = sections
+            .section
+            .into_iter()
+            .flat_map(|s| s.items.into_iter().map(move |item| (item, s.name.clone())))
+            .collect();
+        Self { categories, order }
+    }
+
+    /// Rewrites every name with `normalize`.
+    pub fn map_names<F: Fn(&str) -> String>(self, normalize: F) -> Self {
+        Self {
+            categories: self.categories.map_names(normalize),
+            order: self.order,
+        }
+    }
+
+    /// Adds or replaces entries under normalized names. Categories may be bundled sections or
+    /// new ones.
+    pub fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, entries: I) {
+        self.categories.extend(
+            entries
+                .into_iter()
+                .map(|(name, category)| (name, category.trim().to_lowercase()))
+                .filter(|(_, category)| !category.is_empty()),
+        );
+    }
+
+    /// Finds the section for a normalized name, so "red onion" is found under "onion".
+    pub fn lookup(&self, name: &str) -> Option<&str> {
+        self.categories
+            .lookup(name)
+            .map(|(_, category)| category.as_str())
+    }
+
+    /// Like [`CategoryTable::lookup`], but unmatched names fall in [`OTHER_CATEGORY`].
+    pub fn categorize(&self, name: &str) -> String {
+        self.lookup(name).unwrap_or(OTHER_CATEGORY).to_string()
+    }
+
+    /// Where a category sorts: bundled sections in walking order, then other categories
+    /// alphabetically, then [`OTHER_CATEGORY`] last.
+    fn rank<'a>(&self, category: &'a str) -> (usize, &'a str) {
+        match self.order.iter().position(|c| c == category) {
+            Some(position) => (position, ""),
+            None if category == OTHER_CATEGORY => (self.order.len() + 1, ""),
+            None => (self.order.len(), category),
+        }
+    }
+}
+
+impl Default for CategoryTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Items of one store section.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CategoryGroup<T> {
+    pub category: String,
+    pub items: Vec<T>,
+}
+
+/// Groups items by their category, ordering groups the way a shopper walks through the store.
+/// Items keep their relative order within a group.
+///
+/// # Arguments:
+/// * `items`: Merged grocery items, or items wrapping them such as formatted items.
+///
+/// # Returns:
+/// * One group per category present in `items`.
+pub fn group_by_category<T: AsRef<GroceryItem>>(items: Vec<T>) -> Vec<CategoryGroup<T>> {
+    let table = CategoryTable::new();
+    let mut groups: Vec<CategoryGroup<T>> = vec![];
+    for item in items {
+        let category = &item.as_ref().category;
+        match groups.iter_mut().find(|g| &g.category == category) {
+            Some(group) => group.items.push(item),
+            None => groups.push(CategoryGroup {
+                category: category.clone(),
+                items: vec![item],
+            }),
+        }
+    }
+    groups.sort_by(|a, b| table.rank(&a.category).cmp(&table.rank(&b.category)));
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingredient::Ingredient;
+
+    fn item(name: &str, category: &str) -> GroceryItem {
+        GroceryItem {
+            ingredient: Ingredient::new(name, 1.0, ""),
+            density_conversion: None,
+            pantry: None,
+            purchase: None,
+            sources: vec![],
+            category: category.to_string(),
+        }
+    }
+
+    #[test]
+    fn bundled_table_parses() {
+        let table = CategoryTable::new();
+        assert_eq!(table.lookup("onion"), Some("produce"));
+        assert_eq!(table.lookup("milk"), Some("dairy and eggs"));
+    }
+
+    #[test]
+    fn longest_suffix_wins() {
+        let table = CategoryTable::new();
+        assert_eq!(table.lookup("red onion"), Some("produce"));
+        assert_eq!(table.lookup("black pepper"), Some("spices"));
+        assert_eq!(table.lookup("green pepper"), Some("produce"));
+    }
+
+    #[test]
+    fn unknown_names_are_other() {
+        assert_eq!(
+            CategoryTable::new().categorize("unobtainium"),
+            OTHER_CATEGORY
+        );
+    }
+
+    #[test]
+    fn extend_adds_and_overrides_entries() {
+        let mut table = CategoryTable::new();
+        table.extend([
+            ("tofu".to_string(), "Refrigerated".to_string()),
+            ("onion".to_string(), "pantry".to_string()),
+            ("salt".to_string(), "  ".to_string()),
+        ]);
+        assert_eq!(table.lookup("tofu"), Some("refrigerated"));
+        assert_eq!(table.lookup("onion"), Some("pantry"));
+        assert_eq!(table.lookup("salt"), Some("spices"));
+    }
+
+    #[test]
+    fn groups_follow_store_order() {
+        let groups = group_by_category(vec![
+            item("salt", "spices"),
+            item("mystery", OTHER_CATEGORY),
+            item("tofu", "refrigerated"),
+            item("onion", "produce"),
+            item("garlic", "produce"),
+        ]);
+        let categories = groups
+            .iter()
+            .map(|g| g.category.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            ["produce", "spices", "refrigerated", OTHER_CATEGORY]
+        );
+        let produce = groups[0]
+            .items
+            .iter()
+            .map(|i| i.ingredient.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(produce, ["onion", "garlic"]);
+    }
+
+    #[test]
+    fn grouping_nothing_gives_no_groups() {
+        assert!(group_by_category::<GroceryItem>(vec![]).is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use std::error::Error;
+
+use crate::{
+    ingredient::Ingredient, ingredient_unit::Quantity, merger::Merger, options::MergeOptions,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum ConvertError {
+    /// The unit to convert to is not a known volume, weight or count.
+    UnknownUnit(String),
+    /// The ingredient's unit doesn't convert to the unit, e.g. a volume to a weight with no known
+    /// density.
+    Incompatible { from: String, to: String },
+}
+
+impl std::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownUnit(unit) => write!(f, "Unknown unit: {unit}"),
+            Self::Incompatible { from, to } => {
+                write!(f, "Cannot convert from \"{from}\" to \"{to}\"")
+            }
+        }
+    }
+}
+
+impl Error for ConvertError {}
+
+/// Converts an ingredient's amount to another unit, between weights and volumes through the
+/// ingredient's density where one is known.
+///
+/// Names are matched to densities like [`crate::merge`] normalizes them, and ambiguous units
+/// such as "oz" are read as the options say, but the name and note are left as written. Both
+/// ends of a range are converted, and ingredients used to taste are returned as they are.
+///
+/// # Arguments:
+/// * `ingredient`: The ingredient to convert.
+/// * `unit`: The unit to convert to, e.g. "g" or "cups".
+/// * `options`: Options supplying extra densities and how to read ambiguous units.
+///
+/// # Returns:
+/// * The ingredient in the canonical spelling of `unit`, e.g. "cup" for "cups", or an error if
+///   the unit is unknown or the ingredient's unit doesn't convert to it.
+pub fn convert_unit(
+    ingredient: &Ingredient,
+    unit: &str,
+    options: &MergeOptions,
+) -> Result<Ingredient, ConvertError> {
+    Merger::shared(options).convert_unit(ingredient, unit)
+}
+
+pub(crate) fn convert_with(
+    merger: &Merger,
+    ingredient: &Ingredient,
+    unit: &str,
+) -> Result<Ingredient, ConvertError> {
+    if ingredient.to_taste {
+        return Ok(ingredient.clone());
+    }
+    let target = merger.quantity(&Ingredient {
+        amount: 1.0,
+        unit: unit.to_string(),
+        ..ingredient.clone()
+    });
+    let Quantity::Known {
+        original_unit: target_unit,
+        ..
+    } = &target
+    else {
+        return Err(ConvertError::UnknownUnit(unit.to_string()));
+    };
+    let kg_per_litre = merger
+        .densities()
+        .lookup(&merger.names().normalize(&ingredient.name))
+        .map(|d| d.kg_per_litre());
+    let convert = |amount: f64| {
+        let quantity = merger.quantity(&Ingredient {
+            amount,
+            ..ingredient.clone()
+        });
+        quantity
+            .amount_like(&target, kg_per_litre)
+            .map(|base| base / target.amount())
+            .ok_or_else(|| ConvertError::Incompatible {
+                from: ingredient.unit.clone(),
+                to: unit.to_string(),
+            })
+    };
+    Ok(Ingredient {
+        amount: convert(ingredient.amount)?,
+        amount_max: ingredient.amount_max.map(convert).transpose()?,
+        unit: target_unit.clone(),
+        ..ingredient.clone()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(ingredient: Ingredient, unit: &str) -> Result<Ingredient, ConvertError> {
+        convert_unit(&ingredient, unit, &MergeOptions::default())
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn volumes_convert_within_their_dimension() {
+        let result = convert(Ingredient::new("milk", 2.0, "cups"), "tbsp").unwrap();
+        assert!(close(result.amount, 32.0));
+        assert_eq!(result.unit, "tbsp");
+    }
+
+    #[test]
+    fn ranges_convert_end_by_end() {
+        let result = convert(Ingredient::new("flour", 1.0, "lb").with_max(2.0), "g").unwrap();
+        assert!(close(result.amount, 453.59237));
+        assert!(close(result.amount_max.unwrap(), 907.18474));
+    }
+
+    #[test]
+    fn volumes_convert_to_weights_through_densities() {
+        // 1 cup of sugar weighs 200 g
+        let result = convert(Ingredient::new("granulated sugar", 1.0, "cup"), "g").unwrap();
+        assert!(close(result.amount, 200.0));
+        assert_eq!(result.name, "granulated sugar");
+    }
+
+    #[test]
+    fn unconvertible_units_are_errors() {
+        assert_eq!(
+            convert(Ingredient::new("tofu", 1.0, "cup"), "g").unwrap_err(),
+            ConvertError::Incompatible {
+                from: "cup".to_string(),
+                to: "g".to_string()
+            }
+        );
+        assert_eq!(
+            convert(Ingredient::new("tofu", 1.0, "cup"), "handful").unwrap_err(),
+            ConvertError::UnknownUnit("handful".to_string())
+        );
+    }
+
+    #[test]
+    fn to_taste_ingredients_are_left_alone() {
+        let result = convert(Ingredient::new_to_taste("salt"), "g").unwrap();
+        assert!(result.to_taste);
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grocery_item::GroceryItem, ingredient::Ingredient, merger::Merger, name_table::NameTable,
+    options::MergeOptions,
+};
+
+/// What an amount of an ingredient costs, per unit ("3.49 per 1 lb") or per package ("4.99 per
+/// 12" eggs).
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Price {
+    pub name: String,
+    pub price: f64,
+    /// How much `price` buys, in `unit`.
+    pub amount: f64,
+    /// Empty for whole items such as eggs.
+    #[serde(default)]
+    pub unit: String,
+}
+
+/// The estimated cost of one grocery item.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemCost {
+    pub name: String,
+    /// `None` when no price of the ingredient converts to its unit, or it is used to taste.
+    pub cost: Option<f64>,
+    /// Set for ingredients used to taste, which have no amount to price.
+    #[serde(default)]
+    pub to_taste: bool,
+}
+
+/// The estimated cost of a grocery list.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CostEstimate {
+    /// One entry per item, in list order.
+    pub items: Vec<ItemCost>,
+    /// The total of the items that have a cost.
+    pub total: f64,
+}
+
+impl CostEstimate {
+    /// Names of the items left out of the total for want of a price. Items used to taste are
+    /// not among them.
+    pub fn unpriced(&self) -> impl Iterator<Item = &str> {
+        self.items
+            .iter()
+            .filter(|item| item.cost.is_none() && !item.to_taste)
+            .map(|item| item.name.as_str())
+    }
+}
+
+/// Prices by normalized ingredient name.
+struct PriceBook<'a> {
+    prices: NameTable<Vec<&'a Price>>,
+}
+
+impl<'a> PriceBook<'a> {
+    fn new(merger: &Merger, prices: &'a [Price]) -> Self {
+        let mut book: HashMap<String, Vec<&Price>> = HashMap::new();
+        for price in prices.iter().filter(|p| {
+            p.price.is_finite() && p.price >= 0.0 && p.amount.is_finite() && p.amount > 0.0
+        }) {
+            book.entry(merger.names().normalize(&price.name))
+                .or_default()
+                .push(price);
+        }
+        Self {
+            prices: book.into_iter().collect(),
+        }
+    }
+
+    /// Finds the prices for a normalized name, so "whole milk" is priced like "milk".
+    fn lookup(&self, name: &str) -> &[&'a Price] {
+        self.prices
+            .lookup(name)
+            .map_or(&[], |(_, prices)| prices.as_slice())
+    }
+}
+
+/// Estimates what a merged grocery list costs from a price book.
+///
+/// Price names are normalized like [`crate::merge`] normalizes ingredient names, and amounts are
+/// converted between units, and between weights and volumes where a density is known. An item
+/// is priced by the first of its ingredient's prices that converts to its unit. Items rounded to
+/// packages cost what the packages hold, and ranges cost their upper end.
+///
+/// # Arguments:
+/// * `items`: Output of [`crate::merge_with_options`], possibly after pantry subtraction or
+///   package rounding.
+/// * `prices`: What ingredients cost.
+/// * `options`: The options the list was merged with, for their densities.
+///
+/// # Returns:
+/// * Each item's cost and the total.
+pub fn estimate_cost(
+    items: &[GroceryItem],
+    prices: &[Price],
+    options: &MergeOptions,
+) -> CostEstimate {
+    Merger::shared(options).estimate_cost(items, prices)
+}
+
+pub(crate) fn estimate_with(
+    merger: &Merger,
+    items: &[GroceryItem],
+    prices: &[Price],
+) -> CostEstimate {
+    let book = PriceBook::new(merger, prices);
+    let items = items
+        .iter()
+        .map(|item| ItemCost {
+            name: item.ingredient.name.clone(),
+            cost: cost(merger, &book, item),
+            to_taste: item.ingredient.to_taste,
+        })
+        .collect::<Vec<_>>();
+    CostEstimate {
+        total: items.iter().filter_map(|item| item.cost).sum(),
+        items,
+    }
+}
+
+fn cost(merger: &Merger, book: &PriceBook, item: &GroceryItem) -> Option<f64> {
+    let ingredient = &item.ingredient;
+    if ingredient.to_taste {
+        return None;
+    }
+    let amount = match &item.purchase {
+        Some(purchase) => purchase.amount,
+        None => ingredient.amount_max.unwrap_or(ingredient.amount),
+    };
+    let needed = merger.quantity(&Ingredient {
+        amount,
+        ..ingredient.clone()
+    });
+    let kg_per_litre = merger
+        .densities()
+        .lookup(&ingredient.name)
+        .map(|d| d.kg_per_litre());
+    book.lookup(&ingredient.name).iter().find_map(|price| {
+        let priced = merger.quantity(&Ingredient {
+            amount: price.amount,
+            unit: price.unit.clone(),
+            ..ingredient.clone()
+        });
+        let needed = needed.amount_like(&priced, kg_per_litre)?;
+        Some(price.price * needed / priced.amount())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge_with_options;
+
+    fn price(name: &str, price: f64, amount: f64, unit: &str) -> Price {
+        Price {
+            name: name.to_string(),
+            price,
+            amount,
+            unit: unit.to_string(),
+        }
+    }
+
+    fn estimate(ingredients: &[Ingredient], prices: &[Price]) -> CostEstimate {
+        let options = MergeOptions::default();
+        estimate_cost(&merge_with_options(ingredients, &options), prices, &options)
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn prices_per_unit_convert_to_the_item_unit() {
+        let result = estimate(
+            &[Ingredient::new("milk", 2.0, "cup")],
+            &[price("milk", 4.0, 1.0, "gal")],
+        );
+        // 2 cups are an eighth of a gallon
+        assert!(close(result.items[0].cost.unwrap(), 0.5));
+        assert!(close(result.total, 0.5));
+    }
+
+    #[test]
+    fn prices_per_package_divide_by_its_size() {
+        let result = estimate(
+            &[Ingredient::new("eggs", 3.0, "")],
+            &[price("egg", 6.0, 12.0, "")],
+        );
+        assert!(close(result.items[0].cost.unwrap(), 1.5));
+    }
+
+    #[test]
+    fn weights_are_priced_by_volume_through_densities() {
+        // 1 cup of sugar weighs 200 g
+        let result = estimate(
+            &[Ingredient::new("sugar", 1.0, "cup")],
+            &[price("sugar", 2.0, 1.0, "kg")],
+        );
+        assert!(close(result.items[0].cost.unwrap(), 0.4));
+    }
+
+    #[test]
+    fn unpriced_items_are_left_out_of_the_total() {
+        let result = estimate(
+            &[
+                Ingredient::new("milk", 1.0, "gal"),
+                Ingredient::new("saffron", 1.0, "g"),
+                Ingredient::new_to_taste("salt"),
+            ],
+            &[
+                price("milk", 4.0, 1.0, "gal"),
+                price("salt", 1.0, 1.0, "lb"),
+            ],
+        );
+        assert!(close(result.total, 4.0));
+        assert_eq!(result.items[2].cost, None);
+        assert_eq!(result.unpriced().collect::<Vec<_>>(), vec!["saffron"]);
+    }
+
+    #[test]
+    fn the_first_price_that_converts_is_used() {
+        // tofu has no density, so its price by weight can't price a volume
+        let result = estimate(
+            &[Ingredient::new("tofu", 1.0, "cup")],
+            &[
+                price("tofu", 3.0, 1.0, "lb"),
+                price("tofu", 2.0, 2.0, "cup"),
+            ],
+        );
+        assert!(close(result.items[0].cost.unwrap(), 1.0));
+    }
+
+    #[test]
+    fn rounded_items_cost_what_they_buy() {
+        let options = MergeOptions::default();
+        let merger = Merger::new(options);
+        let items = merger.round_to_packages(merger.merge(&[Ingredient::new("eggs", 7.0, "")]));
+        let result = merger.estimate_cost(&items, &[price("egg", 6.0, 12.0, "")]);
+        assert!(close(result.total, 6.0));
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::name_table::NameTable;
+
+/// Litres in one US cup, for turning grams per cup into kilograms per litre.
+const LITRES_PER_CUP: f64 = 0.2365882365;
+
+/// Records that a merged quantity combined weights and volumes through a density.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DensityConversion {
+    /// The density table entry that was used, e.g. "flour" for "bread flour" if no closer match.
+    pub matched_name: String,
+    pub grams_per_cup: f64,
+}
+
+impl DensityConversion {
+    pub fn kg_per_litre(&self) -> f64 {
+        self.grams_per_cup / 1000.0 / LITRES_PER_CUP
+    }
+}
+
+/// Ingredient densities, in grams per US cup, keyed by normalized ingredient name.
+#[derive(Clone, Debug)]
+pub struct DensityTable {
+    grams_per_cup: NameTable<f64>,
+}
+
+impl DensityTable {
+    pub fn new() -> Self {
+        let data = include_str!("../resources/densities.toml");
+        Self {
+            grams_per_cup: toml::from_str(data).unwrap(),
+        }
+    }
+
+    /// Rewrites every name with `normalize`.
+    pub fn map_names<F: Fn(&str) -> String>(self, normalize: F) -> Self {
+        Self {
+            grams_per_cup: self.grams_per_cup.map_names(normalize),
+        }
+    }
+
+    /// Adds or replaces entries under normalized names, ignoring densities that aren't positive.
+    pub fn extend<I: IntoIterator<Item = (String, f64)>>(&mut self, entries: I) {
+        self.grams_per_cup.extend(
+            entries
+                .into_iter()
+                .filter(|(_, grams)| grams.is_finite() && *grams > 0.0),
+        );
+    }
+
+    /// Finds the density for a normalized name, so "dark brown sugar" uses "brown sugar" and
+    /// "whole milk" uses "milk".
+    pub fn lookup(&self, name: &str) -> Option<DensityConversion> {
+        self.grams_per_cup
+            .lookup(name)
+            .map(|(matched_name, grams_per_cup)| DensityConversion {
+                matched_name: matched_name.to_string(),
+                grams_per_cup: *grams_per_cup,
+            })
+    }
+}
+
+impl Default for DensityTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table_parses() {
+        let table = DensityTable::new();
+        assert_eq!(table.lookup("flour").unwrap().grams_per_cup, 120.0);
+    }
+
+    #[test]
+    fn longest_suffix_wins() {
+        let table = DensityTable::new();
+        assert_eq!(
+            table.lookup("dark brown sugar").unwrap().matched_name,
+            "brown sugar"
+        );
+        assert_eq!(table.lookup("whole milk").unwrap().matched_name, "milk");
+    }
+
+    #[test]
+    fn unknown_names_have_no_density() {
+        assert!(DensityTable::new().lookup("saffron").is_none());
+        assert!(DensityTable::new().lookup("").is_none());
+    }
+
+    #[test]
+    fn extend_adds_and_overrides_entries() {
+        let mut table = DensityTable::new();
+        table.extend([("saffron".to_string(), 50.0), ("flour".to_string(), 125.0)]);
+        assert_eq!(table.lookup("saffron").unwrap().grams_per_cup, 50.0);
+        assert_eq!(table.lookup("flour").unwrap().grams_per_cup, 125.0);
+    }
+
+    #[test]
+    fn extend_ignores_non_positive_densities() {
+        let mut table = DensityTable::new();
+        table.extend([("flour".to_string(), 0.0), ("air".to_string(), -1.0)]);
+        assert_eq!(table.lookup("flour").unwrap().grams_per_cup, 120.0);
+        assert!(table.lookup("air").is_none());
+    }
+
+    #[test]
+    fn kg_per_litre_converts_from_grams_per_cup() {
+        let water = DensityTable::new().lookup("water").unwrap();
+        assert!((water.kg_per_litre() - 1.0).abs() < 0.01);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+/// Words that join descriptors, as in "peeled and diced".
+const CONNECTORS: [&str; 2] = ["and", "or"];
+
+#[derive(Deserialize)]
+struct Descriptors {
+    preparation: Vec<String>,
+    size: Vec<String>,
+    freshness: Vec<String>,
+}
+
+/// Preparation, size and freshness words that are stripped from ingredient names before merging.
+#[derive(Clone, Debug)]
+pub struct DescriptorList {
+    descriptors: HashSet<String>,
+}
+
+impl DescriptorList {
+    pub fn new() -> Self {
+        let data = include_str!("../resources/descriptors.toml");
+        let descriptors: Descriptors = toml::from_str(data).unwrap();
+        let descriptors = descriptors
+            .preparation
+            .into_iter()
+            .chain(descriptors.size)
+            .chain(descriptors.freshness)
+            .collect();
+        Self { descriptors }
+    }
+
+    /// Adds descriptors. Each is a single word; case and surrounding whitespace are ignored.
+    pub fn extend<I: IntoIterator<Item = String>>(&mut self, descriptors: I) {
+        self.descriptors.extend(
+            descriptors
+                .into_iter()
+                .map(|d| d.trim().to_lowercase())
+                .filter(|d| !d.is_empty()),
+        );
+    }
+
+    /// Removes descriptor words from a name.
+    ///
+    /// # Arguments:
+    /// * `name`: An ingredient name, e.g. "peeled and diced large potatoes".
+    ///
+    /// # Returns:
+    /// * The name without descriptors, e.g. "potatoes", and each run of stripped words, e.g.
+    ///   "peeled and diced" and "large". A name made only of descriptors is returned unchanged.
+    pub fn strip(&self, name: &str) -> (String, Vec<String>) {
+        let mut kept: Vec<&str> = vec![];
+        let mut stripped: Vec<String> = vec![];
+        let mut run: Vec<&str> = vec![];
+        for word in name.split_whitespace() {
+            let lower = word.to_lowercase();
+            let joins_run =
+                kept.is_empty() && !run.is_empty() && CONNECTORS.contains(&lower.as_str());
+            if self.descriptors.contains(&lower) || joins_run {
+                run.push(word);
+                continue;
+            }
+            flush_run(&mut run, &mut stripped);
+            kept.push(word);
+        }
+        flush_run(&mut run, &mut stripped);
+        if kept.is_empty() {
+            return (name.trim().to_string(), vec![]);
+        }
+        (kept.join(" "), stripped)
+    }
+}
+
+impl Default for DescriptorList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Moves a run of stripped words into `stripped` as one phrase, without dangling connectors.
+fn flush_run(run: &mut Vec<&str>, stripped: &mut Vec<String>) {
+    while run
+        .last()
+        .is_some_and(|word| CONNECTORS.contains(&word.to_lowercase().as_str()))
+    {
+        run.pop();
+    }
+    if !run.is_empty() {
+        stripped.push(run.join(" "));
+    }
+    run.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(name: &str) -> (String, Vec<String>) {
+        DescriptorList::new().strip(name)
+    }
+
+    #[test]
+    fn strips_preparation_size_and_freshness_words() {
+        assert_eq!(
+            strip("chopped onion"),
+            ("onion".to_string(), vec!["chopped".to_string()])
+        );
+        assert_eq!(
+            strip("large egg"),
+            ("egg".to_string(), vec!["large".to_string()])
+        );
+        assert_eq!(
+            strip("fresh basil"),
+            ("basil".to_string(), vec!["fresh".to_string()])
+        );
+    }
+
+    #[test]
+    fn keeps_adjacent_descriptors_together() {
+        let (name, notes) = strip("finely chopped onion");
+        assert_eq!(name, "onion");
+        assert_eq!(notes, vec!["finely chopped"]);
+    }
+
+    #[test]
+    fn keeps_descriptors_joined_by_a_connector_together() {
+        let (name, notes) = strip("peeled and diced large potatoes");
+        assert_eq!(name, "potatoes");
+        assert_eq!(notes, vec!["peeled and diced large"]);
+    }
+
+    #[test]
+    fn keeps_connectors_between_ingredient_words() {
+        let (name, notes) = strip("salt and pepper");
+        assert_eq!(name, "salt and pepper");
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn strips_descriptors_anywhere_in_the_name() {
+        let (name, notes) = strip("boneless chicken thighs trimmed");
+        assert_eq!(name, "chicken thighs");
+        assert_eq!(notes, vec!["boneless", "trimmed"]);
+    }
+
+    #[test]
+    fn matches_descriptors_case_insensitively() {
+        assert_eq!(strip("Large Onion").0, "Onion");
+    }
+
+    #[test]
+    fn keeps_names_made_only_of_descriptors() {
+        assert_eq!(strip("large"), ("large".to_string(), vec![]));
+    }
+
+    #[test]
+    fn keeps_product_words() {
+        assert_eq!(strip("ground beef").0, "ground beef");
+        assert_eq!(strip("crushed tomatoes").0, "crushed tomatoes");
+        assert_eq!(strip("sliced almonds").0, "sliced almonds");
+        assert_eq!(strip("firm tofu").0, "firm tofu");
+    }
+
+    #[test]
+    fn extend_adds_descriptors() {
+        let mut list = DescriptorList::new();
+        list.extend([" Heaping ".to_string(), "".to_string()]);
+        assert_eq!(list.strip("heaping spoonful").0, "spoonful");
+    }
+}
@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grocery_item::GroceryItem,
+    ingredient::Ingredient,
+    ingredient_unit::{ParsedMeasurement, ParsedMeasurementError},
+    merger::Merger,
+};
+
+/// A merged grocery list, with an explanation for every ingredient left on more than one line.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeReport {
+    pub items: Vec<GroceryItem>,
+    pub diagnostics: Vec<MergeDiagnostic>,
+}
+
+/// Why the lines of one ingredient, e.g. "8 oz cream cheese" and "1 cup cream cheese", were not
+/// merged into one.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeDiagnostic {
+    /// The normalized name the lines share.
+    pub name: String,
+    /// The units of the separate lines, in list order.
+    pub units: Vec<String>,
+    pub reasons: Vec<UnmergedReason>,
+}
+
+/// A reason lines of the same ingredient could not be added together.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum UnmergedReason {
+    /// A weight, a volume and a count don't convert into each other, e.g. "oz" and "cup" of an
+    /// ingredient with no known density.
+    IncompatibleDimensions { units: Vec<String> },
+    /// The unit is spelled like more than one known unit, so it could be either.
+    AmbiguousUnit { unit: String },
+    /// No unit table knows the unit, so it only adds to itself.
+    UnknownUnit { unit: String },
+    /// Counted units such as "clove" and "head" never convert into each other.
+    DifferentCountUnits { units: Vec<String> },
+    /// The caller's own units without a volume or mass equivalent only add to themselves.
+    DifferentCustomUnits { units: Vec<String> },
+}
+
+impl std::fmt::Display for UnmergedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncompatibleDimensions { units } => write!(
+                f,
+                "{} measure different things; add a density to merge them",
+                units.join(" and ")
+            ),
+            Self::AmbiguousUnit { unit } => {
+                write!(f, "\"{unit}\" could mean more than one unit")
+            }
+            Self::UnknownUnit { unit } => write!(f, "\"{unit}\" is not a known unit"),
+            Self::DifferentCountUnits { units } => {
+                write!(f, "{} are counted separately", units.join(" and "))
+            }
+            Self::DifferentCustomUnits { units } => write!(
+                f,
+                "{} are custom units; give them a volume or weight to merge them",
+                units.join(" and ")
+            ),
+        }
+    }
+}
+
+/// What a grocery line's unit measures.
+#[derive(Clone, Copy, PartialEq)]
+enum UnitKind {
+    Volume,
+    Mass,
+    Count,
+    Custom,
+    Unknown,
+    Ambiguous,
+}
+
+impl UnitKind {
+    fn of(ingredient: &Ingredient, merger: &Merger) -> Self {
+        let unit = ingredient.unit.as_str();
+        if unit.is_empty() {
+            // Bare counts such as "3 eggs"
+            return Self::Count;
+        }
+        let registry = merger.registry();
+        match registry.measure_as(ingredient, merger.reading(ingredient)) {
+            Ok((ParsedMeasurement::Volume(_), _)) => Self::Volume,
+            Ok((ParsedMeasurement::Mass(_), _)) => Self::Mass,
+            Ok((ParsedMeasurement::Count { .. }, _)) if registry.is_custom(unit) => Self::Custom,
+            Ok((ParsedMeasurement::Count { .. }, _)) => Self::Count,
+            Err(ParsedMeasurementError::AmbiguousUnit(_)) => Self::Ambiguous,
+            Err(_) => Self::Unknown,
+        }
+    }
+
+    /// What the unit measures, with custom units counted; `None` when that isn't known.
+    fn dimension(self) -> Option<UnitKind> {
+        match self {
+            Self::Custom => Some(Self::Count),
+            Self::Unknown | Self::Ambiguous => None,
+            kind => Some(kind),
+        }
+    }
+}
+
+/// Explains why lines with the same name in a merged list were kept apart.
+pub(crate) fn diagnose(items: &[GroceryItem], merger: &Merger) -> Vec<MergeDiagnostic> {
+    let mut diagnostics: Vec<MergeDiagnostic> = vec![];
+    for (i, item) in items.iter().enumerate() {
+        let name = &item.ingredient.name;
+        let seen = items[..i]
+            .iter()
+            .any(|other| &other.ingredient.name == name);
+        let group = items
+            .iter()
+            .filter(|other| &other.ingredient.name == name)
+            .collect::<Vec<_>>();
+        if seen || group.len() < 2 {
+            continue;
+        }
+        let ingredients = group
+            .iter()
+            .map(|other| &other.ingredient)
+            .collect::<Vec<_>>();
+        diagnostics.push(MergeDiagnostic {
+            name: name.clone(),
+            reasons: reasons(&ingredients, merger),
+            units: ingredients.iter().map(|i| i.unit.clone()).collect(),
+        });
+    }
+    diagnostics
+}
+
+fn reasons(ingredients: &[&Ingredient], merger: &Merger) -> Vec<UnmergedReason> {
+    let kinds = ingredients
+        .iter()
+        .map(|i| (UnitKind::of(i, merger), i.unit.as_str()))
+        .collect::<Vec<_>>();
+    let units_of = |wanted: &[UnitKind]| {
+        let mut units = kinds
+            .iter()
+            .filter(|(kind, _)| wanted.contains(kind))
+            .map(|(_, unit)| describe(unit))
+            .collect::<Vec<_>>();
+        units.dedup();
+        units
+    };
+    let mut reasons = vec![];
+    for (kind, unit) in &kinds {
+        let unit = unit.to_string();
+        match kind {
+            UnitKind::Ambiguous => reasons.push(UnmergedReason::AmbiguousUnit { unit }),
+            UnitKind::Unknown => reasons.push(UnmergedReason::UnknownUnit { unit }),
+            _ => {}
+        }
+    }
+
+    // One unit stands for each dimension, e.g. "oz and cup"
+    let mut by_dimension: Vec<(UnitKind, &str)> = vec![];
+    for (kind, unit) in &kinds {
+        if let Some(dimension) = kind.dimension()
+            && !by_dimension.iter().any(|(d, _)| *d == dimension)
+        {
+            by_dimension.push((dimension, unit));
+        }
+    }
+    if by_dimension.len() > 1 {
+        reasons.push(UnmergedReason::IncompatibleDimensions {
+            units: by_dimension
+                .iter()
+                .map(|(_, unit)| describe(unit))
+                .collect(),
+        });
+    }
+
+    let counted = units_of(&[UnitKind::Count, UnitKind::Custom]);
+    if counted.len() > 1 && !units_of(&[UnitKind::Custom]).is_empty() {
+        reasons.push(UnmergedReason::DifferentCustomUnits { units: counted });
+    } else if counted.len() > 1 {
+        reasons.push(UnmergedReason::DifferentCountUnits { units: counted });
+    }
+    reasons.dedup();
+    reasons
+}
+
+/// A unit as shown in a reason, with bare counts written as "each".
+fn describe(unit: &str) -> String {
+    match unit {
+        "" => "each".to_string(),
+        unit => unit.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Merger, UnitRegistry,
+        ingredient::Ingredient,
+        options::{AmbiguousUnits, MergeOptions},
+    };
+
+    use super::*;
+
+    fn report(ingredients: &[Ingredient]) -> MergeReport {
+        Merger::new(MergeOptions::default()).merge_with_diagnostics(ingredients)
+    }
+
+    #[test]
+    fn merged_lists_have_no_diagnostics() {
+        let report = report(&[
+            Ingredient::new("milk", 1.0, "cup"),
+            Ingredient::new("milk", 4.0, "tbsp"),
+        ]);
+        assert_eq!(report.items.len(), 1);
+        assert!(report.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn weights_and_volumes_without_a_density() {
+        let report = report(&[
+            Ingredient::new("tofu", 8.0, "oz"),
+            Ingredient::new("tofu", 1.0, "cup"),
+        ]);
+        assert_eq!(report.diagnostics.len(), 1);
+        let diagnostic = &report.diagnostics[0];
+        assert_eq!(diagnostic.name, "tofu");
+        assert_eq!(diagnostic.units.len(), 2);
+        assert!(matches!(
+            &diagnostic.reasons[..],
+            [UnmergedReason::IncompatibleDimensions { units }] if units.len() == 2
+        ));
+    }
+
+    #[test]
+    fn unknown_units() {
+        let report = report(&[
+            Ingredient::new("rice", 1.0, "cup"),
+            Ingredient::new("rice", 1.0, "handful"),
+        ]);
+        assert_eq!(
+            report.diagnostics[0].reasons,
+            vec![UnmergedReason::UnknownUnit {
+                unit: "handful".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn different_count_units() {
+        let report = report(&[
+            Ingredient::new("garlic", 3.0, "clove"),
+            Ingredient::new("garlic", 1.0, "head"),
+        ]);
+        assert!(matches!(
+            &report.diagnostics[0].reasons[..],
+            [UnmergedReason::DifferentCountUnits { units }] if units.len() == 2
+        ));
+    }
+
+    #[test]
+    fn different_custom_units() {
+        let registry = UnitRegistry::from_toml(
+            r#"
+            [knob]
+            canonical = "knob"
+            fuzzy = ["knobs"]
+            [pat]
+            canonical = "pat"
+            fuzzy = ["pats"]
+            "#,
+        )
+        .unwrap();
+        let report = Merger::with_registry(MergeOptions::default(), registry)
+            .merge_with_diagnostics(&[
+                Ingredient::new("butter", 2.0, "knobs"),
+                Ingredient::new("butter", 1.0, "pat"),
+            ]);
+        assert!(matches!(
+            &report.diagnostics[0].reasons[..],
+            [UnmergedReason::DifferentCustomUnits { units }] if units.len() == 2
+        ));
+    }
+
+    #[test]
+    fn ambiguous_units_kept_apart() {
+        let options = MergeOptions {
+            ambiguous_units: AmbiguousUnits::KeepApart,
+            ..Default::default()
+        };
+        let report = Merger::new(options).merge_with_diagnostics(&[
+            Ingredient::new("milk", 8.0, "oz"),
+            Ingredient::new("milk", 1.0, "cup"),
+        ]);
+        assert_eq!(
+            report.diagnostics[0].reasons,
+            vec![UnmergedReason::AmbiguousUnit {
+                unit: "oz".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn resolved_units_are_not_ambiguous() {
+        let report = report(&[
+            Ingredient::new("tofu", 8.0, "oz"),
+            Ingredient::new("tofu", 1.0, "cup"),
+        ]);
+        assert!(matches!(
+            &report.diagnostics[0].reasons[..],
+            [UnmergedReason::IncompatibleDimensions { .. }]
+        ));
+    }
+
+    #[test]
+    fn reasons_read_as_hints() {
+        let reason = UnmergedReason::IncompatibleDimensions {
+            units: vec!["oz".to_string(), "cup".to_string()],
+        };
+        assert_eq!(
+            reason.to_string(),
+            "oz and cup measure different things; add a density to merge them"
+        );
+    }
+}
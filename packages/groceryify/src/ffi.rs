@@ -0,0 +1,293 @@
+//! A C ABI over the merger, for native callers such as mobile apps or Python's `ctypes`.
+//!
+//! Ingredients and options go in and come out as UTF-8 JSON in the same shapes the wasm build
+//! uses. Every function returns a [`GroceryifyStatus`] and, through `out`, a string the caller
+//! owns: the result on success, or an error message otherwise. Free it with
+//! [`groceryify_string_free`]. The header is `include/groceryify.h`.
+
+use std::{
+    ffi::{CStr, CString, c_char},
+    panic::{self, AssertUnwindSafe},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    formatting::{FormatOptions, format_ingredients},
+    ingredient::Ingredient,
+    locale::{Locale, LocaleError},
+    options::MergeOptions,
+};
+
+/// The outcome of a call. Anything but `Ok` leaves an error message in `out`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GroceryifyStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    NullArgument = 1,
+    /// A string wasn't UTF-8, or JSON didn't have the expected shape.
+    InvalidInput = 2,
+    /// An ingredient line couldn't be parsed.
+    ParseError = 3,
+    UnknownLocale = 4,
+    /// The library panicked. This is a bug; the message says where.
+    Panic = 5,
+}
+
+struct Failure(GroceryifyStatus, String);
+
+/// Merges like ingredients into a grocery list.
+///
+/// `ingredients` is a JSON array of ingredients and `options` JSON merge options, or null for
+/// the defaults. On success `out` receives a JSON array of grocery items.
+///
+/// # Safety
+/// `ingredients` and a non-null `options` must be NUL-terminated strings, and `out` must point
+/// to writable storage for a pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn groceryify_merge(
+    ingredients: *const c_char,
+    options: *const c_char,
+    out: *mut *mut c_char,
+) -> GroceryifyStatus {
+    // SAFETY: the caller upholds the contract above
+    unsafe {
+        respond(out, || {
+            let ingredients: Vec<Ingredient> = from_json(required(ingredients)?)?;
+            let options: MergeOptions =
+                optional(options)?.map_or(Ok(Default::default()), from_json)?;
+            options.locale().map_err(unknown_locale)?;
+            to_json(&crate::merge_with_options(&ingredients, &options))
+        })
+    }
+}
+
+/// Parses an ingredient line such as "2 cups flour, sifted".
+///
+/// `locale` is the code of the bundled locale the line is written in, or null for English. On
+/// success `out` receives the ingredient as a JSON object.
+///
+/// # Safety
+/// `line` and a non-null `locale` must be NUL-terminated strings, and `out` must point to
+/// writable storage for a pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn groceryify_parse(
+    line: *const c_char,
+    locale: *const c_char,
+    out: *mut *mut c_char,
+) -> GroceryifyStatus {
+    // SAFETY: the caller upholds the contract above
+    unsafe {
+        respond(out, || {
+            let line = required(line)?;
+            let locale = Locale::lookup(optional(locale)?).map_err(unknown_locale)?;
+            let ingredient = crate::parse_ingredient_in(line, locale)
+                .map_err(|e| Failure(GroceryifyStatus::ParseError, e.to_string()))?;
+            to_json(&ingredient)
+        })
+    }
+}
+
+/// Renders ingredients' amounts and units for display.
+///
+/// `ingredients` is a JSON array of ingredients and `options` JSON format options, or null for
+/// the defaults. On success `out` receives a JSON array of the ingredients with their
+/// `amountDisplay`, `unitDisplay` and `display` strings.
+///
+/// # Safety
+/// `ingredients` and a non-null `options` must be NUL-terminated strings, and `out` must point
+/// to writable storage for a pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn groceryify_format(
+    ingredients: *const c_char,
+    options: *const c_char,
+    out: *mut *mut c_char,
+) -> GroceryifyStatus {
+    // SAFETY: the caller upholds the contract above
+    unsafe {
+        respond(out, || {
+            let ingredients: Vec<Ingredient> = from_json(required(ingredients)?)?;
+            let options: FormatOptions =
+                optional(options)?.map_or(Ok(Default::default()), from_json)?;
+            options.locale().map_err(unknown_locale)?;
+            to_json(&format_ingredients(&ingredients, &options))
+        })
+    }
+}
+
+/// Frees a string returned through `out`. Null is ignored.
+///
+/// # Safety
+/// `string` must be null or a string from this library that hasn't been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn groceryify_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the string came from `CString::into_raw` in `respond`
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Runs `f`, writing its result or error to `out` and turning panics into an error, since
+/// unwinding into C is undefined behaviour.
+unsafe fn respond(
+    out: *mut *mut c_char,
+    f: impl FnOnce() -> Result<String, Failure>,
+) -> GroceryifyStatus {
+    if out.is_null() {
+        return GroceryifyStatus::NullArgument;
+    }
+    let result = panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(Failure(GroceryifyStatus::Panic, message))
+    });
+    let (status, text) = match result {
+        Ok(text) => (GroceryifyStatus::Ok, text),
+        Err(Failure(status, message)) => (status, message),
+    };
+    // JSON and error messages never contain NUL, but drop any rather than fail
+    let text = CString::new(text.replace('\0', "")).unwrap_or_default();
+    // SAFETY: `out` is non-null and the caller guarantees it is writable
+    unsafe { *out = text.into_raw() };
+    status
+}
+
+/// Reads a string argument that must be given.
+unsafe fn required<'a>(string: *const c_char) -> Result<&'a str, Failure> {
+    // SAFETY: forwarded from the caller
+    unsafe { optional(string) }?.ok_or_else(|| {
+        Failure(
+            GroceryifyStatus::NullArgument,
+            "a required argument was null".to_string(),
+        )
+    })
+}
+
+/// Reads a string argument that may be null.
+unsafe fn optional<'a>(string: *const c_char) -> Result<Option<&'a str>, Failure> {
+    if string.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: the caller guarantees a non-null string is NUL-terminated
+    unsafe { CStr::from_ptr(string) }
+        .to_str()
+        .map(Some)
+        .map_err(|e| Failure(GroceryifyStatus::InvalidInput, e.to_string()))
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, Failure> {
+    serde_json::from_str(json).map_err(|e| Failure(GroceryifyStatus::InvalidInput, e.to_string()))
+}
+
+fn unknown_locale(error: LocaleError) -> Failure {
+    Failure(GroceryifyStatus::UnknownLocale, error.to_string())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, Failure> {
+    serde_json::to_string(value).map_err(|e| Failure(GroceryifyStatus::InvalidInput, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, ptr};
+
+    use super::*;
+
+    /// Calls an FFI function like C would, returning its status and the string it wrote.
+    fn call(
+        f: unsafe extern "C" fn(*const c_char, *const c_char, *mut *mut c_char) -> GroceryifyStatus,
+        first: Option<&str>,
+        second: Option<&str>,
+    ) -> (GroceryifyStatus, String) {
+        let first = first.map(|s| CString::new(s).unwrap());
+        let second = second.map(|s| CString::new(s).unwrap());
+        let as_ptr = |s: &Option<CString>| s.as_ref().map_or(ptr::null(), |s| s.as_ptr());
+        let mut out = ptr::null_mut();
+        unsafe {
+            let status = f(as_ptr(&first), as_ptr(&second), &mut out);
+            let text = CStr::from_ptr(out).to_str().unwrap().to_string();
+            groceryify_string_free(out);
+            (status, text)
+        }
+    }
+
+    #[test]
+    fn merge_returns_grocery_items() {
+        let (status, json) = call(
+            groceryify_merge,
+            Some(
+                r#"[{"name":"flour","amount":1,"unit":"cup"},{"name":"flour","amount":2,"unit":"cup"}]"#,
+            ),
+            None,
+        );
+        assert_eq!(status, GroceryifyStatus::Ok);
+        let items: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(items[0]["amount"], 3.0);
+        assert_eq!(items[0]["category"], "pantry");
+    }
+
+    #[test]
+    fn parse_reads_a_line() {
+        let (status, json) = call(groceryify_parse, Some("2 cups flour, sifted"), None);
+        assert_eq!(status, GroceryifyStatus::Ok);
+        let ingredient: Ingredient = serde_json::from_str(&json).unwrap();
+        assert_eq!(ingredient.name, "flour");
+        assert_eq!(ingredient.note.as_deref(), Some("sifted"));
+    }
+
+    #[test]
+    fn format_renders_display_strings() {
+        let (status, json) = call(
+            groceryify_format,
+            Some(r#"[{"name":"sugar","amount":0.5,"unit":"cup"}]"#),
+            Some(r#"{"unicodeFractions":false}"#),
+        );
+        assert_eq!(status, GroceryifyStatus::Ok);
+        let items: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(items[0]["display"], "1/2 cup sugar");
+    }
+
+    #[test]
+    fn errors_come_back_with_a_status_and_message() {
+        let (status, message) = call(groceryify_merge, Some("not json"), None);
+        assert_eq!(status, GroceryifyStatus::InvalidInput);
+        assert!(!message.is_empty());
+        let (status, _) = call(groceryify_merge, None, None);
+        assert_eq!(status, GroceryifyStatus::NullArgument);
+        let (status, message) = call(groceryify_parse, Some("2 cups flour"), Some("xx"));
+        assert_eq!(status, GroceryifyStatus::UnknownLocale);
+        assert_eq!(message, "Unknown locale: xx");
+        let (status, _) = call(groceryify_parse, Some(""), None);
+        assert_eq!(status, GroceryifyStatus::ParseError);
+    }
+
+    #[test]
+    fn a_null_out_pointer_is_rejected() {
+        let line = CString::new("1 egg").unwrap();
+        let status = unsafe { groceryify_parse(line.as_ptr(), ptr::null(), ptr::null_mut()) };
+        assert_eq!(status, GroceryifyStatus::NullArgument);
+    }
+
+    #[test]
+    fn header_is_what_cbindgen_generates() {
+        let crate_dir = env!("CARGO_MANIFEST_DIR");
+        let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml")).unwrap();
+        let mut generated = Vec::new();
+        cbindgen::Builder::new()
+            .with_crate(crate_dir)
+            .with_config(config)
+            .generate()
+            .unwrap()
+            .write(&mut generated);
+        let header = fs::read_to_string(format!("{crate_dir}/include/groceryify.h")).unwrap();
+        assert_eq!(
+            String::from_utf8(generated).unwrap(),
+            header,
+            "include/groceryify.h is out of date; regenerate it as cbindgen.toml describes"
+        );
+    }
+}
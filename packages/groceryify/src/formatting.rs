@@ -0,0 +1,507 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ingredient::Ingredient,
+    ingredient_unit::{canonical_unit, plural_unit},
+    locale::{Locale, LocaleError},
+};
+
+/// Denominators a cook can actually measure, smallest first so simpler fractions win ties.
+const KITCHEN_DENOMINATORS: [u32; 5] = [2, 3, 4, 8, 16];
+
+/// Units that read better as decimals ("1.5 kg") than as fractions.
+const METRIC_UNITS: [&str; 4] = ["g", "kg", "ml", "l"];
+
+/// Controls how amounts are rendered.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormatOptions {
+    /// Largest fraction denominator to round to (e.g. 4 for quarters). Values below 2 render
+    /// decimals instead of fractions.
+    pub max_denominator: u32,
+    /// Decimal places used for metric units and for amounts too small for a fraction.
+    pub decimal_places: usize,
+    /// Render fractions as single glyphs ("½") rather than ASCII ("1/2").
+    pub unicode_fractions: bool,
+    /// Code of the bundled locale to write units, plurals and decimals in, such as "es" or "de".
+    /// English when unset; check an unknown code with [`FormatOptions::locale`], as formatting
+    /// falls back to English for one.
+    pub locale: Option<String>,
+}
+
+impl FormatOptions {
+    /// The locale to write amounts in, or [`LocaleError::Unknown`] if no bundled locale has the
+    /// code.
+    pub fn locale(&self) -> Result<&'static Locale, LocaleError> {
+        Locale::lookup(self.locale.as_deref())
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            max_denominator: 8,
+            decimal_places: 2,
+            unicode_fractions: true,
+            locale: None,
+        }
+    }
+}
+
+/// An ingredient, or an item wrapping one, together with its human-readable rendering.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Formatted<T> {
+    #[serde(flatten)]
+    pub item: T,
+    /// The amount alone, e.g. "1 ½".
+    pub amount_display: String,
+    /// The unit, pluralized to agree with the amount, e.g. "cups".
+    pub unit_display: String,
+    /// The whole line, e.g. "1 ½ cups flour, sifted".
+    pub display: String,
+}
+
+pub type FormattedIngredient = Formatted<Ingredient>;
+
+impl<T> AsRef<T> for Formatted<T> {
+    fn as_ref(&self) -> &T {
+        &self.item
+    }
+}
+
+fn fraction_glyph(numerator: u32, denominator: u32) -> Option<&'static str> {
+    match (numerator, denominator) {
+        (1, 2) => Some("½"),
+        (1, 3) => Some("⅓"),
+        (2, 3) => Some("⅔"),
+        (1, 4) => Some("¼"),
+        (3, 4) => Some("¾"),
+        (1, 8) => Some("⅛"),
+        (3, 8) => Some("⅜"),
+        (5, 8) => Some("⅝"),
+        (7, 8) => Some("⅞"),
+        _ => None,
+    }
+}
+
+/// Finds the kitchen fraction closest to `fraction`, which must be in `0.0..1.0`.
+fn nearest_fraction(fraction: f64, max_denominator: u32) -> (u32, u32) {
+    let mut best = (fraction.round() as u32, 1);
+    let mut best_error = (fraction - fraction.round()).abs();
+    for denominator in KITCHEN_DENOMINATORS
+        .into_iter()
+        .filter(|d| *d <= max_denominator)
+    {
+        let numerator = (fraction * denominator as f64).round();
+        let error = (fraction - numerator / denominator as f64).abs();
+        if error < best_error - f64::EPSILON {
+            best = (numerator as u32, denominator);
+            best_error = error;
+        }
+    }
+    best
+}
+
+fn format_decimal(amount: f64, decimal_places: usize) -> String {
+    let formatted = format!("{:.*}", decimal_places, amount);
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+fn is_metric(unit: &str) -> bool {
+    canonical_unit(unit).is_some_and(|u| METRIC_UNITS.contains(&u.as_str()))
+}
+
+/// Renders an amount, returning the text along with the value it was rounded to.
+fn render_amount(amount: f64, unit: &str, options: &FormatOptions) -> (String, f64) {
+    let as_decimal = || {
+        let text = format_decimal(amount, options.decimal_places);
+        let value = text.parse().unwrap_or(amount);
+        if Locale::resolve(options.locale.as_deref()).decimal_comma() {
+            (text.replace('.', ","), value)
+        } else {
+            (text, value)
+        }
+    };
+    if !amount.is_finite() || amount < 0.0 || options.max_denominator < 2 || is_metric(unit) {
+        return as_decimal();
+    }
+
+    let mut whole = amount.trunc() as u64;
+    let (mut numerator, denominator) = nearest_fraction(amount.fract(), options.max_denominator);
+    if numerator >= denominator {
+        whole += 1;
+        numerator = 0;
+    }
+    if whole == 0 && numerator == 0 {
+        // Too small for any kitchen fraction, so show what there actually is.
+        return as_decimal();
+    }
+
+    let value = whole as f64 + numerator as f64 / denominator as f64;
+    let fraction = match fraction_glyph(numerator, denominator) {
+        Some(glyph) if options.unicode_fractions => glyph.to_string(),
+        _ => format!("{numerator}/{denominator}"),
+    };
+    let text = match (whole, numerator) {
+        (whole, 0) => whole.to_string(),
+        (0, _) => fraction,
+        (whole, _) => format!("{whole} {fraction}"),
+    };
+    (text, value)
+}
+
+/// Renders an amount as a kitchen fraction ("1 ½", "⅓") or, for metric units, as a short decimal.
+///
+/// # Arguments:
+/// * `amount`: The amount to render.
+/// * `unit`: The unit the amount is in, used to decide between fractions and decimals.
+/// * `options`: Formatting options.
+///
+/// # Returns:
+/// * The rendered amount.
+pub fn format_amount(amount: f64, unit: &str, options: &FormatOptions) -> String {
+    render_amount(amount, unit, options).0
+}
+
+/// Rounds an amount the way [`format_amount`] would display it, e.g. 0.33 cup -> ⅓ cup.
+///
+/// # Arguments:
+/// * `amount`: The amount to round.
+/// * `unit`: The unit the amount is in, used to decide between fractions and decimals.
+/// * `options`: Formatting options.
+///
+/// # Returns:
+/// * The rounded amount.
+pub fn round_amount(amount: f64, unit: &str, options: &FormatOptions) -> f64 {
+    render_amount(amount, unit, options).1
+}
+
+/// Picks the singular or plural form of a unit. Known units use the locale's name or their table
+/// entry; other units have their head noun pluralized if it isn't plural already.
+fn unit_display(unit: &str, plural: bool, locale: &Locale) -> String {
+    if let (Some(canonical), Some(plural_form)) = (canonical_unit(unit), plural_unit(unit)) {
+        return match locale.unit_name(&canonical, plural) {
+            Some(name) => name.to_string(),
+            None if plural => plural_form,
+            None => canonical,
+        };
+    }
+    if plural {
+        locale.pluralize_name(unit)
+    } else {
+        unit.to_string()
+    }
+}
+
+fn format_with<T: AsRef<Ingredient> + Clone>(
+    item: &T,
+    options: &FormatOptions,
+    locale: &Locale,
+) -> Formatted<T> {
+    let ingredient = item.as_ref();
+    if ingredient.to_taste {
+        let note = ingredient
+            .note
+            .as_deref()
+            .unwrap_or_else(|| locale.to_taste_label());
+        return Formatted {
+            item: item.clone(),
+            amount_display: String::new(),
+            unit_display: String::new(),
+            display: format!("{}, {note}", ingredient.name),
+        };
+    }
+    let (mut amount_display, mut rounded) =
+        render_amount(ingredient.amount, &ingredient.unit, options);
+    // Ranges read "2–3 cloves", agreeing in number with their upper end.
+    if let Some(max) = ingredient.amount_max.filter(|max| *max > ingredient.amount) {
+        let (max_display, max_rounded) = render_amount(max, &ingredient.unit, options);
+        if max_display != amount_display {
+            amount_display = format!("{amount_display}–{max_display}");
+            rounded = max_rounded;
+        }
+    }
+    let plural = rounded > 1.0;
+    let unit_display = unit_display(&ingredient.unit, plural, locale);
+    // Merged names are singular, so count-only items get their name pluralized instead.
+    let name = if unit_display.is_empty() && plural {
+        locale.pluralize_name(&ingredient.name)
+    } else {
+        ingredient.name.clone()
+    };
+    let mut display = [
+        amount_display.as_str(),
+        unit_display.as_str(),
+        name.as_str(),
+    ]
+    .into_iter()
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join(" ");
+    if let Some(note) = &ingredient.note {
+        display = format!("{display}, {note}");
+    }
+    Formatted {
+        item: item.clone(),
+        amount_display,
+        unit_display,
+        display,
+    }
+}
+
+/// Renders an amount and unit together, e.g. "1 ½ cups" or "⅓ cup".
+///
+/// # Arguments:
+/// * `amount`: The amount to render.
+/// * `unit`: The unit, which is pluralized to agree with the rounded amount.
+/// * `options`: Formatting options.
+///
+/// # Returns:
+/// * The rendered quantity.
+pub fn format_quantity(amount: f64, unit: &str, options: &FormatOptions) -> String {
+    let locale = Locale::resolve(options.locale.as_deref());
+    let (formatted_amount, rounded) = render_amount(amount, unit, options);
+    match unit_display(unit, rounded > 1.0, locale) {
+        unit if unit.is_empty() => formatted_amount,
+        unit => format!("{formatted_amount} {unit}"),
+    }
+}
+
+/// Renders a list of ingredients for display, e.g. the output of [`crate::merge`].
+///
+/// # Arguments:
+/// * `ingredients`: List of ingredients.
+/// * `options`: Formatting options.
+///
+/// # Returns:
+/// * The ingredients, each with its rendered amount, unit and line.
+pub fn format_ingredients(
+    ingredients: &[Ingredient],
+    options: &FormatOptions,
+) -> Vec<FormattedIngredient> {
+    format_items(ingredients, options)
+}
+
+/// Renders a list of items wrapping ingredients, e.g. the output of [`crate::merge_with_options`],
+/// keeping each item alongside its rendering.
+///
+/// # Arguments:
+/// * `items`: List of items.
+/// * `options`: Formatting options.
+///
+/// # Returns:
+/// * The items, each with its ingredient's rendered amount, unit and line.
+pub fn format_items<T: AsRef<Ingredient> + Clone>(
+    items: &[T],
+    options: &FormatOptions,
+) -> Vec<Formatted<T>> {
+    let locale = Locale::resolve(options.locale.as_deref());
+    items
+        .iter()
+        .map(|i| format_with(i, options, locale))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> FormatOptions {
+        FormatOptions::default()
+    }
+
+    fn format_one(ingredient: Ingredient) -> FormattedIngredient {
+        format_ingredients(&[ingredient], &defaults()).remove(0)
+    }
+
+    // --- amounts ---
+
+    #[test]
+    fn whole_numbers_have_no_fraction() {
+        assert_eq!(format_amount(2.0, "cup", &defaults()), "2");
+    }
+
+    #[test]
+    fn float_noise_rounds_to_kitchen_fractions() {
+        assert_eq!(format_amount(0.3333333, "cup", &defaults()), "⅓");
+        assert_eq!(format_amount(1.4999999, "tsp", &defaults()), "1 ½");
+        assert_eq!(format_amount(2.74, "cup", &defaults()), "2 ¾");
+    }
+
+    #[test]
+    fn nearly_whole_amounts_carry_over() {
+        assert_eq!(format_amount(1.98, "cup", &defaults()), "2");
+    }
+
+    #[test]
+    fn ascii_fractions_when_unicode_disabled() {
+        let options = FormatOptions {
+            unicode_fractions: false,
+            ..defaults()
+        };
+        assert_eq!(format_amount(1.5, "cup", &options), "1 1/2");
+    }
+
+    #[test]
+    fn max_denominator_limits_precision() {
+        let options = FormatOptions {
+            max_denominator: 4,
+            ..defaults()
+        };
+        assert_eq!(format_amount(0.15, "cup", &options), "¼");
+        assert_eq!(format_amount(0.15, "cup", &defaults()), "⅛");
+    }
+
+    #[test]
+    fn fractions_without_glyphs_fall_back_to_ascii() {
+        let options = FormatOptions {
+            max_denominator: 16,
+            ..defaults()
+        };
+        assert_eq!(format_amount(0.0625, "tsp", &options), "1/16");
+    }
+
+    #[test]
+    fn metric_units_use_decimals() {
+        assert_eq!(format_amount(236.5882, "ml", &defaults()), "236.59");
+        assert_eq!(format_amount(1.5, "kg", &defaults()), "1.5");
+        assert_eq!(format_amount(250.0, "grams", &defaults()), "250");
+    }
+
+    #[test]
+    fn tiny_amounts_fall_back_to_decimals() {
+        assert_eq!(format_amount(0.02, "cup", &defaults()), "0.02");
+    }
+
+    #[test]
+    fn max_denominator_below_two_uses_decimals() {
+        let options = FormatOptions {
+            max_denominator: 1,
+            ..defaults()
+        };
+        assert_eq!(format_amount(1.3333, "cup", &options), "1.33");
+    }
+
+    // --- units ---
+
+    #[test]
+    fn known_units_pluralize_from_table() {
+        assert_eq!(format_quantity(1.5, "cup", &defaults()), "1 ½ cups");
+        assert_eq!(format_quantity(0.3333, "cups", &defaults()), "⅓ cup");
+        assert_eq!(format_quantity(1.0, "cup", &defaults()), "1 cup");
+    }
+
+    #[test]
+    fn abbreviations_do_not_pluralize() {
+        assert_eq!(format_quantity(3.0, "tbsp", &defaults()), "3 tbsp");
+        assert_eq!(format_quantity(2.0, "fl oz", &defaults()), "2 fl oz");
+    }
+
+    #[test]
+    fn custom_units_pluralize_by_rule() {
+        assert_eq!(format_quantity(2.0, "pinch", &defaults()), "2 pinches");
+        assert_eq!(format_quantity(3.0, "cloves", &defaults()), "3 cloves");
+        assert_eq!(format_quantity(1.0, "clove", &defaults()), "1 clove");
+    }
+
+    #[test]
+    fn rounding_decides_plurality() {
+        // 1.01 rounds to 1, so the unit stays singular
+        assert_eq!(format_quantity(1.01, "cup", &defaults()), "1 cup");
+    }
+
+    // --- ingredients ---
+
+    #[test]
+    fn formats_full_line_with_note() {
+        let mut ingredient = Ingredient::new("flour", 1.5, "cup");
+        ingredient.note = Some("sifted".to_string());
+        let formatted = format_one(ingredient);
+        assert_eq!(formatted.amount_display, "1 ½");
+        assert_eq!(formatted.unit_display, "cups");
+        assert_eq!(formatted.display, "1 ½ cups flour, sifted");
+    }
+
+    #[test]
+    fn unitless_counts_pluralize_the_name() {
+        assert_eq!(
+            format_one(Ingredient::new("egg", 3.0, "")).display,
+            "3 eggs"
+        );
+        assert_eq!(format_one(Ingredient::new("egg", 1.0, "")).display, "1 egg");
+    }
+
+    // --- locales ---
+
+    fn spanish() -> FormatOptions {
+        FormatOptions {
+            locale: Some("es".to_string()),
+            ..defaults()
+        }
+    }
+
+    #[test]
+    fn locale_names_units_and_writes_decimal_commas() {
+        let formatted = format_ingredients(
+            &[
+                Ingredient::new("harina", 2.0, "cup"),
+                Ingredient::new("patata", 1.5, "kg"),
+            ],
+            &spanish(),
+        );
+        assert_eq!(formatted[0].display, "2 tazas harina");
+        assert_eq!(formatted[1].display, "1,5 kg patata");
+        assert_eq!(format_quantity(1.0, "tbsp", &spanish()), "1 cucharada");
+    }
+
+    #[test]
+    fn locale_pluralizes_the_head_noun() {
+        let formatted =
+            format_ingredients(&[Ingredient::new("diente de ajo", 3.0, "")], &spanish());
+        assert_eq!(formatted[0].display, "3 dientes de ajo");
+    }
+
+    // --- ranges and to-taste items ---
+
+    #[test]
+    fn ranges_agree_with_their_upper_end() {
+        let formatted = format_one(Ingredient::new("garlic", 1.0, "clove").with_max(2.0));
+        assert_eq!(formatted.amount_display, "1–2");
+        assert_eq!(formatted.display, "1–2 cloves garlic");
+        let formatted = format_one(Ingredient::new("milk", 0.5, "cup").with_max(0.75));
+        assert_eq!(formatted.display, "½–¾ cup milk");
+    }
+
+    #[test]
+    fn ranges_that_round_together_show_one_amount() {
+        let formatted = format_one(Ingredient::new("milk", 1.0, "cup").with_max(1.01));
+        assert_eq!(formatted.amount_display, "1");
+    }
+
+    #[test]
+    fn to_taste_items_have_no_amount() {
+        let formatted = format_one(Ingredient::new_to_taste("salt"));
+        assert_eq!(formatted.amount_display, "");
+        assert_eq!(formatted.unit_display, "");
+        assert_eq!(formatted.display, "salt, to taste");
+        let formatted = format_ingredients(&[Ingredient::new_to_taste("sal")], &spanish());
+        assert_eq!(formatted[0].display, "sal, al gusto");
+    }
+
+    #[test]
+    fn serializes_flattened_with_camel_case_fields() {
+        let formatted = format_one(Ingredient::new("milk", 0.5, "cup"));
+        let json = toml::Value::try_from(&formatted).unwrap();
+        assert_eq!(json["name"].as_str(), Some("milk"));
+        assert_eq!(json["amountDisplay"].as_str(), Some("½"));
+        assert_eq!(json["unitDisplay"].as_str(), Some("cup"));
+    }
+}
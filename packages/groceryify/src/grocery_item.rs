@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    category::OTHER_CATEGORY, density::DensityConversion, ingredient::Ingredient,
+    package::Purchase, pantry::PantryDeduction, parsed_ingredient::ParsedIngredient,
+};
+
+fn default_category() -> String {
+    OTHER_CATEGORY.to_string()
+}
+
+/// One ingredient that went into a merged grocery item, as it was written.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceQuantity {
+    /// The ingredient's source tag, if it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub amount: f64,
+    pub unit: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_max: Option<f64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub to_taste: bool,
+    /// The unit a unit of both a volume and a weight was read as, e.g. "fl oz" for "oz" of milk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreted_as: Option<String>,
+}
+
+impl From<&Ingredient> for SourceQuantity {
+    fn from(value: &Ingredient) -> Self {
+        Self {
+            source: value.source.clone(),
+            amount: value.amount,
+            unit: value.unit.clone(),
+            amount_max: value.amount_max,
+            to_taste: value.to_taste,
+            interpreted_as: None,
+        }
+    }
+}
+
+/// A line of a merged grocery list: the merged ingredient plus what was done to produce it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroceryItem {
+    #[serde(flatten)]
+    pub ingredient: Ingredient,
+    /// Present when weights and volumes were added together through the ingredient's density.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub density_conversion: Option<DensityConversion>,
+    /// Present when on-hand pantry quantities were subtracted from the item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pantry: Option<PantryDeduction>,
+    /// Present when the list was rounded to the packages the item is sold in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purchase: Option<Purchase>,
+    /// The store section the item is shelved in, e.g. "produce".
+    #[serde(default = "default_category")]
+    pub category: String,
+    /// Every ingredient merged into this item, in the order they were merged.
+    #[serde(default)]
+    pub sources: Vec<SourceQuantity>,
+}
+
+impl AsRef<GroceryItem> for GroceryItem {
+    fn as_ref(&self) -> &GroceryItem {
+        self
+    }
+}
+
+impl AsRef<Ingredient> for GroceryItem {
+    fn as_ref(&self) -> &Ingredient {
+        &self.ingredient
+    }
+}
+
+impl From<ParsedIngredient> for GroceryItem {
+    fn from(value: ParsedIngredient) -> Self {
+        let density_conversion = value.density.clone();
+        let sources = value.sources.clone();
+        Self {
+            ingredient: value.into(),
+            density_conversion,
+            pantry: None,
+            purchase: None,
+            category: default_category(),
+            sources,
+        }
+    }
+}
@@ -0,0 +1,495 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grocery_item::GroceryItem, ingredient::Ingredient, merger::Merger, options::MergeOptions,
+    parsed_ingredient::ParsedIngredient,
+};
+
+/// The ingredients one source, such as a recipe, added to a [`GroceryList`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Batch {
+    pub source: String,
+    pub ingredients: Vec<Ingredient>,
+}
+
+/// Changes made to an item by hand, kept while its ingredients come and go.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ItemEdit {
+    /// Ticked off, e.g. already in the cart.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub checked: bool,
+    /// Taken off the list, e.g. because it is already at home.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub removed: bool,
+}
+
+impl ItemEdit {
+    fn is_empty(&self) -> bool {
+        !self.checked && !self.removed
+    }
+}
+
+/// A line of a [`GroceryList`], with whether it has been checked off.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItem {
+    /// Names the line for edits. Lines of the same ingredient in units that don't add up, such
+    /// as cloves and heads of garlic, have different keys.
+    pub key: String,
+    #[serde(flatten)]
+    pub item: GroceryItem,
+    pub checked: bool,
+}
+
+impl AsRef<GroceryItem> for ListItem {
+    fn as_ref(&self) -> &GroceryItem {
+        &self.item
+    }
+}
+
+impl AsRef<Ingredient> for ListItem {
+    fn as_ref(&self) -> &Ingredient {
+        &self.item.ingredient
+    }
+}
+
+/// What a [`GroceryList`] saves: its options, batches and edits. Items are merged again on load,
+/// so saved lists pick up changes to the bundled tables.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SavedGroceryList {
+    pub options: MergeOptions,
+    pub batches: Vec<Batch>,
+    /// Edits by item key.
+    pub edits: BTreeMap<String, ItemEdit>,
+}
+
+/// A grocery list that ingredients can be added to and removed from a batch at a time, such as a
+/// recipe at a time, keeping its merged totals and the edits made by hand up to date.
+///
+/// Adding a batch reads only its own ingredients, and removing one or editing an item reads
+/// none. Serializes to a [`SavedGroceryList`], and merges it when deserialized.
+#[derive(Deserialize)]
+#[serde(from = "SavedGroceryList")]
+pub struct GroceryList {
+    merger: Arc<Merger>,
+    batches: Vec<Batch>,
+    /// Each batch's ingredients combined among themselves, in the order of `batches`.
+    combined_batches: Vec<Vec<ParsedIngredient>>,
+    /// Every batch combined.
+    totals: Vec<ParsedIngredient>,
+    /// `totals` as grocery items, before edits.
+    merged: Vec<ListItem>,
+    edits: BTreeMap<String, ItemEdit>,
+    items: Vec<ListItem>,
+}
+
+impl GroceryList {
+    /// An empty list that merges with `options`.
+    pub fn new(options: MergeOptions) -> Self {
+        SavedGroceryList {
+            options,
+            ..Default::default()
+        }
+        .into()
+    }
+
+    pub fn options(&self) -> &MergeOptions {
+        self.merger.options()
+    }
+
+    /// Saved batches and edits, merged with `merger`, which may be shared with other lists and
+    /// callers, e.g. one an app keeps between commands.
+    pub fn with_merger(
+        merger: Arc<Merger>,
+        batches: Vec<Batch>,
+        edits: BTreeMap<String, ItemEdit>,
+    ) -> Self {
+        let combined_batches = batches
+            .iter()
+            .map(|b| merger.combine(merger.read(&b.ingredients)))
+            .collect();
+        let mut list = Self {
+            merger,
+            batches,
+            combined_batches,
+            totals: vec![],
+            merged: vec![],
+            edits,
+            items: vec![],
+        };
+        list.recombine();
+        list
+    }
+
+    /// Merges the list again with new options, e.g. after the user's synonyms change.
+    pub fn set_options(&mut self, options: MergeOptions) {
+        self.set_merger(Arc::new(Merger::new(options)));
+    }
+
+    /// Merges the list again with another merger, like [`GroceryList::set_options`].
+    pub fn set_merger(&mut self, merger: Arc<Merger>) {
+        self.merger = merger;
+        self.combined_batches = self
+            .batches
+            .iter()
+            .map(|b| self.merger.combine(self.merger.read(&b.ingredients)))
+            .collect();
+        self.recombine();
+    }
+
+    /// Adds a source's ingredients, replacing any it added before. Each ingredient is tagged with
+    /// `source`, which merged items report back.
+    pub fn add(&mut self, source: &str, ingredients: &[Ingredient]) {
+        let batch = Batch {
+            source: source.to_string(),
+            ingredients: ingredients
+                .iter()
+                .map(|i| i.clone().with_source(source))
+                .collect(),
+        };
+        let combined = self.merger.combine(self.merger.read(&batch.ingredients));
+        match self.batches.iter().position(|b| b.source == source) {
+            Some(index) => {
+                self.batches[index] = batch;
+                self.combined_batches[index] = combined;
+                self.recombine();
+            }
+            None => {
+                self.batches.push(batch);
+                let totals = std::mem::take(&mut self.totals);
+                self.totals = self.merger.combine([totals, combined.clone()].concat());
+                self.combined_batches.push(combined);
+                self.update();
+            }
+        }
+    }
+
+    /// Removes a source's ingredients, returning whether it had added any.
+    pub fn remove(&mut self, source: &str) -> bool {
+        let Some(index) = self.batches.iter().position(|b| b.source == source) else {
+            return false;
+        };
+        self.batches.remove(index);
+        self.combined_batches.remove(index);
+        self.recombine();
+        true
+    }
+
+    pub fn contains(&self, source: &str) -> bool {
+        self.batches.iter().any(|b| b.source == source)
+    }
+
+    /// The batches, in the order they were first added.
+    pub fn batches(&self) -> &[Batch] {
+        &self.batches
+    }
+
+    /// The merged items, without those removed by hand.
+    pub fn items(&self) -> &[ListItem] {
+        &self.items
+    }
+
+    /// Checks an item off, or back on. Items are named by their [`ListItem::key`].
+    pub fn set_checked(&mut self, key: &str, checked: bool) {
+        self.edit(key, |edit| edit.checked = checked);
+    }
+
+    /// Takes an item off the list, or puts it back. The item stays off while its ingredients
+    /// change, until it is put back or [`GroceryList::clear_edits`] is called.
+    pub fn set_removed(&mut self, key: &str, removed: bool) {
+        self.edit(key, |edit| edit.removed = removed);
+    }
+
+    /// The edits made by hand, by item key.
+    pub fn edits(&self) -> &BTreeMap<String, ItemEdit> {
+        &self.edits
+    }
+
+    /// Forgets every edit made by hand.
+    pub fn clear_edits(&mut self) {
+        self.edits.clear();
+        self.apply_edits();
+    }
+
+    /// Removes every batch and edit.
+    pub fn clear(&mut self) {
+        self.batches.clear();
+        self.combined_batches.clear();
+        self.edits.clear();
+        self.recombine();
+    }
+
+    fn edit(&mut self, key: &str, f: impl FnOnce(&mut ItemEdit)) {
+        let edit = self.edits.entry(key.to_string()).or_default();
+        f(edit);
+        if edit.is_empty() {
+            self.edits.remove(key);
+        }
+        self.apply_edits();
+    }
+
+    /// Combines the batches' own totals again, after one was replaced or removed.
+    fn recombine(&mut self) {
+        self.totals = self.merger.combine(self.combined_batches.concat());
+        self.update();
+    }
+
+    /// Turns the totals into items and applies the edits.
+    fn update(&mut self) {
+        let keys = self
+            .totals
+            .iter()
+            .map(|i| item_key(&self.merger, i))
+            .collect::<Vec<_>>();
+        self.merged = keys
+            .into_iter()
+            .zip(self.merger.finish(self.totals.clone()))
+            .map(|(key, item)| ListItem {
+                key,
+                item,
+                checked: false,
+            })
+            .collect();
+        self.apply_edits();
+    }
+
+    fn apply_edits(&mut self) {
+        self.items = self
+            .merged
+            .iter()
+            .filter_map(|item| {
+                let edit = self.edits.get(&item.key);
+                if edit.is_some_and(|e| e.removed) {
+                    return None;
+                }
+                Some(ListItem {
+                    checked: edit.is_some_and(|e| e.checked),
+                    ..item.clone()
+                })
+            })
+            .collect();
+    }
+}
+
+/// The name of a combined ingredient, with the unit it is added up in when there is one, e.g.
+/// "garlic (clove)". Volumes of an ingredient with a density share the key of its weights, so a
+/// line keeps its key when a batch adds the other.
+fn item_key(merger: &Merger, ingredient: &ParsedIngredient) -> String {
+    let unit = match ingredient.quantity.unit_key() {
+        "l" if merger.densities().lookup(&ingredient.name).is_some() => "kg",
+        unit => unit,
+    };
+    match unit {
+        "" => ingredient.name.clone(),
+        unit => format!("{} ({unit})", ingredient.name),
+    }
+}
+
+impl Default for GroceryList {
+    fn default() -> Self {
+        Self::new(MergeOptions::default())
+    }
+}
+
+impl From<SavedGroceryList> for GroceryList {
+    fn from(saved: SavedGroceryList) -> Self {
+        Self::with_merger(
+            Arc::new(Merger::new(saved.options)),
+            saved.batches,
+            saved.edits,
+        )
+    }
+}
+
+impl Serialize for GroceryList {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The shape of a `SavedGroceryList`, borrowed rather than cloned
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Saving<'a> {
+            options: &'a MergeOptions,
+            batches: &'a [Batch],
+            edits: &'a BTreeMap<String, ItemEdit>,
+        }
+        Saving {
+            options: self.options(),
+            batches: &self.batches,
+            edits: &self.edits,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::UnitSystem;
+
+    fn pancakes() -> Vec<Ingredient> {
+        vec![
+            Ingredient::new("flour", 1.0, "cup"),
+            Ingredient::new("eggs", 2.0, ""),
+        ]
+    }
+
+    fn cookies() -> Vec<Ingredient> {
+        vec![
+            Ingredient::new("flour", 2.0, "cups"),
+            Ingredient::new("butter", 0.5, "cup"),
+        ]
+    }
+
+    fn amount(list: &GroceryList, name: &str) -> Option<f64> {
+        list.items()
+            .iter()
+            .find(|i| i.item.ingredient.name == name)
+            .map(|i| i.item.ingredient.amount)
+    }
+
+    #[test]
+    fn batches_add_to_the_totals_and_come_back_off() {
+        let mut list = GroceryList::default();
+        list.add("pancakes", &pancakes());
+        list.add("cookies", &cookies());
+        assert_eq!(amount(&list, "flour"), Some(3.0));
+        assert!(list.remove("pancakes"));
+        assert_eq!(amount(&list, "flour"), Some(2.0));
+        assert_eq!(amount(&list, "egg"), None);
+        assert!(!list.remove("pancakes"));
+    }
+
+    #[test]
+    fn adding_a_source_again_replaces_it() {
+        let mut list = GroceryList::default();
+        list.add("pancakes", &pancakes());
+        list.add("pancakes", &[Ingredient::new("flour", 3.0, "cups")]);
+        assert_eq!(list.batches().len(), 1);
+        assert_eq!(amount(&list, "flour"), Some(3.0));
+        assert_eq!(amount(&list, "egg"), None);
+    }
+
+    #[test]
+    fn items_report_their_sources() {
+        let mut list = GroceryList::default();
+        list.add("pancakes", &pancakes());
+        list.add("cookies", &cookies());
+        let flour = list
+            .items()
+            .iter()
+            .find(|i| i.item.ingredient.name == "flour")
+            .unwrap();
+        let mut sources = flour
+            .item
+            .sources
+            .iter()
+            .filter_map(|s| s.source.as_deref())
+            .collect::<Vec<_>>();
+        sources.sort();
+        assert_eq!(sources, vec!["cookies", "pancakes"]);
+    }
+
+    #[test]
+    fn edits_survive_changes_to_the_batches() {
+        let mut list = GroceryList::default();
+        list.add("pancakes", &pancakes());
+        list.set_checked("flour (kg)", true);
+        list.set_removed("egg", true);
+        list.add("cookies", &cookies());
+        list.add("bread", &[Ingredient::new("flour", 500.0, "g")]);
+        let flour = list
+            .items()
+            .iter()
+            .find(|i| i.item.ingredient.name == "flour")
+            .unwrap();
+        assert!(flour.checked);
+        assert_eq!(amount(&list, "egg"), None);
+        list.set_removed("egg", false);
+        assert_eq!(amount(&list, "egg"), Some(2.0));
+        assert_eq!(list.edits().len(), 1);
+    }
+
+    #[test]
+    fn edits_apply_to_one_line_of_an_ingredient() {
+        let mut list = GroceryList::default();
+        list.add(
+            "roast",
+            &[
+                Ingredient::new("garlic", 2.0, "cloves"),
+                Ingredient::new("garlic", 1.0, "head"),
+            ],
+        );
+        list.set_checked("garlic (clove)", true);
+        list.set_removed("garlic (head)", true);
+        assert_eq!(list.items().len(), 1);
+        assert_eq!(list.items()[0].item.ingredient.unit, "clove");
+        assert!(list.items()[0].checked);
+    }
+
+    #[test]
+    fn adding_batches_one_at_a_time_matches_merging_them_together() {
+        let mut list = GroceryList::default();
+        list.add("pancakes", &pancakes());
+        list.add("cookies", &cookies());
+        list.add("bread", &[Ingredient::new("flour", 500.0, "g")]);
+        list.remove("cookies");
+        list.add("cookies", &cookies());
+        let all = [
+            pancakes(),
+            cookies(),
+            vec![Ingredient::new("flour", 500.0, "g")],
+        ]
+        .concat();
+        let merged = Merger::new(MergeOptions::default()).merge(&all);
+        assert_eq!(list.items().len(), merged.len());
+        for item in &merged {
+            let amount = amount(&list, &item.ingredient.name).unwrap();
+            assert!((amount - item.ingredient.amount).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn new_options_merge_the_list_again() {
+        let mut list = GroceryList::default();
+        list.add("pancakes", &[Ingredient::new("milk", 1.0, "cup")]);
+        list.set_options(MergeOptions {
+            unit_system: UnitSystem::Metric,
+            ..Default::default()
+        });
+        assert_eq!(list.items()[0].item.ingredient.unit, "ml");
+    }
+
+    #[test]
+    fn lists_restored_with_a_shared_merger_keep_their_edits() {
+        let merger = Arc::new(Merger::new(MergeOptions::default()));
+        let mut list = GroceryList::with_merger(merger.clone(), vec![], BTreeMap::new());
+        list.add("pancakes", &pancakes());
+        list.set_checked("egg", true);
+        let restored =
+            GroceryList::with_merger(merger, list.batches().to_vec(), list.edits().clone());
+        assert_eq!(restored.items().len(), 2);
+        assert!(restored.items().iter().any(|i| i.key == "egg" && i.checked));
+    }
+
+    #[test]
+    fn round_trips_through_serde() {
+        let mut list = GroceryList::new(MergeOptions {
+            locale: Some("en".to_string()),
+            ..Default::default()
+        });
+        list.add("pancakes", &pancakes());
+        list.set_checked("egg", true);
+        let saved = toml::to_string(&list).unwrap();
+        let loaded: GroceryList = toml::from_str(&saved).unwrap();
+        assert_eq!(loaded.batches()[0].source, "pancakes");
+        assert_eq!(loaded.batches()[0].ingredients.len(), 2);
+        assert_eq!(loaded.edits(), list.edits());
+        assert_eq!(loaded.options().locale.as_deref(), Some("en"));
+        assert_eq!(loaded.items().len(), 2);
+        assert!(loaded.items().iter().any(|i| i.checked));
+    }
+}
@@ -1,28 +1,36 @@
-use crate::{ingredient_unit::Quantity, parsed_ingredient::ParsedIngredient};
+use crate::{
+    ingredient_unit::{Quantity, base_units_per},
+    parsed_ingredient::ParsedIngredient,
+};
 
-use measurements::{Mass, Measurement, Volume};
 use serde::{Deserialize, Serialize};
 
 fn convert_from_base(base_amount: f64, base_unit: &str, original_unit: &str) -> f64 {
-    let one_target_in_base = match base_unit {
-        "l" => format!("1 {original_unit}")
-            .parse::<Volume>()
-            .unwrap()
-            .as_base_units(),
-        "kg" => format!("1 {original_unit}")
-            .parse::<Mass>()
-            .unwrap()
-            .as_base_units(),
-        _ => return base_amount, // unknown dimension, give up
-    };
-    base_amount / one_target_in_base
+    match base_units_per(base_unit, original_unit) {
+        Some(one_target_in_base) => base_amount / one_target_in_base,
+        None => base_amount, // unknown dimension, give up
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Ingredient {
     pub name: String,
     pub amount: f64,
     pub unit: String,
+    /// Upper end of a range such as "2–3 cloves", whose lower end is `amount`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount_max: Option<f64>,
+    /// Set for ingredients used without a measured amount, such as "salt to taste" or "oil for
+    /// frying". Their amount and unit are not used.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub to_taste: bool,
+    /// Preparation note such as "sifted" or "softened", kept apart from the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Where the ingredient came from, such as a recipe, reported back on merged grocery items.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 impl Ingredient {
@@ -31,30 +39,77 @@ impl Ingredient {
             name: String::from(name),
             amount,
             unit: String::from(unit),
+            amount_max: None,
+            to_taste: false,
+            note: None,
+            source: None,
+        }
+    }
+
+    /// An ingredient used without a measured amount, e.g. "salt to taste".
+    pub fn new_to_taste(name: &str) -> Self {
+        Self {
+            to_taste: true,
+            ..Self::new(name, 0.0, "")
+        }
+    }
+
+    /// Makes the ingredient a range from its amount up to `amount_max`, e.g. "2–3 cloves".
+    pub fn with_max(self, amount_max: f64) -> Self {
+        Self {
+            amount_max: Some(amount_max),
+            ..self
         }
     }
+
+    /// Tags the ingredient with where it came from.
+    pub fn with_source(self, source: &str) -> Self {
+        Self {
+            source: Some(source.to_string()),
+            ..self
+        }
+    }
+}
+
+impl AsRef<Ingredient> for Ingredient {
+    fn as_ref(&self) -> &Ingredient {
+        self
+    }
+}
+
+/// The amount of a quantity in its display unit, with that unit.
+fn display_amount(quantity: Quantity) -> (f64, String) {
+    match quantity {
+        Quantity::Known {
+            amount,
+            unit_key,
+            original_unit,
+        } => (
+            convert_from_base(amount, &unit_key, &original_unit),
+            original_unit,
+        ),
+        Quantity::Custom { amount, unit } => (amount, unit),
+    }
 }
 
 impl From<ParsedIngredient> for Ingredient {
     fn from(value: ParsedIngredient) -> Self {
-        match value.quantity {
-            Quantity::Known {
-                amount,
-                unit_key,
-                original_unit,
-            } => {
-                let display_amount = convert_from_base(amount, &unit_key, &original_unit);
-                Self {
-                    name: value.name,
-                    amount: display_amount,
-                    unit: original_unit,
-                }
-            }
-            Quantity::Custom { amount, unit } => Self {
-                name: value.name,
-                amount,
-                unit,
-            },
+        let note = (!value.notes.is_empty()).then(|| value.notes.join(", "));
+        if value.to_taste {
+            return Self {
+                note,
+                ..Self::new_to_taste(&value.name)
+            };
+        }
+        let (amount, unit) = display_amount(value.quantity);
+        Self {
+            name: value.name,
+            amount,
+            unit,
+            amount_max: value.max.map(|max| display_amount(max).0),
+            to_taste: false,
+            note,
+            source: None,
         }
     }
 }
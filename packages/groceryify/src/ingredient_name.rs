@@ -23,6 +23,57 @@ impl IngredientName {
         IngredientName(String::from(self.0.split(',').collect::<Vec<&str>>()[0]))
     }
 
+    /// Returns the contents of each top-level parenthesized group, trimmed, skipping empty groups.
+    pub fn parenthesized(&self) -> Vec<String> {
+        let mut groups = vec![];
+        let mut current = String::new();
+        let mut depth: u32 = 0;
+
+        for ch in self.0.chars() {
+            match ch {
+                '(' => {
+                    if depth > 0 {
+                        current.push(ch);
+                    }
+                    depth += 1;
+                }
+                ')' if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 {
+                        groups.push(current.trim().to_string());
+                        current.clear();
+                    } else {
+                        current.push(ch);
+                    }
+                }
+                _ if depth > 0 => current.push(ch),
+                _ => {}
+            }
+        }
+        if depth > 0 {
+            groups.push(current.trim().to_string());
+        }
+
+        groups.into_iter().filter(|g| !g.is_empty()).collect()
+    }
+
+    /// Returns the trimmed text after the first comma, if there is any.
+    pub fn after_comma(&self) -> Option<String> {
+        self.0
+            .split_once(',')
+            .map(|(_, rest)| rest.trim().to_string())
+            .filter(|rest| !rest.is_empty())
+    }
+
+    /// Returns the name and each shorter name it ends with, longest first, e.g. "red onion" and
+    /// "onion", for looking names up in tables of plainer names.
+    pub fn suffixes(&self) -> Vec<String> {
+        let words = self.0.split_whitespace().collect::<Vec<_>>();
+        (0..words.len())
+            .map(|start| words[start..].join(" "))
+            .collect()
+    }
+
     pub fn lemmatize(&self, lemmatizer: &Lemmatizer) -> Self {
         IngredientName(
             self.0
@@ -150,6 +201,65 @@ mod tests {
         assert_eq!(inner(name("").remove_after_comma()), "");
     }
 
+    // --- parenthesized ---
+
+    #[test]
+    fn test_parenthesized_basic() {
+        assert_eq!(name("butter (softened)").parenthesized(), vec!["softened"]);
+    }
+
+    #[test]
+    fn test_parenthesized_keeps_nested_groups_whole() {
+        assert_eq!(
+            name("butter (unsalted (grass-fed))").parenthesized(),
+            vec!["unsalted (grass-fed)"]
+        );
+    }
+
+    #[test]
+    fn test_parenthesized_multiple_groups_and_empty() {
+        assert_eq!(
+            name("egg (large) yolk () (beaten)").parenthesized(),
+            vec!["large", "beaten"]
+        );
+    }
+
+    #[test]
+    fn test_parenthesized_unmatched_open() {
+        assert_eq!(name("salt (fine").parenthesized(), vec!["fine"]);
+    }
+
+    // --- after_comma ---
+
+    #[test]
+    fn test_after_comma_basic() {
+        assert_eq!(
+            name("onion, peeled, chopped").after_comma(),
+            Some("peeled, chopped".to_string())
+        );
+    }
+
+    #[test]
+    fn test_after_comma_none() {
+        assert_eq!(name("garlic").after_comma(), None);
+        assert_eq!(name("garlic, ").after_comma(), None);
+    }
+
+    // --- suffixes ---
+
+    #[test]
+    fn test_suffixes_longest_first() {
+        assert_eq!(
+            name("dark brown sugar").suffixes(),
+            vec!["dark brown sugar", "brown sugar", "sugar"]
+        );
+    }
+
+    #[test]
+    fn test_suffixes_empty_string() {
+        assert!(name("  ").suffixes().is_empty());
+    }
+
     // --- lemmatize ---
 
     #[test]
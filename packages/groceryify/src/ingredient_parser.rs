@@ -0,0 +1,646 @@
+use std::error::Error;
+
+use crate::{
+    ingredient::Ingredient, ingredient_name::IngredientName, ingredient_unit::UnitRegistry,
+    locale::Locale,
+};
+
+/// Longest unit name, in words, that is looked up in the unit tables (e.g. "fluid ounces").
+const MAX_UNIT_WORDS: usize = 3;
+
+#[derive(Debug, PartialEq)]
+pub enum ParseIngredientError {
+    Empty,
+    /// The line starts with a number that isn't an amount, e.g. "1/0" or "1.5.2".
+    InvalidAmount(String),
+    MissingName(String),
+}
+
+impl std::fmt::Display for ParseIngredientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Ingredient line is empty"),
+            Self::InvalidAmount(line) => write!(f, "Invalid amount in: {line}"),
+            Self::MissingName(line) => write!(f, "No ingredient name found in: {line}"),
+        }
+    }
+}
+
+impl Error for ParseIngredientError {}
+
+fn vulgar_fraction(ch: char) -> Option<&'static str> {
+    match ch {
+        '½' => Some("1/2"),
+        '⅓' => Some("1/3"),
+        '⅔' => Some("2/3"),
+        '¼' => Some("1/4"),
+        '¾' => Some("3/4"),
+        '⅕' => Some("1/5"),
+        '⅖' => Some("2/5"),
+        '⅗' => Some("3/5"),
+        '⅘' => Some("4/5"),
+        '⅙' => Some("1/6"),
+        '⅚' => Some("5/6"),
+        '⅛' => Some("1/8"),
+        '⅜' => Some("3/8"),
+        '⅝' => Some("5/8"),
+        '⅞' => Some("7/8"),
+        _ => None,
+    }
+}
+
+/// Reads commas between digits as decimal points, e.g. "1,5 kg" -> "1.5 kg".
+fn decimal_commas_to_points(line: &str) -> String {
+    let chars = line.chars().collect::<Vec<_>>();
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, ch)| {
+            let between_digits = i > 0
+                && chars[i - 1].is_ascii_digit()
+                && chars.get(i + 1).is_some_and(char::is_ascii_digit);
+            if *ch == ',' && between_digits {
+                '.'
+            } else {
+                *ch
+            }
+        })
+        .collect()
+}
+
+/// Whether `token` is a dash joining the ends of a range, as in "2-3" or "2–3".
+fn is_dash(token: &str) -> bool {
+    matches!(token, "-" | "–" | "—")
+}
+
+/// Splits a line into whitespace separated tokens, expanding unicode fractions ("1½" -> "1 1/2"),
+/// splitting numbers glued to a unit ("200g" -> "200 g") and dashes glued between numbers
+/// ("2-3" -> "2 - 3").
+fn tokenize(line: &str) -> Vec<String> {
+    let mut expanded = String::new();
+    let chars = line.chars().collect::<Vec<_>>();
+    for (i, ch) in chars.iter().enumerate() {
+        let joins_numbers = || {
+            let before = chars[..i].iter().rev().find(|c| !c.is_whitespace());
+            let after = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            [before, after]
+                .into_iter()
+                .all(|c| c.is_some_and(|c| c.is_ascii_digit() || vulgar_fraction(*c).is_some()))
+        };
+        match vulgar_fraction(*ch) {
+            Some(fraction) => {
+                expanded.push(' ');
+                expanded.push_str(fraction);
+                expanded.push(' ');
+            }
+            None if *ch == '⁄' => expanded.push('/'),
+            None if is_dash(&ch.to_string()) && joins_numbers() => {
+                expanded.push_str(" - ");
+            }
+            None => expanded.push(*ch),
+        }
+    }
+
+    let mut tokens = vec![];
+    for token in expanded.split_whitespace() {
+        let split_at = token
+            .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '/'))
+            .unwrap_or(token.len());
+        let (number, rest) = token.split_at(split_at);
+        let unit_like =
+            rest.starts_with(char::is_alphabetic) && !rest.contains(|c: char| c.is_ascii_digit());
+        if !number.is_empty() && unit_like {
+            tokens.push(number.to_string());
+            tokens.push(rest.to_string());
+        } else {
+            tokens.push(token.to_string());
+        }
+    }
+    tokens
+}
+
+fn parse_number(token: &str) -> Option<f64> {
+    // Only plain decimals, so "1e3" and "inf" aren't read as numbers
+    let decimal = |part: &str| {
+        part.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && part.chars().all(|c| c.is_ascii_digit() || c == '.')
+    };
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        if !decimal(numerator) || !decimal(denominator) {
+            return None;
+        }
+        let numerator = numerator.parse::<f64>().ok()?;
+        let denominator = denominator.parse::<f64>().ok()?;
+        return (denominator != 0.0).then(|| numerator / denominator);
+    }
+    if !decimal(token) {
+        return None;
+    }
+    token.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Whether `token` starts like a number, signed or not, so failing to read it as an amount means
+/// the amount is malformed rather than missing.
+fn looks_like_number(token: &str) -> bool {
+    let unsigned = token.trim_start_matches(['-', '+']);
+    unsigned.starts_with(|c: char| c.is_ascii_digit())
+        || unsigned.starts_with('.') && unsigned[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Reads a leading amount such as "2", "0.5", "1/2" or "2 1/2", returning it with the number of
+/// tokens it used.
+fn parse_amount(tokens: &[String]) -> Option<(f64, usize)> {
+    let whole = parse_number(tokens.first()?)?;
+    if tokens[0].contains('/') {
+        return Some((whole, 1));
+    }
+    match tokens.get(1) {
+        Some(next) if next.contains('/') && looks_like_number(next) => match parse_number(next) {
+            Some(fraction) => Some((whole + fraction, 2)),
+            // A fraction glued to a word, as in "1 1/2-inch piece", belongs to the name
+            None if !next
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '.' || c == '/') =>
+            {
+                Some((whole, 1))
+            }
+            None => None,
+        },
+        _ => Some((whole, 1)),
+    }
+}
+
+/// The number of leading tokens that make up a parenthesized aside such as "(14 oz)", or 0 if
+/// the tokens don't start with one.
+fn parenthesized_len(tokens: &[String]) -> usize {
+    if !tokens.first().is_some_and(|t| t.starts_with('(')) {
+        return 0;
+    }
+    tokens
+        .iter()
+        .position(|t| t.ends_with(')'))
+        .map_or(0, |last| last + 1)
+}
+
+fn lookup_unit(candidate: &str, registry: &UnitRegistry) -> Option<String> {
+    let lowercase = candidate.to_lowercase();
+    registry
+        .canonical(candidate)
+        .or_else(|| registry.canonical(&lowercase))
+        .or_else(|| registry.canonical(lowercase.trim_end_matches('.')))
+        .map(str::to_string)
+}
+
+/// Matches the longest run of leading tokens that names a known unit, returning the canonical unit
+/// with the number of tokens it used.
+fn parse_unit(tokens: &[String], registry: &UnitRegistry) -> Option<(String, usize)> {
+    (1..=MAX_UNIT_WORDS.min(tokens.len()))
+        .rev()
+        .find_map(|words| {
+            lookup_unit(&tokens[..words].join(" "), registry).map(|unit| (unit, words))
+        })
+}
+
+/// Parses a free-text ingredient line such as "2 1/2 cups all-purpose flour, sifted".
+///
+/// Lines without a leading amount are read as a single item, and lines without a known volume,
+/// mass or count unit keep an empty unit (e.g. "3 large eggs"). Text after the first comma and text in
+/// parentheses is kept as the ingredient's note. Ranges such as "2-3 cloves" or "2 to 3 cloves"
+/// keep their upper end, whichever way round they are written, and lines with neither an amount
+/// nor a unit that end in a phrase such as "to taste" or "for frying" are read as used to taste.
+///
+/// # Arguments:
+/// * `line`: A single line from a recipe's ingredient list.
+///
+/// # Returns:
+/// * The parsed ingredient, or an error if the line has no ingredient name or starts with a
+///   number that isn't an amount.
+pub fn parse_ingredient(line: &str) -> Result<Ingredient, ParseIngredientError> {
+    parse_line(line, UnitRegistry::bundled(), Locale::english())
+}
+
+/// Parses a free-text ingredient line like [`parse_ingredient`], recognizing the units of
+/// `registry`, e.g. one loaded with the user's own units.
+pub fn parse_ingredient_with(
+    line: &str,
+    registry: &UnitRegistry,
+) -> Result<Ingredient, ParseIngredientError> {
+    parse_line(line, registry, Locale::english())
+}
+
+/// Parses a free-text ingredient line like [`parse_ingredient`], written in `locale`: its unit
+/// spellings, articles ("una taza") and decimal commas ("1,5 kg") are understood. Units keep
+/// their bundled canonical spelling, e.g. "2 cucharadas de aceite" has the unit "tbsp".
+pub fn parse_ingredient_in(
+    line: &str,
+    locale: &Locale,
+) -> Result<Ingredient, ParseIngredientError> {
+    parse_line(line, locale.unit_registry(), locale)
+}
+
+fn parse_line(
+    line: &str,
+    registry: &UnitRegistry,
+    locale: &Locale,
+) -> Result<Ingredient, ParseIngredientError> {
+    let tokens = if locale.decimal_comma() {
+        tokenize(&decimal_commas_to_points(line.trim()))
+    } else {
+        tokenize(line.trim())
+    };
+    if tokens.is_empty() {
+        return Err(ParseIngredientError::Empty);
+    }
+
+    let mut position = 0;
+    let mut amount = match parse_amount(&tokens) {
+        Some((amount, used)) => {
+            position += used;
+            Some(amount)
+        }
+        None if looks_like_number(&tokens[0]) => {
+            return Err(ParseIngredientError::InvalidAmount(line.trim().to_string()));
+        }
+        None => {
+            if locale.is_article(&tokens[0]) && parse_unit(&tokens[1..], registry).is_some() {
+                position += 1;
+            }
+            None
+        }
+    };
+    let mut amount_max = None;
+    if let Some(first) = amount
+        && tokens
+            .get(position)
+            .is_some_and(|t| is_dash(t) || locale.is_range_word(t))
+        && let Some((second, used)) = parse_amount(&tokens[position + 1..])
+    {
+        // Read a range written high to low, such as "3-2", the usual way round
+        amount = Some(first.min(second));
+        amount_max = (second != first).then_some(first.max(second));
+        position += 1 + used;
+    }
+
+    // A size in parentheses before the unit, as in "1 (14 oz) can tomatoes", is kept as a note
+    let size_len = parenthesized_len(&tokens[position..]);
+    let (unit, size) = match parse_unit(&tokens[position + size_len..], registry) {
+        Some((unit, used)) => {
+            let size = tokens[position..position + size_len].to_vec();
+            position += size_len + used;
+            (unit, size)
+        }
+        None => (String::new(), vec![]),
+    };
+    if tokens.get(position).is_some_and(|t| locale.is_connector(t)) {
+        position += 1;
+    }
+
+    let raw_name = IngredientName(
+        size.iter()
+            .chain(&tokens[position..])
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let without_parentheses = raw_name.remove_parenthesized();
+    let mut notes = raw_name.parenthesized();
+    notes.extend(without_parentheses.after_comma());
+
+    let mut name = without_parentheses
+        .remove_after_comma()
+        .0
+        .trim()
+        .to_string();
+    if let Some((rest, phrase)) = locale.strip_to_taste(&name)
+        && !rest.is_empty()
+    {
+        name = rest;
+        notes.insert(0, phrase.to_string());
+    }
+    if name.is_empty() {
+        return Err(ParseIngredientError::MissingName(line.trim().to_string()));
+    }
+
+    let note = (!notes.is_empty()).then(|| notes.join(", "));
+    let unmeasured = amount.is_none() && unit.is_empty();
+    if unmeasured
+        && notes.iter().any(|n| {
+            locale
+                .strip_to_taste(n)
+                .is_some_and(|(rest, _)| rest.is_empty())
+        })
+    {
+        return Ok(Ingredient {
+            note,
+            ..Ingredient::new_to_taste(&name)
+        });
+    }
+    Ok(Ingredient {
+        name,
+        amount: amount.unwrap_or(1.0),
+        unit,
+        amount_max,
+        to_taste: false,
+        note,
+        source: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Ingredient {
+        parse_ingredient(line).expect("line should parse")
+    }
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9
+    }
+
+    // --- ranges and to-taste items ---
+
+    #[test]
+    fn parses_ranges() {
+        for line in [
+            "2-3 cloves garlic",
+            "2–3 cloves garlic",
+            "2 to 3 cloves garlic",
+        ] {
+            let i = parse(line);
+            assert_eq!(i.amount, 2.0, "{line}");
+            assert_eq!(i.amount_max, Some(3.0), "{line}");
+            assert_eq!(i.unit, "clove", "{line}");
+            assert_eq!(i.name, "garlic", "{line}");
+        }
+        let i = parse("½-1 cup milk");
+        assert_eq!(i.amount, 0.5);
+        assert_eq!(i.amount_max, Some(1.0));
+    }
+
+    #[test]
+    fn reversed_ranges_are_read_low_to_high() {
+        for line in ["3-2 cloves garlic", "3 to 2 cloves garlic"] {
+            let i = parse(line);
+            assert_eq!(i.amount, 2.0, "{line}");
+            assert_eq!(i.amount_max, Some(3.0), "{line}");
+            assert_eq!(i.name, "garlic", "{line}");
+        }
+        let i = parse("2 to 2 eggs");
+        assert_eq!(i.amount, 2.0);
+        assert_eq!(i.amount_max, None);
+        assert_eq!(i.name, "eggs");
+    }
+
+    #[test]
+    fn parses_to_taste_items() {
+        for line in ["salt to taste", "Salt, to taste", "salt (to taste)"] {
+            let i = parse(line);
+            assert!(i.to_taste, "{line}");
+            assert_eq!(i.name.to_lowercase(), "salt", "{line}");
+            assert_eq!(i.note.as_deref(), Some("to taste"), "{line}");
+        }
+        let i = parse("vegetable oil for frying");
+        assert!(i.to_taste);
+        assert_eq!(i.name, "vegetable oil");
+    }
+
+    #[test]
+    fn measured_items_are_not_to_taste() {
+        let i = parse("2 cups oil for frying");
+        assert!(!i.to_taste);
+        assert_eq!(i.amount, 2.0);
+        assert_eq!(i.name, "oil");
+        assert_eq!(i.note.as_deref(), Some("for frying"));
+        assert!(!parse("1 tsp salt, to taste").to_taste);
+    }
+
+    #[test]
+    fn parses_ranges_and_to_taste_items_in_other_locales() {
+        let spanish = Locale::bundled("es").unwrap();
+        let i = parse_ingredient_in("2 a 3 dientes de ajo", spanish).unwrap();
+        assert_eq!(i.amount_max, Some(3.0));
+        assert_eq!(i.name, "ajo");
+        let i = parse_ingredient_in("sal al gusto", spanish).unwrap();
+        assert!(i.to_taste);
+        assert_eq!(i.name, "sal");
+        let german = Locale::bundled("de").unwrap();
+        let i = parse_ingredient_in("Salz nach Geschmack", german).unwrap();
+        assert!(i.to_taste);
+        assert_eq!(i.name, "Salz");
+    }
+
+    // --- amounts ---
+
+    #[test]
+    fn parses_mixed_number_unit_name_and_note() {
+        let i = parse("2 1/2 cups all-purpose flour, sifted");
+        assert_eq!(i.amount, 2.5);
+        assert_eq!(i.unit, "cup");
+        assert_eq!(i.name, "all-purpose flour");
+        assert_eq!(i.note.as_deref(), Some("sifted"));
+    }
+
+    #[test]
+    fn parses_unicode_fraction_attached_to_whole_number() {
+        let i = parse("1½ tbsp olive oil");
+        assert_eq!(i.amount, 1.5);
+        assert_eq!(i.unit, "tbsp");
+        assert_eq!(i.name, "olive oil");
+        assert_eq!(i.note, None);
+    }
+
+    #[test]
+    fn parses_lone_unicode_fraction() {
+        let i = parse("⅓ cup sugar");
+        assert!(approx_eq(i.amount, 1.0 / 3.0));
+        assert_eq!(i.unit, "cup");
+    }
+
+    #[test]
+    fn parses_decimal_and_plain_fraction() {
+        assert_eq!(parse("0.5 l milk").amount, 0.5);
+        assert_eq!(parse("3/4 tsp salt").amount, 0.75);
+    }
+
+    #[test]
+    fn missing_amount_defaults_to_one() {
+        let i = parse("salt");
+        assert_eq!(i.amount, 1.0);
+        assert_eq!(i.unit, "");
+        assert_eq!(i.name, "salt");
+    }
+
+    #[test]
+    fn article_before_unit_counts_as_one() {
+        let i = parse("a cup of milk");
+        assert_eq!(i.amount, 1.0);
+        assert_eq!(i.unit, "cup");
+        assert_eq!(i.name, "milk");
+    }
+
+    // --- units ---
+
+    #[test]
+    fn no_unit_keeps_descriptors_in_name() {
+        let i = parse("3 large eggs");
+        assert_eq!(i.amount, 3.0);
+        assert_eq!(i.unit, "");
+        assert_eq!(i.name, "large eggs");
+    }
+
+    #[test]
+    fn unit_glued_to_number() {
+        let i = parse("200g butter");
+        assert_eq!(i.amount, 200.0);
+        assert_eq!(i.unit, "g");
+        assert_eq!(i.name, "butter");
+    }
+
+    #[test]
+    fn multi_word_unit_is_matched() {
+        let i = parse("8 fluid ounces cream");
+        assert_eq!(i.unit, "fl oz");
+        assert_eq!(i.name, "cream");
+    }
+
+    #[test]
+    fn unit_lookup_ignores_case_and_trailing_period() {
+        assert_eq!(parse("2 Cups rice").unit, "cup");
+        assert_eq!(parse("1 lbs. beef").unit, "lb");
+    }
+
+    #[test]
+    fn count_units_are_matched() {
+        let i = parse("2 cloves garlic, minced");
+        assert_eq!(i.unit, "clove");
+        assert_eq!(i.name, "garlic");
+        let i = parse("a pinch of salt");
+        assert_eq!(i.amount, 1.0);
+        assert_eq!(i.unit, "pinch");
+        assert_eq!(i.name, "salt");
+    }
+
+    // --- names and notes ---
+
+    #[test]
+    fn parenthesized_text_becomes_note() {
+        let i = parse("1 cup butter (softened), divided");
+        assert_eq!(i.name, "butter");
+        assert_eq!(i.note.as_deref(), Some("softened, divided"));
+    }
+
+    #[test]
+    fn comma_inside_parentheses_does_not_split_name() {
+        let i = parse("1 cup flour (about 2, sifted) unbleached");
+        assert_eq!(i.name, "flour unbleached");
+        assert_eq!(i.note.as_deref(), Some("about 2, sifted"));
+    }
+
+    #[test]
+    fn units_come_from_the_given_registry() {
+        let registry = UnitRegistry::from_toml(
+            r#"
+            [knob]
+            canonical = "knob"
+            fuzzy = ["knobs"]
+            equals = "15 g"
+            "#,
+        )
+        .unwrap();
+        let i = parse_ingredient_with("2 knobs butter", &registry).unwrap();
+        assert_eq!(i.unit, "knob");
+        assert_eq!(i.name, "butter");
+        assert_eq!(parse("2 knobs butter").name, "knobs butter");
+    }
+
+    // --- locales ---
+
+    #[test]
+    fn spanish_units_articles_and_decimal_commas() {
+        let spanish = Locale::bundled("es").unwrap();
+        let i = parse_ingredient_in("1,5 tazas de harina, tamizada", spanish).unwrap();
+        assert_eq!(i.amount, 1.5);
+        assert_eq!(i.unit, "cup");
+        assert_eq!(i.name, "harina");
+        assert_eq!(i.note.as_deref(), Some("tamizada"));
+        let i = parse_ingredient_in("una pizca de sal", spanish).unwrap();
+        assert_eq!(i.amount, 1.0);
+        assert_eq!(i.unit, "pinch");
+        assert_eq!(i.name, "sal");
+    }
+
+    #[test]
+    fn german_units_glued_to_decimal_comma_amounts() {
+        let german = Locale::bundled("de").unwrap();
+        let i = parse_ingredient_in("0,5kg Kartoffeln", german).unwrap();
+        assert_eq!(i.amount, 0.5);
+        assert_eq!(i.unit, "kg");
+        assert_eq!(i.name, "Kartoffeln");
+        assert_eq!(
+            parse_ingredient_in("2 EL Zucker", german).unwrap().unit,
+            "tbsp"
+        );
+    }
+
+    // --- errors ---
+
+    #[test]
+    fn empty_line_is_an_error() {
+        assert!(matches!(
+            parse_ingredient("   "),
+            Err(ParseIngredientError::Empty)
+        ));
+    }
+
+    #[test]
+    fn fractions_glued_to_a_word_belong_to_the_name() {
+        let ingredient = parse("1 1/2-inch piece ginger");
+        assert_eq!(ingredient.amount, 1.0);
+        assert_eq!(ingredient.unit, "");
+        assert_eq!(ingredient.name, "1/2-inch piece ginger");
+    }
+
+    #[test]
+    fn sizes_in_parentheses_before_the_unit_are_notes() {
+        for line in ["1 (14 oz) can tomatoes", "1 can (14 oz) tomatoes"] {
+            let ingredient = parse(line);
+            assert_eq!(ingredient.amount, 1.0, "{line}");
+            assert_eq!(ingredient.unit, "can", "{line}");
+            assert_eq!(ingredient.name, "tomatoes", "{line}");
+            assert_eq!(ingredient.note.as_deref(), Some("14 oz"), "{line}");
+        }
+        // Without a unit after it, the aside stays a note of the name
+        let ingredient = parse("2 (large) eggs");
+        assert_eq!(ingredient.unit, "");
+        assert_eq!(ingredient.name, "eggs");
+        assert_eq!(ingredient.note.as_deref(), Some("large"));
+    }
+
+    #[test]
+    fn malformed_amounts_are_errors() {
+        for line in [
+            "-2 cups flour",
+            "1/0 cup sugar",
+            "1.5.2 cups flour",
+            "1e400 cups milk",
+            "1e3 cups milk",
+            "1,5 cups flour",
+            "2 1/0 cups flour",
+        ] {
+            assert_eq!(
+                parse_ingredient(line).unwrap_err(),
+                ParseIngredientError::InvalidAmount(line.to_string()),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn amount_without_name_is_an_error() {
+        assert!(matches!(
+            parse_ingredient("2 cups"),
+            Err(ParseIngredientError::MissingName(line)) if line == "2 cups"
+        ));
+    }
+}
@@ -4,12 +4,103 @@ use measurements::Measurement;
 
 use crate::{
     ingredient::Ingredient,
-    ingredient_unit::measurement_parser::{
-        ParsedMeasurement, ParsedMeasurementError, Units, canonical_from_fuzzy,
-    },
+    liquid::LiquidTable,
+    options::{DisplayUnit, UnitSystem},
 };
 
 mod measurement_parser;
+mod registry;
+
+pub(crate) use measurement_parser::{ParsedMeasurement, ParsedMeasurementError};
+pub use registry::{UnitRegistry, UnitRegistryError};
+
+/// Units that best-fit display may step up through, smallest first, each with the least amount
+/// that still reads well in that unit.
+const DISPLAY_LADDERS: [&[(&str, f64)]; 4] = [
+    &[
+        ("pinch", 0.0),
+        ("dash", 1.0),
+        ("tsp", 0.25),
+        ("tbsp", 1.0),
+        ("cup", 0.5),
+    ],
+    &[("ml", 0.0), ("l", 1.0)],
+    &[("oz", 0.0), ("lb", 1.0)],
+    &[("g", 0.0), ("kg", 1.0)],
+];
+
+/// Metric units, which every other volume or mass unit is US customary against.
+const METRIC_UNITS: [&str; 4] = ["ml", "l", "g", "kg"];
+
+/// Least amount a scaled quantity may have in its unit before a smaller unit is picked.
+const MIN_SCALED_AMOUNT: f64 = 0.25;
+
+/// Slack for float noise when comparing against ladder thresholds, e.g. 3 tsp vs 1 tbsp.
+const LADDER_TOLERANCE: f64 = 1e-9;
+
+/// Looks up the canonical spelling of a volume, mass or count unit, e.g. "tablespoons" -> "tbsp"
+/// or "cloves" -> "clove".
+pub fn canonical_unit(input: &str) -> Option<String> {
+    UnitRegistry::bundled().canonical(input).map(str::to_string)
+}
+
+/// Returns how many base units (litres or kilograms) one `unit` is worth, or `None` if `unit`
+/// is not a unit of the `unit_key` dimension. Count units with a volume equivalent, such as
+/// "stick", are worth that volume.
+pub fn base_units_per(unit_key: &str, unit: &str) -> Option<f64> {
+    UnitRegistry::bundled().base_units_per(unit_key, unit)
+}
+
+/// Picks the largest unit at or above `unit` on its ladder that still gives a readable amount.
+fn best_fit_unit(amount: f64, unit_key: &str, unit: &str) -> String {
+    let Some(ladder) = DISPLAY_LADDERS
+        .iter()
+        .find(|ladder| ladder.iter().any(|(u, _)| *u == unit))
+    else {
+        return unit.to_string();
+    };
+    ladder
+        .iter()
+        .skip_while(|(u, _)| *u != unit)
+        .filter(|(u, min_amount)| {
+            base_units_per(unit_key, u)
+                .is_some_and(|per| amount / per >= min_amount * (1.0 - LADDER_TOLERANCE))
+        })
+        .last()
+        .map_or(unit, |(u, _)| u)
+        .to_string()
+}
+
+/// Picks the unit for an amount that was scaled: best-fit like [`best_fit_unit`], except that an
+/// amount too small to read in its unit may move down its ladder (⅛ cup -> 2 tbsp).
+fn scaled_unit(amount: f64, unit_key: &str, unit: &str) -> String {
+    let stepped_up = best_fit_unit(amount, unit_key, unit);
+    let readable = base_units_per(unit_key, unit)
+        .is_some_and(|per| amount / per >= MIN_SCALED_AMOUNT * (1.0 - LADDER_TOLERANCE));
+    if stepped_up != unit || readable {
+        return stepped_up;
+    }
+    match DISPLAY_LADDERS
+        .iter()
+        .find(|ladder| ladder.iter().any(|(u, _)| *u == unit))
+    {
+        Some(ladder) => best_fit_unit(amount, unit_key, ladder[0].0),
+        None => unit.to_string(),
+    }
+}
+
+/// Looks up the plural display form of a volume, mass or count unit, e.g. "cup" -> "cups",
+/// "tbsp" -> "tbsp" and "pinch" -> "pinches".
+pub fn plural_unit(input: &str) -> Option<String> {
+    UnitRegistry::bundled().plural(input).map(str::to_string)
+}
+
+/// How to read a unit that spells both a volume and a weight, such as "oz".
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Reading {
+    Volume,
+    Mass,
+}
 
 #[derive(Clone, Debug)]
 pub enum Quantity {
@@ -25,20 +116,29 @@ pub enum Quantity {
 }
 
 impl Quantity {
+    /// Reads an ingredient's amount and unit with the bundled units, reading "oz" as fluid ounces
+    /// for liquids and as a weight otherwise.
     pub fn new(ingredient: &Ingredient) -> Self {
-        let vol_toml = include_str!("ingredient_unit/volume.toml");
-        let mass_toml = include_str!("ingredient_unit/mass.toml");
-        let units: Units = toml::from_str(format!("{} {}", vol_toml, mass_toml).as_str()).unwrap();
-        let parsed_measurement: Result<ParsedMeasurement, ParsedMeasurementError> =
-            <&Ingredient>::try_into(ingredient);
-        let original_unit = ingredient.unit.as_str();
-        match parsed_measurement {
-            Ok(measurement) => Self::Known {
+        let registry = UnitRegistry::bundled();
+        let reading = registry
+            .is_ambiguous(&ingredient.unit)
+            .then(|| LiquidTable::bundled().reading(&ingredient.name));
+        Self::with_registry(ingredient, registry, reading)
+    }
+
+    /// Reads an ingredient's amount and unit with `registry`'s units, and a unit that spells both
+    /// a volume and a weight as `reading` says. Quantities in units the registry doesn't know, or
+    /// ambiguous units without a reading, are custom and only add to the same unit.
+    pub(crate) fn with_registry(
+        ingredient: &Ingredient,
+        registry: &UnitRegistry,
+        reading: Option<Reading>,
+    ) -> Self {
+        match registry.measure_as(ingredient, reading) {
+            Ok((measurement, original_unit)) => Self::Known {
                 amount: measurement.as_base_units(),
-                unit_key: measurement.get_base_units_name().to_string(),
-                original_unit: canonical_from_fuzzy(&units, original_unit)
-                    .unwrap_or(original_unit)
-                    .to_string(),
+                unit_key: measurement.unit_key().to_string(),
+                original_unit,
             },
             Err(_) => Self::Custom {
                 amount: ingredient.amount,
@@ -47,6 +147,160 @@ impl Quantity {
         }
     }
 
+    /// Re-expresses a known volume or mass in the smallest unit of `system` if its display unit
+    /// belongs to the other system. Count and custom quantities are returned as-is.
+    pub fn in_unit_system(self, system: UnitSystem) -> Self {
+        let Quantity::Known {
+            amount,
+            unit_key,
+            original_unit,
+        } = self
+        else {
+            return self;
+        };
+        let is_metric = METRIC_UNITS.contains(&original_unit.as_str());
+        let target = match (system, unit_key.as_str()) {
+            (UnitSystem::Metric, "l") if !is_metric => Some("ml"),
+            (UnitSystem::Metric, "kg") if !is_metric => Some("g"),
+            (UnitSystem::UsCustomary, "l") if is_metric => Some("tsp"),
+            (UnitSystem::UsCustomary, "kg") if is_metric => Some("oz"),
+            _ => None,
+        };
+        Quantity::Known {
+            amount,
+            unit_key,
+            original_unit: target.map_or(original_unit, str::to_string),
+        }
+    }
+
+    /// Chooses the unit a known quantity is displayed in. Custom quantities are returned as-is.
+    pub fn with_display_unit(self, display_unit: &DisplayUnit) -> Self {
+        let Quantity::Known {
+            amount,
+            unit_key,
+            original_unit,
+        } = self
+        else {
+            return self;
+        };
+        let display = match display_unit {
+            DisplayUnit::Original => original_unit,
+            DisplayUnit::BestFit => best_fit_unit(amount, &unit_key, &original_unit),
+            DisplayUnit::Fixed(unit) => canonical_unit(unit)
+                .filter(|u| base_units_per(&unit_key, u).is_some())
+                .unwrap_or(original_unit),
+        };
+        Quantity::Known {
+            amount,
+            unit_key,
+            original_unit: display,
+        }
+    }
+
+    /// Displays a known quantity in the unit `other` is displayed in, if they measure the same
+    /// thing, so both ends of a range read in one unit. Otherwise the quantity is returned as-is.
+    pub fn with_unit_of(self, other: &Quantity) -> Self {
+        match (self, other) {
+            (
+                Quantity::Known {
+                    amount, unit_key, ..
+                },
+                Quantity::Known {
+                    unit_key: other_key,
+                    original_unit,
+                    ..
+                },
+            ) if unit_key == *other_key => Quantity::Known {
+                amount,
+                unit_key,
+                original_unit: original_unit.clone(),
+            },
+            (quantity, _) => quantity,
+        }
+    }
+
+    /// Multiplies the quantity by `factor`, re-picking the display unit of known quantities so
+    /// the amount stays readable (6 tsp -> 2 tbsp, ⅛ cup -> 2 tbsp).
+    pub fn scaled(self, factor: f64) -> Self {
+        match self {
+            Quantity::Known {
+                amount,
+                unit_key,
+                original_unit,
+            } => {
+                let amount = amount * factor;
+                let display = scaled_unit(amount, &unit_key, &original_unit);
+                Quantity::Known {
+                    amount,
+                    unit_key,
+                    original_unit: display,
+                }
+            }
+            Quantity::Custom { amount, unit } => Quantity::Custom {
+                amount: amount * factor,
+                unit,
+            },
+        }
+    }
+
+    /// Subtracts `other`, converting between volume and mass with `kg_per_litre` when given. The
+    /// difference keeps `self`'s units and may be negative.
+    pub fn try_subtract(
+        self,
+        other: Quantity,
+        kg_per_litre: Option<f64>,
+    ) -> Result<Quantity, (Quantity, Quantity)> {
+        let negated = match other {
+            Quantity::Known {
+                amount,
+                unit_key,
+                original_unit,
+            } => Quantity::Known {
+                amount: -amount,
+                unit_key,
+                original_unit,
+            },
+            Quantity::Custom { amount, unit } => Quantity::Custom {
+                amount: -amount,
+                unit,
+            },
+        };
+        match kg_per_litre {
+            Some(kg_per_litre) => self.try_add_with_density(negated, kg_per_litre),
+            None => self.try_add(negated),
+        }
+    }
+
+    /// The amount in the base units `like` is measured in, converting between volume and mass
+    /// with `kg_per_litre` when given, or `None` if the quantities don't convert.
+    pub(crate) fn amount_like(&self, like: &Quantity, kg_per_litre: Option<f64>) -> Option<f64> {
+        let empty = like.clone().scaled(0.0);
+        let sum = match kg_per_litre {
+            Some(kg_per_litre) => empty.try_add_with_density(self.clone(), kg_per_litre),
+            None => empty.try_add(self.clone()),
+        };
+        sum.ok().map(|sum| sum.amount())
+    }
+
+    /// Replaces a negative amount with zero.
+    pub fn at_least_zero(self) -> Self {
+        match self {
+            Quantity::Known {
+                amount,
+                unit_key,
+                original_unit,
+            } => Quantity::Known {
+                amount: amount.max(0.0),
+                unit_key,
+                original_unit,
+            },
+            Quantity::Custom { amount, unit } => Quantity::Custom {
+                amount: amount.max(0.0),
+                unit,
+            },
+        }
+    }
+
     pub fn kind_rank(&self) -> u8 {
         match self {
             Quantity::Known { .. } => 0,
@@ -119,6 +373,46 @@ impl Quantity {
             (a, b) => Err((a, b)),
         }
     }
+
+    /// Adds two known quantities of different dimensions (volume and mass) using a density in
+    /// kilograms per litre. The sum keeps `self`'s dimension and display unit. Quantities of the
+    /// same dimension are added as in [`Quantity::try_add`].
+    pub fn try_add_with_density(
+        self,
+        other: Quantity,
+        kg_per_litre: f64,
+    ) -> Result<Quantity, (Quantity, Quantity)> {
+        let converted = match (&self, &other) {
+            (
+                Quantity::Known { unit_key: k1, .. },
+                Quantity::Known {
+                    amount: a2,
+                    unit_key: k2,
+                    ..
+                },
+            ) if kg_per_litre > 0.0 => match (k1.as_str(), k2.as_str()) {
+                ("kg", "l") => Some(a2 * kg_per_litre),
+                ("l", "kg") => Some(a2 / kg_per_litre),
+                _ => None,
+            },
+            _ => None,
+        };
+        match (converted, self) {
+            (
+                Some(a2),
+                Quantity::Known {
+                    amount: a1,
+                    unit_key,
+                    original_unit,
+                },
+            ) => Ok(Quantity::Known {
+                amount: a1 + a2,
+                unit_key,
+                original_unit,
+            }),
+            (_, this) => this.try_add(other),
+        }
+    }
 }
 
 impl PartialEq for Quantity {
@@ -226,6 +520,35 @@ mod tests {
         assert!(matches!(q, Quantity::Custom { amount, .. } if amount == 0.125));
     }
 
+    #[test]
+    fn new_keys_count_units_by_canonical_unit() {
+        let q = Quantity::new(&Ingredient::new("garlic", 2.0, "cloves"));
+        assert!(matches!(
+            q,
+            Quantity::Known { amount, ref unit_key, ref original_unit }
+            if amount == 2.0 && unit_key == "clove" && original_unit == "clove"
+        ));
+    }
+
+    #[test]
+    fn new_converts_count_units_with_a_volume_equivalent() {
+        let q = Quantity::new(&Ingredient::new("butter", 1.0, "stick"));
+        let tbsp = base_units_per("l", "tbsp").unwrap();
+        assert!(matches!(
+            q,
+            Quantity::Known { amount, ref unit_key, ref original_unit }
+            if (amount - 8.0 * tbsp).abs() < 1e-12 && unit_key == "l" && original_unit == "stick"
+        ));
+    }
+
+    #[test]
+    fn count_equivalents_are_worth_their_volume() {
+        let tsp = base_units_per("l", "tsp").unwrap();
+        assert!((base_units_per("l", "pinch").unwrap() - tsp / 16.0).abs() < 1e-12);
+        assert!((base_units_per("l", "dash").unwrap() - tsp / 8.0).abs() < 1e-12);
+        assert_eq!(base_units_per("l", "clove"), None);
+    }
+
     #[test]
     fn new_known_amount_is_base_units_value() {
         let ing = Ingredient::new("x", 1.0, "ml");
@@ -321,6 +644,140 @@ mod tests {
         assert!(c > b);
     }
 
+    // display unit tests
+
+    fn known_in(amount: f64, unit: &str) -> Quantity {
+        Quantity::new(&Ingredient::new("x", amount, unit))
+    }
+
+    fn display_unit_of(q: Quantity) -> String {
+        match q {
+            Quantity::Known { original_unit, .. } => original_unit,
+            Quantity::Custom { unit, .. } => unit,
+        }
+    }
+
+    #[test]
+    fn best_fit_steps_up_to_readable_unit() {
+        let q = known_in(6.0, "tsp").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "tbsp");
+        let q = known_in(1500.0, "g").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "kg");
+        let q = known_in(16.0, "oz").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "lb");
+    }
+
+    #[test]
+    fn best_fit_keeps_unit_when_larger_one_is_not_readable() {
+        let q = known_in(2.0, "tsp").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "tsp");
+        let q = known_in(6.0, "tbsp").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "tbsp");
+    }
+
+    #[test]
+    fn best_fit_steps_up_from_pinches() {
+        let q = known_in(2.0, "pinch").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "dash");
+        let q = known_in(8.0, "pinch").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "tsp");
+    }
+
+    #[test]
+    fn best_fit_leaves_plain_counts_alone() {
+        let q = known_in(12.0, "clove").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "clove");
+    }
+
+    #[test]
+    fn best_fit_never_steps_down() {
+        let q = known_in(0.25, "cup").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "cup");
+    }
+
+    #[test]
+    fn best_fit_leaves_units_off_the_ladders_alone() {
+        let q = known_in(40.0, "fl oz").with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "fl oz");
+    }
+
+    #[test]
+    fn fixed_display_unit_is_canonicalized() {
+        let q = known_in(3.0, "tsp").with_display_unit(&DisplayUnit::Fixed("milliliters".into()));
+        assert_eq!(display_unit_of(q), "ml");
+    }
+
+    #[test]
+    fn fixed_display_unit_of_other_dimension_is_ignored() {
+        let q = known_in(3.0, "tsp").with_display_unit(&DisplayUnit::Fixed("g".into()));
+        assert_eq!(display_unit_of(q), "tsp");
+    }
+
+    #[test]
+    fn metric_system_converts_us_units() {
+        let q = known_in(2.0, "cup").in_unit_system(UnitSystem::Metric);
+        assert_eq!(display_unit_of(q), "ml");
+        let q = known_in(2.0, "lb").in_unit_system(UnitSystem::Metric);
+        assert_eq!(
+            display_unit_of(q.with_display_unit(&DisplayUnit::BestFit)),
+            "g"
+        );
+    }
+
+    #[test]
+    fn us_system_converts_metric_units() {
+        let q = known_in(500.0, "ml")
+            .in_unit_system(UnitSystem::UsCustomary)
+            .with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "cup");
+        let q = known_in(1.0, "kg")
+            .in_unit_system(UnitSystem::UsCustomary)
+            .with_display_unit(&DisplayUnit::BestFit);
+        assert_eq!(display_unit_of(q), "lb");
+    }
+
+    #[test]
+    fn unit_system_keeps_units_already_in_it() {
+        let q = known_in(3.0, "tbsp").in_unit_system(UnitSystem::UsCustomary);
+        assert_eq!(display_unit_of(q), "tbsp");
+        let q = known_in(3.0, "kg").in_unit_system(UnitSystem::Metric);
+        assert_eq!(display_unit_of(q), "kg");
+        let q = known_in(3.0, "clove").in_unit_system(UnitSystem::Metric);
+        assert_eq!(display_unit_of(q), "clove");
+    }
+
+    #[test]
+    fn scaling_up_steps_up_the_ladder() {
+        let q = known_in(3.0, "tsp").scaled(2.0);
+        assert_eq!(display_unit_of(q), "tbsp");
+    }
+
+    #[test]
+    fn scaling_down_steps_down_when_unreadable() {
+        let q = known_in(1.0, "cup").scaled(0.125);
+        assert_eq!(display_unit_of(q), "tbsp");
+        let q = known_in(1.0, "kg").scaled(0.1);
+        assert_eq!(display_unit_of(q), "g");
+    }
+
+    #[test]
+    fn scaling_down_keeps_readable_units() {
+        let q = known_in(1.0, "cup").scaled(1.0 / 3.0);
+        assert_eq!(display_unit_of(q), "cup");
+    }
+
+    #[test]
+    fn scaling_custom_quantities_keeps_unit() {
+        let q = custom(2.0, "handful").scaled(1.5);
+        assert_eq!(q, custom(3.0, "handful"));
+    }
+
+    #[test]
+    fn custom_quantities_ignore_display_unit() {
+        let q = custom(2.0, "sprig").with_display_unit(&DisplayUnit::Fixed("cup".into()));
+        assert_eq!(q, custom(2.0, "sprig"));
+    }
+
     // adding tests
 
     // --- Known + Known ---
@@ -443,4 +900,63 @@ mod tests {
         let result = known(1.0, "ML").try_add(known(1.0, "ml"));
         assert!(result.is_err());
     }
+
+    // --- Subtraction ---
+
+    #[test]
+    fn subtracts_same_dimension() {
+        let result = known(3.0, "l").try_subtract(known(1.0, "l"), None);
+        assert!(matches!(result, Ok(Quantity::Known { amount, .. }) if amount == 2.0));
+    }
+
+    #[test]
+    fn subtraction_may_go_negative() {
+        let result = custom(1.0, "can").try_subtract(custom(3.0, "can"), None);
+        assert!(matches!(result, Ok(Quantity::Custom { amount, .. }) if amount == -2.0));
+    }
+
+    #[test]
+    fn subtracts_across_dimensions_with_density() {
+        let result = known(1.0, "kg").try_subtract(known(0.5, "l"), Some(0.8));
+        assert!(
+            matches!(result, Ok(Quantity::Known { amount, .. }) if (amount - 0.6).abs() < 1e-12)
+        );
+    }
+
+    #[test]
+    fn subtraction_without_density_keeps_dimensions_apart() {
+        let result = known(1.0, "kg").try_subtract(known(0.5, "l"), None);
+        assert!(result.is_err());
+    }
+
+    // --- Density conversion ---
+
+    #[test]
+    fn density_converts_volume_into_mass() {
+        // 0.5 l at 0.8 kg/l is 0.4 kg
+        let result = known(1.0, "kg").try_add_with_density(known(0.5, "l"), 0.8);
+        assert!(
+            matches!(result, Ok(Quantity::Known { amount, ref unit_key, .. }) if (amount - 1.4).abs() < 1e-12 && unit_key == "kg")
+        );
+    }
+
+    #[test]
+    fn density_converts_mass_into_volume() {
+        let result = known(1.0, "l").try_add_with_density(known(0.4, "kg"), 0.8);
+        assert!(
+            matches!(result, Ok(Quantity::Known { amount, ref unit_key, .. }) if (amount - 1.5).abs() < 1e-12 && unit_key == "l")
+        );
+    }
+
+    #[test]
+    fn density_is_ignored_for_same_dimension() {
+        let result = known(1.0, "l").try_add_with_density(known(1.0, "l"), 0.8);
+        assert!(matches!(result, Ok(Quantity::Known { amount, .. }) if amount == 2.0));
+    }
+
+    #[test]
+    fn density_does_not_apply_to_custom_units() {
+        let result = known(1.0, "kg").try_add_with_density(custom(1.0, "l"), 0.8);
+        assert!(result.is_err());
+    }
 }
@@ -3,12 +3,28 @@ use std::{collections::HashMap, error::Error, num::ParseFloatError};
 use measurements::{Mass, Measurement, Volume};
 use serde::Deserialize;
 
-use crate::ingredient::Ingredient;
+use crate::{ingredient::Ingredient, ingredient_unit::registry::UnitRegistry};
 
 #[derive(Debug)]
 pub enum ParsedMeasurement {
     Volume(Volume),
     Mass(Mass),
+    /// A counted unit with no volume or mass equivalent, e.g. "2 cloves" or "1 can".
+    Count {
+        amount: f64,
+        unit: String,
+    },
+}
+
+impl ParsedMeasurement {
+    /// The key quantities must share to be added: the base unit for volume and mass, or the
+    /// canonical unit itself for counts (cloves never add to cans).
+    pub fn unit_key(&self) -> &str {
+        match self {
+            ParsedMeasurement::Count { unit, .. } => unit,
+            other => other.get_base_units_name(),
+        }
+    }
 }
 
 impl Measurement for ParsedMeasurement {
@@ -16,6 +32,7 @@ impl Measurement for ParsedMeasurement {
         match self {
             ParsedMeasurement::Volume(v) => Volume::get_base_units_name(v),
             ParsedMeasurement::Mass(m) => Mass::get_base_units_name(m),
+            ParsedMeasurement::Count { .. } => "count",
         }
     }
 
@@ -23,6 +40,7 @@ impl Measurement for ParsedMeasurement {
         match self {
             ParsedMeasurement::Volume(v) => v.as_base_units(),
             ParsedMeasurement::Mass(m) => m.as_base_units(),
+            ParsedMeasurement::Count { amount, .. } => *amount,
         }
     }
 
@@ -34,6 +52,8 @@ impl Measurement for ParsedMeasurement {
 #[derive(Debug)]
 pub enum ParsedMeasurementError {
     UnknownUnit(String),
+    /// The unit is spelled like more than one known unit.
+    AmbiguousUnit(String),
     ParseUnitData(toml::de::Error),
     ParseFloat(ParseFloatError),
 }
@@ -42,6 +62,7 @@ impl std::fmt::Display for ParsedMeasurementError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::UnknownUnit(u) => write!(f, "Unknown unit: {u}"),
+            Self::AmbiguousUnit(u) => write!(f, "Ambiguous unit: {u}"),
             Self::ParseUnitData(e) => write!(f, "Failed to parse unit data: {e}"),
             Self::ParseFloat(e) => write!(f, "Failed to parse float: {e}"),
         }
@@ -62,57 +83,63 @@ impl From<ParseFloatError> for ParsedMeasurementError {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct Unit {
-    canonical: String,
+    pub(super) canonical: String,
     fuzzy: Vec<String>,
+    /// Plural display form; units without one (mostly abbreviations) are invariant.
+    #[serde(default)]
+    plural: Option<String>,
+    /// Volume or mass one of this unit stands for, e.g. "8 tbsp" for a stick of butter.
+    #[serde(default)]
+    pub(super) equals: Option<String>,
+}
+
+impl Unit {
+    /// Another spelling of a bundled unit, e.g. "cucharada" for "tbsp".
+    pub(super) fn alias(canonical: &str, fuzzy: Vec<String>) -> Self {
+        Self {
+            canonical: canonical.to_string(),
+            fuzzy,
+            plural: None,
+            equals: None,
+        }
+    }
 }
 
 pub type Units = HashMap<String, Unit>;
 
+pub(super) fn find_unit<'a>(
+    units: &'a Units,
+    input: &str,
+) -> Result<&'a Unit, ParsedMeasurementError> {
+    units
+        .values()
+        .find(|unit| unit.fuzzy.iter().any(|f| f == input) || unit.canonical == input)
+        .ok_or_else(|| ParsedMeasurementError::UnknownUnit(format!("unknown unit: {}", input)))
+}
+
 pub fn canonical_from_fuzzy<'a>(
     units: &'a Units,
     input: &str,
 ) -> Result<&'a str, ParsedMeasurementError> {
-    for unit in units.values() {
-        if unit.fuzzy.iter().any(|f| f == input) || unit.canonical == input {
-            return Ok(unit.canonical.as_str());
-        }
-    }
-    Err(ParsedMeasurementError::UnknownUnit(format!(
-        "unknown unit: {}",
-        input
-    )))
+    find_unit(units, input).map(|unit| unit.canonical.as_str())
+}
+
+pub fn plural_from_fuzzy<'a>(
+    units: &'a Units,
+    input: &str,
+) -> Result<&'a str, ParsedMeasurementError> {
+    find_unit(units, input).map(|unit| unit.plural.as_deref().unwrap_or(&unit.canonical))
 }
 
 impl TryFrom<&Ingredient> for ParsedMeasurement {
     type Error = ParsedMeasurementError;
 
     fn try_from(value: &Ingredient) -> Result<Self, Self::Error> {
-        let vol_toml = include_str!("volume.toml");
-        let mass_toml = include_str!("mass.toml");
-        let volume_units: Units = toml::from_str(vol_toml)?;
-        let mass_units: Units = toml::from_str(mass_toml)?;
-
-        match (
-            canonical_from_fuzzy(&volume_units, value.unit.as_str()),
-            canonical_from_fuzzy(&mass_units, value.unit.as_str()),
-        ) {
-            (Ok(u), Err(_)) => Ok(ParsedMeasurement::Volume(
-                format!("{} {}", value.amount, u).parse::<Volume>()?,
-            )),
-            (Err(_), Ok(u)) => Ok(ParsedMeasurement::Mass(
-                format!("{} {}", value.amount, u).parse::<Mass>()?,
-            )),
-            (Ok(_), Ok(_)) => Err(ParsedMeasurementError::UnknownUnit(format!(
-                "ambiguous unit: {}",
-                value.unit
-            ))),
-            (Err(_), Err(_)) => Err(ParsedMeasurementError::UnknownUnit(format!(
-                "unknown unit: {}",
-                value.unit
-            ))),
-        }
+        UnitRegistry::bundled()
+            .measure(value)
+            .map(|(measurement, _)| measurement)
     }
 }
 
@@ -138,7 +165,7 @@ mod tests {
     #[test]
     fn parsed_measurement_volume_delegates_get_base_units_name() {
         let v: Volume = "1 ml".parse().unwrap();
-        let pm = ParsedMeasurement::Volume(v.clone());
+        let pm = ParsedMeasurement::Volume(v);
 
         assert_eq!(pm.get_base_units_name(), Volume::get_base_units_name(&v));
     }
@@ -146,7 +173,7 @@ mod tests {
     #[test]
     fn parsed_measurement_volume_delegates_as_base_units() {
         let v: Volume = "2 ml".parse().unwrap();
-        let pm = ParsedMeasurement::Volume(v.clone());
+        let pm = ParsedMeasurement::Volume(v);
 
         assert!(approx_eq(pm.as_base_units(), v.as_base_units()));
     }
@@ -154,7 +181,7 @@ mod tests {
     #[test]
     fn parsed_measurement_mass_delegates_get_base_units_name() {
         let m: Mass = "1 g".parse().unwrap();
-        let pm = ParsedMeasurement::Mass(m.clone());
+        let pm = ParsedMeasurement::Mass(m);
 
         assert_eq!(pm.get_base_units_name(), Mass::get_base_units_name(&m));
     }
@@ -162,7 +189,7 @@ mod tests {
     #[test]
     fn parsed_measurement_mass_delegates_as_base_units() {
         let m: Mass = "3 g".parse().unwrap();
-        let pm = ParsedMeasurement::Mass(m.clone());
+        let pm = ParsedMeasurement::Mass(m);
 
         assert!(approx_eq(pm.as_base_units(), m.as_base_units()));
     }
@@ -193,6 +220,28 @@ mod tests {
         assert!(matches!(parsed, ParsedMeasurement::Volume(_)));
     }
 
+    #[test]
+    fn count_unit_parses_as_count() {
+        let ing = ingredient(2.0, "cloves");
+
+        let parsed = ParsedMeasurement::try_from(&ing).expect("should parse count");
+        assert!(
+            matches!(parsed, ParsedMeasurement::Count { amount, ref unit } if amount == 2.0 && unit == "clove")
+        );
+        assert_eq!(parsed.unit_key(), "clove");
+    }
+
+    #[test]
+    fn count_unit_with_equivalent_parses_as_volume() {
+        let ing = ingredient(2.0, "sticks");
+
+        let parsed = ParsedMeasurement::try_from(&ing).expect("should parse stick as volume");
+        let tbsp: Volume = "16 tbsp".parse().unwrap();
+        assert!(
+            matches!(parsed, ParsedMeasurement::Volume(v) if approx_eq(v.as_base_units(), tbsp.as_base_units()))
+        );
+    }
+
     #[test]
     fn unknown_unit_returns_unknown_unit() {
         let ing = ingredient(1.0, "this_unit_does_not_exist");
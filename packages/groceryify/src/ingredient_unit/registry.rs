@@ -0,0 +1,500 @@
+use std::{collections::HashMap, error::Error, sync::OnceLock};
+
+use measurements::{Mass, Measurement, Volume};
+
+use crate::{
+    ingredient::Ingredient,
+    ingredient_unit::{
+        Reading,
+        measurement_parser::{
+            ParsedMeasurement, ParsedMeasurementError, Unit, Units, canonical_from_fuzzy,
+            find_unit, plural_from_fuzzy,
+        },
+    },
+};
+
+static BUNDLED: OnceLock<UnitRegistry> = OnceLock::new();
+
+/// Failure to load user-supplied units.
+#[derive(Debug)]
+pub enum UnitRegistryError {
+    /// The TOML is not a table of units.
+    Parse(toml::de::Error),
+    /// A unit's `equals` is not an amount of a bundled volume or mass unit, such as "15 g".
+    InvalidEquivalent { unit: String, equals: String },
+}
+
+impl std::fmt::Display for UnitRegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "Failed to parse unit data: {e}"),
+            Self::InvalidEquivalent { unit, equals } => {
+                write!(
+                    f,
+                    "Unit {unit} equals {equals}, which is not a known volume or mass"
+                )
+            }
+        }
+    }
+}
+
+impl Error for UnitRegistryError {}
+
+/// The volume, mass and count units quantities are read with, parsed once and reused.
+///
+/// User-supplied units use the same TOML layout as the bundled tables and are looked up first.
+/// A unit with `equals` (e.g. `equals = "15 g"`) is read as that volume or mass and displayed in
+/// its unit; a unit without one is an alias of the bundled unit its `canonical` names, or else a
+/// counted unit that only adds to itself.
+#[derive(Clone, Debug)]
+pub struct UnitRegistry {
+    volume: Units,
+    mass: Units,
+    count: Units,
+    custom: Units,
+    /// Litres per canonical volume unit and kilograms per canonical mass unit, worked out once
+    /// because `measurements` compiles a regex for every string it parses.
+    litres_per: HashMap<String, f64>,
+    kilograms_per: HashMap<String, f64>,
+}
+
+impl UnitRegistry {
+    /// Parses the bundled unit tables.
+    pub fn new() -> Self {
+        let parse = |data: &str| -> Units { toml::from_str(data).unwrap() };
+        let volume = parse(include_str!("volume.toml"));
+        let mass = parse(include_str!("mass.toml"));
+        Self {
+            litres_per: base_units_table(&volume, parse_base_units::<Volume>),
+            kilograms_per: base_units_table(&mass, parse_base_units::<Mass>),
+            volume,
+            mass,
+            count: parse(include_str!("count.toml")),
+            custom: Units::new(),
+        }
+    }
+
+    /// The bundled units, parsed on first use and shared by the whole process.
+    pub fn bundled() -> &'static UnitRegistry {
+        BUNDLED.get_or_init(Self::new)
+    }
+
+    /// The bundled units plus the units in `data`.
+    pub fn from_toml(data: &str) -> Result<Self, UnitRegistryError> {
+        let mut registry = Self::new();
+        registry.load_toml(data)?;
+        Ok(registry)
+    }
+
+    /// Adds the units in `data`, replacing user-supplied units with the same table key. Nothing is
+    /// added if any unit is invalid.
+    pub fn load_toml(&mut self, data: &str) -> Result<(), UnitRegistryError> {
+        let units: Units = toml::from_str(data).map_err(UnitRegistryError::Parse)?;
+        if let Some((key, equals)) = units.iter().find_map(|(key, unit)| {
+            unit.equals
+                .as_ref()
+                .filter(|equals| self.equivalent(equals).is_none())
+                .map(|equals| (key, equals))
+        }) {
+            return Err(UnitRegistryError::InvalidEquivalent {
+                unit: key.clone(),
+                equals: equals.clone(),
+            });
+        }
+        self.custom.extend(units);
+        Ok(())
+    }
+
+    /// Adds other spellings of a bundled unit, read like user-supplied units.
+    pub(crate) fn add_aliases(&mut self, key: &str, canonical: &str, aliases: Vec<String>) {
+        self.custom
+            .insert(key.to_string(), Unit::alias(canonical, aliases));
+    }
+
+    /// The unit tables in lookup order. Spellings of both a weight and a volume, such as "oz",
+    /// are looked up as the weight, whose canonical spelling they share.
+    fn tables(&self) -> [&Units; 4] {
+        [&self.custom, &self.mass, &self.volume, &self.count]
+    }
+
+    /// Looks up the canonical spelling of a unit, e.g. "tablespoons" -> "tbsp".
+    pub fn canonical(&self, input: &str) -> Option<&str> {
+        self.tables()
+            .into_iter()
+            .find_map(|units| canonical_from_fuzzy(units, input).ok())
+    }
+
+    /// Whether `input` is one of the caller's own units, rather than a bundled unit or a locale's
+    /// spelling of one.
+    pub(crate) fn is_custom(&self, input: &str) -> bool {
+        find_unit(&self.custom, input).is_ok_and(|unit| {
+            [&self.volume, &self.mass, &self.count]
+                .into_iter()
+                .all(|units| find_unit(units, &unit.canonical).is_err())
+        })
+    }
+
+    /// Whether `input` spells both a volume and a weight, such as "oz", so that measuring it
+    /// takes a [`Reading`].
+    pub(crate) fn is_ambiguous(&self, input: &str) -> bool {
+        let input = match find_unit(&self.custom, input) {
+            Ok(unit) if unit.equals.is_some() => return false,
+            Ok(unit) => unit.canonical.as_str(),
+            Err(_) => input,
+        };
+        canonical_from_fuzzy(&self.volume, input).is_ok()
+            && canonical_from_fuzzy(&self.mass, input).is_ok()
+    }
+
+    /// Looks up the plural display form of a unit, e.g. "pinch" -> "pinches".
+    pub fn plural(&self, input: &str) -> Option<&str> {
+        self.tables()
+            .into_iter()
+            .find_map(|units| plural_from_fuzzy(units, input).ok())
+    }
+
+    /// Returns how many base units (litres or kilograms) one `unit` is worth, or `None` if `unit`
+    /// is not a unit of the `unit_key` dimension.
+    pub(crate) fn base_units_per(&self, unit_key: &str, unit: &str) -> Option<f64> {
+        let equivalent = || {
+            [&self.custom, &self.count]
+                .into_iter()
+                .find_map(|units| find_unit(units, unit).ok())
+                .and_then(|found| found.equals.as_deref())
+                .and_then(|equals| self.equivalent(equals))
+                .map(|(measurement, _)| measurement)
+        };
+        match unit_key {
+            "l" => self.litres(unit).or_else(|| match equivalent() {
+                Some(ParsedMeasurement::Volume(v)) => Some(v.as_base_units()),
+                _ => None,
+            }),
+            "kg" => self.kilograms(unit).or_else(|| match equivalent() {
+                Some(ParsedMeasurement::Mass(m)) => Some(m.as_base_units()),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    fn litres(&self, unit: &str) -> Option<f64> {
+        self.litres_per
+            .get(unit)
+            .copied()
+            .or_else(|| parse_base_units::<Volume>(unit))
+    }
+
+    fn kilograms(&self, unit: &str) -> Option<f64> {
+        self.kilograms_per
+            .get(unit)
+            .copied()
+            .or_else(|| parse_base_units::<Mass>(unit))
+    }
+
+    fn volume(&self, amount: f64, unit: &str) -> Result<Volume, ParsedMeasurementError> {
+        if !amount.is_finite() {
+            // Leave amounts such as NaN for `measurements` to reject
+            return Ok(format!("{amount} {unit}").parse::<Volume>()?);
+        }
+        self.litres(unit)
+            .map(|litres| Volume::from_base_units(amount * litres))
+            .ok_or_else(|| ParsedMeasurementError::UnknownUnit(format!("unknown unit: {unit}")))
+    }
+
+    fn mass(&self, amount: f64, unit: &str) -> Result<Mass, ParsedMeasurementError> {
+        if !amount.is_finite() {
+            // Leave amounts such as NaN for `measurements` to reject
+            return Ok(format!("{amount} {unit}").parse::<Mass>()?);
+        }
+        self.kilograms(unit)
+            .map(|kilograms| Mass::from_base_units(amount * kilograms))
+            .ok_or_else(|| ParsedMeasurementError::UnknownUnit(format!("unknown unit: {unit}")))
+    }
+
+    /// Reads an equivalent such as "8 tbsp" or "15 g" with the bundled volume and mass units,
+    /// returning it with the canonical unit it is written in.
+    fn equivalent(&self, equals: &str) -> Option<(ParsedMeasurement, String)> {
+        let (amount, unit) = equals.trim().split_once(' ')?;
+        let amount = amount.parse::<f64>().ok()?;
+        let unit = unit.trim();
+        // Weights first, so an equivalent in "oz" is a weight like elsewhere in the tables
+        if let Ok(u) = canonical_from_fuzzy(&self.mass, unit) {
+            let mass = self.mass(amount, u).ok()?;
+            return Some((ParsedMeasurement::Mass(mass), u.to_string()));
+        }
+        let u = canonical_from_fuzzy(&self.volume, unit).ok()?;
+        let volume = self.volume(amount, u).ok()?;
+        Some((ParsedMeasurement::Volume(volume), u.to_string()))
+    }
+
+    /// Reads an ingredient's amount and unit.
+    ///
+    /// # Returns:
+    /// * The measurement and the canonical unit to display it in, or an error if the unit is
+    ///   unknown or names units of more than one dimension.
+    pub(crate) fn measure(
+        &self,
+        ingredient: &Ingredient,
+    ) -> Result<(ParsedMeasurement, String), ParsedMeasurementError> {
+        self.measure_as(ingredient, None)
+    }
+
+    /// Reads an ingredient's amount and unit like [`UnitRegistry::measure`], reading a unit that
+    /// spells both a volume and a weight as `reading` says.
+    pub(crate) fn measure_as(
+        &self,
+        ingredient: &Ingredient,
+        reading: Option<Reading>,
+    ) -> Result<(ParsedMeasurement, String), ParsedMeasurementError> {
+        let Ok(unit) = find_unit(&self.custom, &ingredient.unit) else {
+            return self.measure_bundled(ingredient.amount, &ingredient.unit, reading);
+        };
+        match &unit.equals {
+            Some(equals) => {
+                let (one, display) = self.equivalent(equals).ok_or_else(|| {
+                    ParsedMeasurementError::UnknownUnit(format!("unknown unit: {equals}"))
+                })?;
+                Ok((times(one, ingredient.amount), display))
+            }
+            None => self
+                .measure_bundled(ingredient.amount, &unit.canonical, reading)
+                .or_else(|_| {
+                    Ok((
+                        ParsedMeasurement::Count {
+                            amount: ingredient.amount,
+                            unit: unit.canonical.clone(),
+                        },
+                        unit.canonical.clone(),
+                    ))
+                }),
+        }
+    }
+
+    fn measure_bundled(
+        &self,
+        amount: f64,
+        input: &str,
+        reading: Option<Reading>,
+    ) -> Result<(ParsedMeasurement, String), ParsedMeasurementError> {
+        let (volume, mass) = match (
+            canonical_from_fuzzy(&self.volume, input),
+            canonical_from_fuzzy(&self.mass, input),
+            reading,
+        ) {
+            (Ok(_), Ok(m), Some(Reading::Mass)) => (Err(()), Ok(m)),
+            (Ok(v), Ok(_), Some(Reading::Volume)) => (Ok(v), Err(())),
+            (volume, mass, _) => (volume.map_err(|_| ()), mass.map_err(|_| ())),
+        };
+        match (volume, mass, find_unit(&self.count, input)) {
+            (Ok(u), Err(_), Err(_)) => Ok((
+                ParsedMeasurement::Volume(self.volume(amount, u)?),
+                u.to_string(),
+            )),
+            (Err(_), Ok(u), Err(_)) => Ok((
+                ParsedMeasurement::Mass(self.mass(amount, u)?),
+                u.to_string(),
+            )),
+            (Err(_), Err(_), Ok(unit)) => {
+                let measurement = match &unit.equals {
+                    Some(equals) => {
+                        let (one, _) = self.equivalent(equals).ok_or_else(|| {
+                            ParsedMeasurementError::UnknownUnit(format!("unknown unit: {equals}"))
+                        })?;
+                        times(one, amount)
+                    }
+                    None => ParsedMeasurement::Count {
+                        amount,
+                        unit: unit.canonical.clone(),
+                    },
+                };
+                Ok((measurement, unit.canonical.clone()))
+            }
+            (Err(_), Err(_), Err(_)) => Err(ParsedMeasurementError::UnknownUnit(format!(
+                "unknown unit: {input}"
+            ))),
+            _ => Err(ParsedMeasurementError::AmbiguousUnit(input.to_string())),
+        }
+    }
+}
+
+impl Default for UnitRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses one `unit` with `measurements`, returning its worth in base units.
+fn parse_base_units<M: Measurement + std::str::FromStr>(unit: &str) -> Option<f64> {
+    format!("1 {unit}")
+        .parse::<M>()
+        .ok()
+        .map(|m| m.as_base_units())
+}
+
+/// Base units per canonical unit of a table, for the units `measurements` can parse.
+fn base_units_table(units: &Units, per: fn(&str) -> Option<f64>) -> HashMap<String, f64> {
+    units
+        .values()
+        .filter_map(|unit| per(&unit.canonical).map(|base| (unit.canonical.clone(), base)))
+        .collect()
+}
+
+/// Multiplies a measurement by `factor`.
+fn times(measurement: ParsedMeasurement, factor: f64) -> ParsedMeasurement {
+    match measurement {
+        ParsedMeasurement::Volume(v) => {
+            ParsedMeasurement::Volume(Volume::from_base_units(v.as_base_units() * factor))
+        }
+        ParsedMeasurement::Mass(m) => {
+            ParsedMeasurement::Mass(Mass::from_base_units(m.as_base_units() * factor))
+        }
+        ParsedMeasurement::Count { amount, unit } => ParsedMeasurement::Count {
+            amount: amount * factor,
+            unit,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measure(registry: &UnitRegistry, amount: f64, unit: &str) -> (ParsedMeasurement, String) {
+        registry
+            .measure(&Ingredient::new("x", amount, unit))
+            .unwrap()
+    }
+
+    fn approx_eq(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn bundled_registry_is_shared() {
+        assert!(std::ptr::eq(
+            UnitRegistry::bundled(),
+            UnitRegistry::bundled()
+        ));
+    }
+
+    #[test]
+    fn reads_bundled_units() {
+        let registry = UnitRegistry::new();
+        assert_eq!(registry.canonical("tablespoons"), Some("tbsp"));
+        assert_eq!(registry.plural("pinch"), Some("pinches"));
+        let (measurement, unit) = measure(&registry, 2.0, "sticks");
+        assert_eq!(unit, "stick");
+        assert_eq!(measurement.unit_key(), "l");
+    }
+
+    #[test]
+    fn custom_unit_with_a_mass_equivalent_reads_as_that_mass() {
+        let registry = UnitRegistry::from_toml(
+            r#"
+            [knob]
+            canonical = "knob"
+            fuzzy = ["knobs"]
+            equals = "15 g"
+            "#,
+        )
+        .unwrap();
+        let (measurement, unit) = measure(&registry, 2.0, "knobs");
+        assert_eq!(unit, "g");
+        assert!(matches!(measurement, ParsedMeasurement::Mass(m) if approx_eq(m.as_grams(), 30.0)));
+    }
+
+    #[test]
+    fn custom_unit_without_an_equivalent_is_counted() {
+        let registry = UnitRegistry::from_toml(
+            r#"
+            [punnet]
+            canonical = "punnet"
+            plural = "punnets"
+            fuzzy = ["punnets"]
+            "#,
+        )
+        .unwrap();
+        let (measurement, unit) = measure(&registry, 3.0, "punnets");
+        assert_eq!(unit, "punnet");
+        assert_eq!(measurement.unit_key(), "punnet");
+        assert_eq!(registry.plural("punnet"), Some("punnets"));
+    }
+
+    #[test]
+    fn custom_alias_of_a_bundled_unit() {
+        let registry = UnitRegistry::from_toml(
+            r#"
+            [tbls]
+            canonical = "tbsp"
+            fuzzy = ["tbls"]
+            "#,
+        )
+        .unwrap();
+        let (measurement, unit) = measure(&registry, 1.0, "tbls");
+        assert_eq!(unit, "tbsp");
+        assert_eq!(measurement.unit_key(), "l");
+    }
+
+    #[test]
+    fn custom_units_do_not_leak_into_the_bundled_registry() {
+        let _ = UnitRegistry::from_toml("[knob]\ncanonical = \"knob\"\nfuzzy = []\n").unwrap();
+        assert_eq!(UnitRegistry::bundled().canonical("knob"), None);
+    }
+
+    #[test]
+    fn spellings_of_a_volume_and_a_mass_are_ambiguous() {
+        let mut registry = UnitRegistry::new();
+        registry
+            .mass
+            .insert("cup".to_string(), Unit::alias("g", vec!["cup".to_string()]));
+        let err = registry
+            .measure(&Ingredient::new("x", 1.0, "cup"))
+            .unwrap_err();
+        assert!(matches!(err, ParsedMeasurementError::AmbiguousUnit(u) if u == "cup"));
+    }
+
+    #[test]
+    fn ounces_spell_both_a_weight_and_a_volume() {
+        let registry = UnitRegistry::bundled();
+        assert!(registry.is_ambiguous("oz"));
+        assert!(registry.is_ambiguous("ounces"));
+        assert!(!registry.is_ambiguous("fl oz"));
+        assert_eq!(registry.canonical("ounces"), Some("oz"));
+        let ounces = Ingredient::new("x", 1.0, "oz");
+        assert!(matches!(
+            registry.measure(&ounces),
+            Err(ParsedMeasurementError::AmbiguousUnit(_))
+        ));
+        let (measurement, unit) = registry.measure_as(&ounces, Some(Reading::Volume)).unwrap();
+        assert_eq!((measurement.unit_key(), unit.as_str()), ("l", "fl oz"));
+        let (measurement, unit) = registry.measure_as(&ounces, Some(Reading::Mass)).unwrap();
+        assert_eq!((measurement.unit_key(), unit.as_str()), ("kg", "oz"));
+    }
+
+    #[test]
+    fn locale_spellings_are_not_custom_units() {
+        let locale = crate::Locale::bundled("es").unwrap();
+        assert!(!locale.unit_registry().is_custom("dientes"));
+        let registry =
+            UnitRegistry::from_toml("[knob]\ncanonical = \"knob\"\nfuzzy = []\n").unwrap();
+        assert!(registry.is_custom("knob"));
+    }
+
+    #[test]
+    fn rejects_invalid_toml() {
+        let err = UnitRegistry::from_toml("not toml").unwrap_err();
+        assert!(matches!(err, UnitRegistryError::Parse(_)));
+    }
+
+    #[test]
+    fn rejects_an_unknown_equivalent() {
+        let mut registry = UnitRegistry::new();
+        let err = registry
+            .load_toml("[scoop]\ncanonical = \"scoop\"\nfuzzy = []\nequals = \"2 handfuls\"\n")
+            .unwrap_err();
+        assert!(
+            matches!(err, UnitRegistryError::InvalidEquivalent { ref unit, .. } if unit == "scoop")
+        );
+        assert_eq!(registry.canonical("scoop"), None);
+    }
+}
@@ -1,21 +1,88 @@
 use std::collections::HashMap;
 
+/// English suffix rules for reading plurals as singular (NLTK morphy order for nouns).
+const ENGLISH_SINGULAR_RULES: [(&str, &str); 5] = [
+    ("ies", "y"),
+    ("ves", "f"), // knives → knife, leaves → leaf
+    ("ses", "s"),
+    ("es", ""),
+    ("s", ""),
+];
+
+/// English suffix rules for writing plurals; other words just take an "s".
+const ENGLISH_PLURAL_RULES: [(&str, &str); 11] = [
+    ("s", "ses"),
+    ("x", "xes"),
+    ("z", "zes"),
+    ("ch", "ches"),
+    ("sh", "shes"),
+    // A "y" after a vowel stays (day → days), after a consonant it becomes "ies"
+    ("ay", "ays"),
+    ("ey", "eys"),
+    ("iy", "iys"),
+    ("oy", "oys"),
+    ("uy", "uys"),
+    ("y", "ies"),
+];
+
+#[derive(Clone, Debug)]
 pub struct Lemmatizer {
     exceptions: HashMap<String, String>,
+    plurals: HashMap<String, String>,
+    singular_rules: Vec<(String, String)>,
+    plural_rules: Vec<(String, String)>,
+    plural_suffix: String,
 }
 
 impl Lemmatizer {
     pub fn new() -> Self {
         // Bundle the file at compile time — zero runtime file I/O
         let data = include_str!("../resources/noun.exc");
-        let mut exceptions = HashMap::new();
-        for line in data.lines() {
+        let exceptions = data.lines().filter_map(|line| {
             let mut parts = line.split_whitespace();
-            if let (Some(form), Some(lemma)) = (parts.next(), parts.next()) {
-                exceptions.insert(form.to_string(), lemma.to_string());
-            }
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        });
+        let rules = |rules: &[(&str, &str)]| {
+            rules
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect()
+        };
+        Self::with_rules(
+            exceptions,
+            rules(&ENGLISH_SINGULAR_RULES),
+            rules(&ENGLISH_PLURAL_RULES),
+            "s",
+        )
+    }
+
+    /// A lemmatizer for another language.
+    ///
+    /// # Arguments:
+    /// * `exceptions`: Irregular plurals with their singular, e.g. ("eier", "ei").
+    /// * `singular_rules`: Suffixes replaced to read a plural as singular, tried in order.
+    /// * `plural_rules`: Suffixes replaced to write a singular as plural, tried in order.
+    /// * `plural_suffix`: Appended to singulars no plural rule matches.
+    pub fn with_rules(
+        exceptions: impl IntoIterator<Item = (String, String)>,
+        singular_rules: Vec<(String, String)>,
+        plural_rules: Vec<(String, String)>,
+        plural_suffix: &str,
+    ) -> Self {
+        let mut lemmas = HashMap::new();
+        let mut plurals = HashMap::new();
+        for (form, lemma) in exceptions {
+            let (form, lemma) = (form.to_lowercase(), lemma.to_lowercase());
+            plurals.entry(lemma.clone()).or_insert_with(|| form.clone());
+            lemmas.insert(form, lemma);
+        }
+        Self {
+            exceptions: lemmas,
+            plurals,
+            singular_rules,
+            plural_rules,
+            plural_suffix: plural_suffix.to_string(),
         }
-        Self { exceptions }
     }
 
     pub fn lemmatize(&self, word: &str) -> String {
@@ -24,21 +91,30 @@ impl Lemmatizer {
         if let Some(lemma) = self.exceptions.get(&word) {
             return lemma.clone();
         }
-        // 2. Suffix stripping rules (NLTK morphy order for nouns)
-        let rules: &[(&str, &str)] = &[
-            ("ies", "y"),
-            ("ves", "f"), // knives → knife, leaves → leaf
-            ("ses", "s"),
-            ("es", ""),
-            ("s", ""),
-        ];
-        for (suffix, replacement) in rules {
-            if word.ends_with(suffix) && word.len() > suffix.len() + 2 {
+        // 2. Suffix stripping rules
+        for (suffix, replacement) in &self.singular_rules {
+            if word.ends_with(suffix.as_str()) && word.len() > suffix.len() + 2 {
                 return format!("{}{}", &word[..word.len() - suffix.len()], replacement);
             }
         }
         word
     }
+
+    /// Returns the plural of a singular noun, e.g. "pinch" -> "pinches", "loaf" -> "loaves".
+    pub fn pluralize(&self, word: &str) -> String {
+        let word = word.to_lowercase();
+        // 1. Irregular plurals are the exception list read backwards
+        if let Some(plural) = self.plurals.get(&word) {
+            return plural.clone();
+        }
+        // 2. Regular suffix rules
+        for (suffix, replacement) in &self.plural_rules {
+            if word.ends_with(suffix.as_str()) {
+                return format!("{}{}", &word[..word.len() - suffix.len()], replacement);
+            }
+        }
+        format!("{word}{}", self.plural_suffix)
+    }
 }
 
 #[cfg(test)]
@@ -172,6 +248,34 @@ mod tests {
         }
     }
 
+    // --- Pluralization ---
+
+    #[test]
+    fn test_pluralize_regular_suffixes() {
+        let l = lemmatizer();
+        assert_eq!(l.pluralize("cup"), "cups");
+        assert_eq!(l.pluralize("pinch"), "pinches");
+        assert_eq!(l.pluralize("box"), "boxes");
+        assert_eq!(l.pluralize("berry"), "berries");
+        assert_eq!(l.pluralize("day"), "days");
+    }
+
+    #[test]
+    fn test_pluralize_uses_exception_list() {
+        let l = lemmatizer();
+        assert_eq!(l.pluralize("loaf"), "loaves");
+        assert_eq!(l.pluralize("leaf"), "leaves");
+        assert_eq!(l.pluralize("tomato"), "tomatoes");
+    }
+
+    #[test]
+    fn test_pluralize_round_trips_through_lemmatize() {
+        let l = lemmatizer();
+        for word in &["can", "potato", "knife", "bunch", "berry"] {
+            assert_eq!(l.lemmatize(&l.pluralize(word)), *word);
+        }
+    }
+
     // --- Suffix rule priority order ---
 
     #[test]
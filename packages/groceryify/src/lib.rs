@@ -1,16 +1,50 @@
+use std::sync::OnceLock;
+
 use crate::{
-    ingredient::Ingredient, ingredient_name::IngredientName, ingredient_unit::Quantity,
-    lemmatizer::Lemmatizer, parsed_ingredient::ParsedIngredient,
+    diagnostics::MergeReport, grocery_item::GroceryItem, ingredient::Ingredient,
+    options::MergeOptions,
 };
 
+pub mod category;
+pub mod conversion;
+pub mod cost;
+pub mod density;
+pub mod descriptor;
+pub mod diagnostics;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod formatting;
+pub mod grocery_item;
+pub mod grocery_list;
 pub mod ingredient;
 mod ingredient_name;
+mod ingredient_parser;
 mod ingredient_unit;
 mod lemmatizer;
+pub mod liquid;
+pub mod locale;
+pub mod merger;
+mod name_table;
+pub mod nutrition;
+pub mod options;
+pub mod package;
+pub mod pantry;
 mod parsed_ingredient;
-#[cfg(feature = "wasm")]
+pub mod render;
+pub mod scaling;
+pub mod synonym;
+// Also built for tests, which check its TypeScript definitions on any target
+#[cfg(any(feature = "wasm", test))]
 pub mod wasm;
 
+pub use ingredient_parser::{
+    ParseIngredientError, parse_ingredient, parse_ingredient_in, parse_ingredient_with,
+};
+pub use ingredient_unit::{UnitRegistry, UnitRegistryError};
+pub use locale::{Locale, LocaleError};
+pub use merger::Merger;
+pub use scaling::{Scale, ScaleError, scale_ingredients};
+
 /// Takes a list of ingredients and merges like ingredients to create a grocery list.
 ///
 /// # Arguments:
@@ -18,60 +52,50 @@ pub mod wasm;
 ///
 /// # Returns:
 /// * List of ingredients, with like ingredients merged.
-pub fn merge(ingredients: &Vec<Ingredient>) -> Vec<Ingredient> {
-    let lemmatizer = Lemmatizer::new();
-    let mut cleaned_ingredients = ingredients
-        .into_iter()
-        .map(|i| {
-            let lemmatized_name = IngredientName(i.name.clone())
-                .remove_parenthesized()
-                .remove_after_comma()
-                .lemmatize(&lemmatizer)
-                .0;
-            let quantity = Quantity::new(&i);
-            ParsedIngredient::new(lemmatized_name.as_str(), quantity)
-        })
-        .collect::<Vec<_>>();
-    cleaned_ingredients.sort();
-    let merged =
-        cleaned_ingredients
-            .into_iter()
-            .fold(vec![], |mut acc: Vec<ParsedIngredient>, next| {
-                let last = acc.last_mut();
-                if let Some(last) = last {
-                    if last.name == next.name
-                        && last.quantity.unit_key() == next.quantity.unit_key()
-                    {
-                        match last.clone().merge(next.clone()) {
-                            Ok(merged) => {
-                                *last = merged;
-                            }
-                            Err(_) => {
-                                acc.push(next);
-                            }
-                        }
-                        return acc;
-                    }
-                }
-                acc.push(next);
-                acc
-            });
-    merged
+pub fn merge(ingredients: &[Ingredient]) -> Vec<Ingredient> {
+    // Every call shares one default merger, so its tables are only built once
+    static DEFAULT: OnceLock<Merger> = OnceLock::new();
+    DEFAULT
+        .get_or_init(|| Merger::new(MergeOptions::default()))
+        .merge(ingredients)
         .into_iter()
-        .map(|i| <ParsedIngredient>::into(i))
-        .collect::<Vec<Ingredient>>()
+        .map(|item| item.ingredient)
+        .collect()
+}
+
+/// Takes a list of ingredients and merges like ingredients to create a grocery list.
+///
+/// # Arguments:
+/// * `ingredients`: List of ingredients.
+/// * `options`: Options controlling how quantities are combined and presented.
+///
+/// # Returns:
+/// * List of grocery items, with like ingredients merged. See [`Merger::merge`]. The merger built
+///   for `options` is kept, so calls repeating the same options don't build it again.
+pub fn merge_with_options(ingredients: &[Ingredient], options: &MergeOptions) -> Vec<GroceryItem> {
+    Merger::shared(options).merge(ingredients)
+}
+
+/// Merges like ingredients like [`merge_with_options`], also explaining why any ingredient was
+/// left on more than one line.
+///
+/// # Arguments:
+/// * `ingredients`: List of ingredients.
+/// * `options`: Options controlling how quantities are combined and presented.
+///
+/// # Returns:
+/// * The grocery items, with a diagnostic for each name that appears on more than one of them.
+pub fn merge_with_diagnostics(ingredients: &[Ingredient], options: &MergeOptions) -> MergeReport {
+    Merger::shared(options).merge_with_diagnostics(ingredients)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::options::{AmbiguousUnits, DisplayUnit, UnitSystem};
 
     fn ing(name: &str, amount: f64, unit: &str) -> Ingredient {
-        Ingredient {
-            name: name.to_string(),
-            amount,
-            unit: unit.to_string(),
-        }
+        Ingredient::new(name, amount, unit)
     }
 
     // ── basic merging ────────────────────────────────────────────────────────
@@ -86,7 +110,8 @@ mod tests {
 
     #[test]
     fn does_not_merge_different_units() {
-        let ingredients = vec![ing("flour", 1.0, "cup"), ing("flour", 100.0, "g")];
+        // saffron has no bundled density, so its weight and volume stay apart
+        let ingredients = vec![ing("saffron", 1.0, "tsp"), ing("saffron", 1.0, "g")];
         let result = merge(&ingredients);
         assert_eq!(result.len(), 2);
     }
@@ -130,11 +155,580 @@ mod tests {
         assert_eq!(result[0].amount, 3.0);
     }
 
+    // ── synonyms ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn merges_synonyms_under_the_canonical_name() {
+        let ingredients = vec![
+            ing("scallions", 2.0, ""),
+            ing("green onions", 3.0, ""),
+            ing("cilantro", 1.0, "cup"),
+            ing("coriander leaves", 0.5, "cup"),
+        ];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 2);
+        let onion = result.iter().find(|i| i.name == "green onion").unwrap();
+        assert_eq!(onion.amount, 5.0);
+        let cilantro = result.iter().find(|i| i.name == "cilantro").unwrap();
+        assert_eq!(cilantro.amount, 1.5);
+    }
+
+    #[test]
+    fn merges_synonyms_with_an_apostrophe() {
+        let ingredients = vec![
+            ing("confectioners' sugar", 1.0, "cup"),
+            ing("powdered sugar", 1.0, "cup"),
+        ];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].amount, 2.0);
+    }
+
+    #[test]
+    fn caller_synonyms_extend_the_bundled_table() {
+        let options = MergeOptions {
+            synonyms: [("brinjals".to_string(), "eggplant".to_string())].into(),
+            ..Default::default()
+        };
+        let ingredients = vec![
+            ing("brinjal", 1.0, ""),
+            ing("aubergine", 1.0, ""),
+            ing("eggplant", 1.0, ""),
+        ];
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.amount, 3.0);
+        assert_eq!(result[0].category, "produce");
+    }
+
+    // ── descriptors ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn merges_after_stripping_descriptors() {
+        let ingredients = vec![
+            ing("finely chopped onion", 1.0, ""),
+            ing("large onion", 1.0, ""),
+            ing("onion", 1.0, ""),
+        ];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "onion");
+        assert_eq!(result[0].amount, 3.0);
+    }
+
+    #[test]
+    fn keeps_stripped_descriptors_and_notes_as_the_note() {
+        let mut minced = ing("minced garlic", 2.0, "clove");
+        minced.note = Some("divided".to_string());
+        let ingredients = vec![
+            minced,
+            ing("garlic", 1.0, "clove"),
+            ing("minced garlic", 1.0, "clove"),
+        ];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].note.as_deref(), Some("minced, divided"));
+    }
+
+    #[test]
+    fn leaves_the_note_empty_without_descriptors() {
+        let result = merge(&[ing("flour", 1.0, "cup")]);
+        assert_eq!(result[0].note, None);
+    }
+
+    #[test]
+    fn keeps_products_named_by_a_preparation_apart() {
+        let ingredients = vec![
+            ing("sliced almonds", 1.0, "cup"),
+            ing("almonds", 1.0, "cup"),
+        ];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().any(|i| i.name == "sliced almond"));
+    }
+
+    #[test]
+    fn synonyms_holding_a_descriptor_leave_the_plain_name_alone() {
+        // The bundled alias "minced beef" must not turn "beef" into "ground beef"
+        let ingredients = vec![ing("ground beef", 1.0, "lb"), ing("beef", 1.0, "lb")];
+        let mut names = merge(&ingredients)
+            .into_iter()
+            .map(|i| i.name)
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["beef", "ground beef"]);
+        let ingredients = vec![ing("ground beef", 1.0, "lb"), ing("minced beef", 1.0, "lb")];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "ground beef");
+    }
+
+    #[test]
+    fn synonyms_are_found_behind_a_trailing_descriptor() {
+        let ingredients = vec![
+            ing("scallions chopped", 2.0, ""),
+            ing("green onion", 1.0, ""),
+        ];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].amount, 3.0);
+    }
+
+    #[test]
+    fn caller_synonyms_to_a_longer_name_merge_with_it() {
+        let options = MergeOptions {
+            synonyms: [("onion".to_string(), "yellow onion".to_string())].into(),
+            ..Default::default()
+        };
+        let ingredients = vec![ing("onion", 1.0, ""), ing("yellow onion", 2.0, "")];
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.name, "yellow onion");
+        assert_eq!(result[0].ingredient.amount, 3.0);
+    }
+
+    #[test]
+    fn caller_descriptors_extend_the_bundled_list() {
+        let options = MergeOptions {
+            descriptors: vec!["heaping".to_string()],
+            ..Default::default()
+        };
+        let ingredients = vec![ing("heaping flour", 1.0, "cup"), ing("flour", 1.0, "cup")];
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.note.as_deref(), Some("heaping"));
+    }
+
+    // ── locales ──────────────────────────────────────────────────────────────
+
+    fn spanish() -> MergeOptions {
+        MergeOptions {
+            locale: Some("es".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn merges_locale_units_and_plurals() {
+        let ingredients = vec![
+            ing("limones", 2.0, ""),
+            ing("limón", 1.0, ""),
+            ing("harina", 1.0, "taza"),
+            ing("harina", 8.0, "cucharadas"),
+        ];
+        let result = merge_with_options(&ingredients, &spanish());
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].ingredient.name, "harina");
+        assert_eq!(result[0].ingredient.unit, "cup");
+        assert!((result[0].ingredient.amount - 1.5).abs() < 1e-9);
+        assert_eq!(result[1].ingredient.name, "limón");
+        assert_eq!(result[1].ingredient.amount, 3.0);
+    }
+
+    #[test]
+    fn strips_locale_descriptors() {
+        let ingredients = vec![ing("cebolla picada", 1.0, ""), ing("cebollas", 2.0, "")];
+        let result = merge_with_options(&ingredients, &spanish());
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.name, "cebolla");
+        assert_eq!(result[0].ingredient.note.as_deref(), Some("picada"));
+    }
+
+    #[test]
+    fn locale_is_chosen_per_call() {
+        let ingredients = vec![ing("harina", 1.0, "taza"), ing("harina", 1.0, "cup")];
+        assert_eq!(merge_with_options(&ingredients, &spanish()).len(), 1);
+        assert_eq!(merge(&ingredients).len(), 2);
+    }
+
+    // ── provenance ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn lists_the_sources_merged_into_each_item() {
+        let ingredients = vec![
+            ing("milk", 1.0, "cup").with_source("pancakes"),
+            ing("milk", 2.0, "cup").with_source("custard"),
+            ing("sugar", 0.5, "cup"),
+        ];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        let milk = result.iter().find(|i| i.ingredient.name == "milk").unwrap();
+        let mut sources = milk
+            .sources
+            .iter()
+            .map(|s| (s.source.as_deref(), s.amount, s.unit.as_str()))
+            .collect::<Vec<_>>();
+        sources.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            sources,
+            vec![
+                (Some("custard"), 2.0, "cup"),
+                (Some("pancakes"), 1.0, "cup")
+            ]
+        );
+        let sugar = result
+            .iter()
+            .find(|i| i.ingredient.name == "sugar")
+            .unwrap();
+        assert_eq!(sugar.sources.len(), 1);
+        assert_eq!(sugar.sources[0].source, None);
+    }
+
+    #[test]
+    fn sources_keep_original_quantities_across_units() {
+        let ingredients = vec![
+            ing("butter", 2.0, "tbsp").with_source("toast"),
+            ing("butter", 1.0, "cup").with_source("cake"),
+        ];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert_eq!(result.len(), 1);
+        let units = result[0]
+            .sources
+            .iter()
+            .map(|s| (s.amount, s.unit.as_str()))
+            .collect::<Vec<_>>();
+        assert!(units.contains(&(2.0, "tbsp")));
+        assert!(units.contains(&(1.0, "cup")));
+    }
+
+    // ── ranges and to-taste items ────────────────────────────────────────────
+
+    #[test]
+    fn adds_ranges_end_by_end() {
+        let result = merge(&[
+            ing("garlic", 2.0, "clove").with_max(3.0),
+            ing("garlic", 1.0, "clove").with_max(2.0),
+        ]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].amount, 3.0);
+        assert_eq!(result[0].amount_max, Some(5.0));
+    }
+
+    #[test]
+    fn single_amounts_add_to_both_ends_of_a_range() {
+        let result = merge(&[
+            ing("flour", 1.0, "cup").with_max(2.0),
+            ing("flour", 8.0, "tbsp"),
+        ]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].unit, "cup");
+        assert!((result[0].amount - 1.5).abs() < 1e-9);
+        assert!((result[0].amount_max.unwrap() - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn merged_amounts_without_ranges_have_no_upper_end() {
+        let result = merge(&[ing("flour", 1.0, "cup"), ing("flour", 1.0, "cup")]);
+        assert_eq!(result[0].amount_max, None);
+    }
+
+    #[test]
+    fn to_taste_items_collapse_into_one_line() {
+        let ingredients = [
+            Ingredient::new_to_taste("salt"),
+            Ingredient::new_to_taste("Salt"),
+            Ingredient::new_to_taste("pepper"),
+        ];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert_eq!(result.len(), 2);
+        let salt = result.iter().find(|i| i.ingredient.name == "salt").unwrap();
+        assert!(salt.ingredient.to_taste);
+        assert_eq!(salt.sources.len(), 2);
+    }
+
+    #[test]
+    fn to_taste_items_fold_into_a_measured_item() {
+        let ingredients = [
+            Ingredient::new_to_taste("salt"),
+            ing("salt", 1.0, "tsp"),
+            ing("salt", 0.5, "tsp"),
+        ];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert_eq!(result.len(), 1);
+        let salt = &result[0].ingredient;
+        assert!(!salt.to_taste);
+        assert_eq!((salt.amount, salt.unit.as_str()), (1.5, "tsp"));
+        assert_eq!(result[0].sources.len(), 3);
+    }
+
+    #[test]
+    fn ranges_and_to_taste_items_round_trip_through_serde() {
+        let ingredients = [
+            ing("garlic", 2.0, "clove").with_max(3.0),
+            Ingredient::new_to_taste("salt"),
+        ];
+        for ingredient in ingredients {
+            let value = toml::Value::try_from(&ingredient).unwrap();
+            let read: Ingredient = value.clone().try_into().unwrap();
+            assert_eq!(read.amount_max, ingredient.amount_max);
+            assert_eq!(read.to_taste, ingredient.to_taste);
+            assert_eq!(
+                value.get("amountMax").is_some(),
+                ingredient.amount_max.is_some()
+            );
+        }
+        let plain: Ingredient =
+            toml::from_str("name = \"flour\"\namount = 1.0\nunit = \"cup\"").unwrap();
+        assert_eq!(plain.amount_max, None);
+        assert!(!plain.to_taste);
+    }
+
+    // ── ambiguous units ──────────────────────────────────────────────────────
+
+    /// What each source's unit was read as, in sorted order.
+    fn interpretations(item: &GroceryItem) -> Vec<Option<&str>> {
+        let mut interpretations = item
+            .sources
+            .iter()
+            .map(|s| s.interpreted_as.as_deref())
+            .collect::<Vec<_>>();
+        interpretations.sort();
+        interpretations
+    }
+
+    #[test]
+    fn ounces_of_liquids_are_fluid_ounces() {
+        let ingredients = vec![
+            ing("chicken broth", 8.0, "oz"),
+            ing("chicken broth", 1.0, "cup"),
+        ];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.unit, "cup");
+        assert!((result[0].ingredient.amount - 2.0).abs() < 1e-9);
+        assert_eq!(interpretations(&result[0]), vec![None, Some("fl oz")]);
+    }
+
+    #[test]
+    fn ounces_of_solids_are_weights() {
+        let ingredients = vec![ing("cheddar", 8.0, "ounces"), ing("cheddar", 0.5, "lb")];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.unit, "lb");
+        assert!((result[0].ingredient.amount - 1.0).abs() < 1e-9);
+        assert_eq!(interpretations(&result[0]), vec![None, Some("oz")]);
+    }
+
+    #[test]
+    fn fixed_readings_ignore_the_name() {
+        let options = MergeOptions {
+            ambiguous_units: AmbiguousUnits::Weight,
+            ..Default::default()
+        };
+        let result = merge_with_options(&[ing("chicken broth", 8.0, "oz")], &options);
+        assert_eq!(interpretations(&result[0]), vec![Some("oz")]);
+        let options = MergeOptions {
+            ambiguous_units: AmbiguousUnits::Volume,
+            ..Default::default()
+        };
+        let result = merge_with_options(&[ing("cheddar", 8.0, "oz")], &options);
+        assert_eq!(interpretations(&result[0]), vec![Some("fl oz")]);
+    }
+
+    #[test]
+    fn ambiguous_units_can_be_kept_apart() {
+        let options = MergeOptions {
+            ambiguous_units: AmbiguousUnits::KeepApart,
+            ..Default::default()
+        };
+        let ingredients = vec![
+            ing("chicken broth", 8.0, "oz"),
+            ing("chicken broth", 8.0, "oz"),
+            ing("chicken broth", 1.0, "cup"),
+        ];
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result.len(), 2);
+        let ounces = result.iter().find(|i| i.ingredient.unit == "oz").unwrap();
+        assert_eq!(ounces.ingredient.amount, 16.0);
+        assert_eq!(interpretations(ounces), vec![None, None]);
+    }
+
+    #[test]
+    fn callers_add_liquids() {
+        let ingredients = vec![ing("dashi", 8.0, "oz"), ing("dashi", 1.0, "cup")];
+        assert_eq!(
+            merge_with_options(&ingredients, &MergeOptions::default()).len(),
+            2
+        );
+        let options = MergeOptions {
+            liquids: vec!["Dashi".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(merge_with_options(&ingredients, &options).len(), 1);
+    }
+
+    // ── count units ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn merges_count_units_across_plural_forms() {
+        let ingredients = vec![ing("garlic", 2.0, "cloves"), ing("garlic", 1.0, "clove")];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].amount, 3.0);
+        assert_eq!(result[0].unit, "clove");
+    }
+
+    #[test]
+    fn does_not_merge_different_count_units() {
+        let ingredients = vec![ing("tomato", 1.0, "can"), ing("tomato", 2.0, "slice")];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn merges_sticks_with_spoons() {
+        let ingredients = vec![ing("butter", 1.0, "stick"), ing("butter", 2.0, "tbsp")];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].unit, "cup");
+        assert!((result[0].amount - 0.625).abs() < 1e-6);
+    }
+
+    #[test]
+    fn merges_pinches_into_teaspoons() {
+        let ingredients = vec![ing("salt", 1.0, "pinch"), ing("salt", 1.0, "tsp")];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert!((result[0].amount - 1.0625).abs() < 1e-6);
+        assert_eq!(result[0].unit, "tsp");
+    }
+
+    // ── categories ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn merged_items_are_categorized() {
+        let ingredients = vec![
+            ing("red onions", 2.0, ""),
+            ing("milk", 1.0, "cup"),
+            ing("unobtainium", 1.0, "g"),
+        ];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        let category_of = |name: &str| {
+            result
+                .iter()
+                .find(|i| i.ingredient.name == name)
+                .map(|i| i.category.as_str())
+        };
+        assert_eq!(category_of("red onion"), Some("produce"));
+        assert_eq!(category_of("milk"), Some("dairy and eggs"));
+        assert_eq!(category_of("unobtainium"), Some("other"));
+    }
+
+    #[test]
+    fn caller_categories_extend_the_bundled_table() {
+        let ingredients = vec![ing("tofu blocks", 1.0, "")];
+        let options = MergeOptions {
+            categories: [("tofu block".to_string(), "refrigerated".to_string())].into(),
+            ..Default::default()
+        };
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result[0].category, "refrigerated");
+    }
+
+    // ── display units ────────────────────────────────────────────────────────
+
+    #[test]
+    fn merged_quantity_uses_best_fit_unit() {
+        let ingredients = vec![ing("sugar", 1.0, "tsp"), ing("sugar", 4.0, "cup")];
+        let result = merge(&ingredients);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].unit, "cup");
+        assert!((result[0].amount - (4.0 + 1.0 / 48.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn original_display_unit_keeps_first_unit() {
+        let ingredients = vec![ing("sugar", 1.0, "tsp"), ing("sugar", 4.0, "cup")];
+        let options = MergeOptions {
+            display_unit: DisplayUnit::Original,
+            ..Default::default()
+        };
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result[0].ingredient.unit, "tsp");
+        assert!((result[0].ingredient.amount - 193.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fixed_display_unit_overrides_per_call() {
+        let ingredients = vec![ing("milk", 1.0, "cup"), ing("milk", 1.0, "cup")];
+        let options = MergeOptions {
+            display_unit: DisplayUnit::Fixed("ml".to_string()),
+            ..Default::default()
+        };
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result[0].ingredient.unit, "ml");
+        assert!((result[0].ingredient.amount - 473.176).abs() < 1e-3);
+    }
+
+    // ── densities ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn merges_weight_and_volume_through_density() {
+        let ingredients = vec![ing("flour", 1.0, "cup"), ing("flour", 120.0, "g")];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.unit, "g");
+        assert!((result[0].ingredient.amount - 240.0).abs() < 1e-6);
+        let conversion = result[0].density_conversion.as_ref().unwrap();
+        assert_eq!(conversion.matched_name, "flour");
+    }
+
+    #[test]
+    fn same_unit_merge_is_not_flagged() {
+        let ingredients = vec![ing("flour", 1.0, "cup"), ing("flour", 1.0, "cup")];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert!(result[0].density_conversion.is_none());
+    }
+
+    #[test]
+    fn caller_densities_extend_the_bundled_table() {
+        let ingredients = vec![ing("saffron", 1.0, "cup"), ing("saffron", 10.0, "g")];
+        let options = MergeOptions {
+            densities: [("Saffron".to_string(), 50.0)].into(),
+            ..Default::default()
+        };
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result.len(), 1);
+        assert!((result[0].ingredient.amount - 60.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bundled_density_names_are_normalized_like_ingredients() {
+        // "molasses" lemmatizes to "molass", which must still find its density
+        let ingredients = vec![ing("molasses", 1.0, "cup"), ing("molasses", 10.0, "g")];
+        let result = merge_with_options(&ingredients, &MergeOptions::default());
+        assert_eq!(result.len(), 1);
+        assert!(result[0].density_conversion.is_some());
+    }
+
+    #[test]
+    fn metric_unit_system_reexpresses_cups() {
+        let ingredients = vec![ing("milk", 1.0, "cup"), ing("milk", 1.0, "cup")];
+        let options = MergeOptions {
+            unit_system: UnitSystem::Metric,
+            ..Default::default()
+        };
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result[0].ingredient.unit, "ml");
+        assert!((result[0].ingredient.amount - 473.176).abs() < 1e-3);
+    }
+
+    #[test]
+    fn us_unit_system_reexpresses_grams() {
+        let ingredients = vec![ing("beef", 500.0, "g"), ing("beef", 500.0, "g")];
+        let options = MergeOptions {
+            unit_system: UnitSystem::UsCustomary,
+            ..Default::default()
+        };
+        let result = merge_with_options(&ingredients, &options);
+        assert_eq!(result[0].ingredient.unit, "lb");
+        assert!((result[0].ingredient.amount - 2.20462).abs() < 1e-4);
+    }
+
     // ── edge cases ───────────────────────────────────────────────────────────
 
     #[test]
     fn empty_input_returns_empty() {
-        let result = merge(&vec![]);
+        let result = merge(&[]);
         assert!(result.is_empty());
     }
 
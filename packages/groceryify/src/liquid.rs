@@ -0,0 +1,100 @@
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::{ingredient_name::IngredientName, ingredient_unit::Reading, name_table::NameTable};
+
+static BUNDLED: OnceLock<LiquidTable> = OnceLock::new();
+
+#[derive(Deserialize)]
+struct Liquids {
+    liquids: Vec<String>,
+    solids: Vec<String>,
+}
+
+/// Whether ingredients are liquids, for reading "8 oz" as fluid ounces or as a weight, keyed by
+/// normalized ingredient name.
+#[derive(Clone, Debug)]
+pub struct LiquidTable {
+    is_liquid: NameTable<bool>,
+}
+
+impl LiquidTable {
+    pub fn new() -> Self {
+        let data = include_str!("../resources/liquids.toml");
+        let data: Liquids = toml::from_str(data).unwrap();
+        let liquids = data.liquids.into_iter().map(|name| (name, true));
+        let solids = data.solids.into_iter().map(|name| (name, false));
+        Self {
+            is_liquid: liquids.chain(solids).collect(),
+        }
+    }
+
+    /// The bundled table, shared by every caller that doesn't add its own liquids.
+    pub(crate) fn bundled() -> &'static LiquidTable {
+        BUNDLED.get_or_init(Self::new)
+    }
+
+    /// Rewrites every name with `normalize`.
+    pub fn map_names<F: Fn(&str) -> String>(self, normalize: F) -> Self {
+        Self {
+            is_liquid: self.is_liquid.map_names(normalize),
+        }
+    }
+
+    /// Adds liquids under normalized names.
+    pub fn extend<I: IntoIterator<Item = String>>(&mut self, liquids: I) {
+        self.is_liquid
+            .extend(liquids.into_iter().map(|name| (name, true)));
+    }
+
+    /// Whether an ingredient is a liquid, going by the longest entry its name ends with, so
+    /// "whole milk" is a liquid like "milk" and "milk chocolate" is not.
+    pub fn is_liquid(&self, name: &str) -> bool {
+        let name = IngredientName(name.to_lowercase())
+            .remove_parenthesized()
+            .remove_after_comma();
+        self.is_liquid
+            .lookup(name.0.trim())
+            .is_some_and(|(_, liquid)| *liquid)
+    }
+
+    /// How to read "oz" and other units of both a volume and a weight for an ingredient.
+    pub(crate) fn reading(&self, name: &str) -> Reading {
+        if self.is_liquid(name) {
+            Reading::Volume
+        } else {
+            Reading::Mass
+        }
+    }
+}
+
+impl Default for LiquidTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_entry_decides() {
+        let table = LiquidTable::new();
+        assert!(table.is_liquid("milk"));
+        assert!(table.is_liquid("whole milk"));
+        assert!(table.is_liquid("Heavy Cream, cold"));
+        assert!(!table.is_liquid("sour cream"));
+        assert!(!table.is_liquid("milk chocolate"));
+        assert!(!table.is_liquid("cheddar"));
+    }
+
+    #[test]
+    fn extends_with_more_liquids() {
+        let mut table = LiquidTable::new();
+        assert!(!table.is_liquid("dashi"));
+        table.extend(["dashi".to_string()]);
+        assert!(table.is_liquid("dashi"));
+    }
+}
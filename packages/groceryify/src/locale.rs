@@ -0,0 +1,430 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    sync::{Arc, OnceLock},
+};
+
+use serde::Deserialize;
+
+use crate::{ingredient_unit::UnitRegistry, lemmatizer::Lemmatizer};
+
+static ENGLISH: OnceLock<Locale> = OnceLock::new();
+static SPANISH: OnceLock<Locale> = OnceLock::new();
+static GERMAN: OnceLock<Locale> = OnceLock::new();
+
+/// Failure to load a locale.
+#[derive(Debug)]
+pub enum LocaleError {
+    /// No bundled locale has the code.
+    Unknown(String),
+    /// The TOML is not a locale.
+    Parse(toml::de::Error),
+    /// A unit entry is keyed by something other than a bundled canonical unit, such as "tbsp".
+    UnknownUnit(String),
+}
+
+impl std::fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(code) => write!(f, "Unknown locale: {code}"),
+            Self::Parse(e) => write!(f, "Failed to parse locale data: {e}"),
+            Self::UnknownUnit(u) => write!(f, "Locale names unknown unit: {u}"),
+        }
+    }
+}
+
+impl Error for LocaleError {}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct LocaleUnit {
+    /// Singular display name; units without one keep their bundled spelling.
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    plural: Option<String>,
+    /// Other spellings read as this unit, in addition to the display names.
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Plurals {
+    #[serde(default)]
+    exceptions: HashMap<String, String>,
+    #[serde(default)]
+    singular_rules: Vec<(String, String)>,
+    #[serde(default)]
+    plural_rules: Vec<(String, String)>,
+    suffix: String,
+}
+
+#[derive(Deserialize)]
+struct LocaleData {
+    code: String,
+    #[serde(default)]
+    decimal_comma: bool,
+    #[serde(default)]
+    head_noun_first: bool,
+    #[serde(default)]
+    articles: Vec<String>,
+    #[serde(default)]
+    connectors: Vec<String>,
+    #[serde(default)]
+    descriptors: Vec<String>,
+    #[serde(default)]
+    liquids: Vec<String>,
+    #[serde(default)]
+    range_words: Vec<String>,
+    #[serde(default)]
+    to_taste: Vec<String>,
+    plurals: Plurals,
+    #[serde(default)]
+    units: HashMap<String, LocaleUnit>,
+}
+
+/// The language an ingredient list is written in: how its units are spelled, how its nouns form
+/// plurals and how its amounts are written.
+///
+/// English, Spanish ("es") and German ("de") are bundled. Other locales can be loaded from TOML
+/// with the layout of `resources/locales/es.toml`.
+#[derive(Clone, Debug)]
+pub struct Locale {
+    code: String,
+    decimal_comma: bool,
+    head_noun_first: bool,
+    articles: Vec<String>,
+    connectors: Vec<String>,
+    descriptors: Vec<String>,
+    /// Ingredients measured by volume, for reading "oz" as fluid ounces.
+    liquids: Vec<String>,
+    /// Words joining the ends of a range, as in "2 to 3 cloves".
+    range_words: Vec<String>,
+    /// Phrases marking an ingredient used without a measured amount, as in "salt to taste". The
+    /// first is the one written for such items.
+    to_taste: Vec<String>,
+    /// Shared with every merger built for this locale, which would otherwise copy its tables.
+    lemmatizer: Arc<Lemmatizer>,
+    /// Display names keyed by bundled canonical unit.
+    units: HashMap<String, LocaleUnit>,
+    /// The bundled units plus this locale's spellings of them.
+    registry: UnitRegistry,
+}
+
+impl Locale {
+    /// English, the language of the bundled unit tables and lemmatizer.
+    pub fn english() -> &'static Locale {
+        ENGLISH.get_or_init(|| Locale {
+            code: "en".to_string(),
+            decimal_comma: false,
+            head_noun_first: false,
+            articles: vec!["a".to_string(), "an".to_string()],
+            connectors: vec!["of".to_string()],
+            descriptors: vec![],
+            liquids: vec![],
+            range_words: vec!["to".to_string()],
+            to_taste: [
+                "to taste",
+                "as needed",
+                "for frying",
+                "for serving",
+                "for garnish",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            lemmatizer: Arc::new(Lemmatizer::new()),
+            units: HashMap::new(),
+            registry: UnitRegistry::bundled().clone(),
+        })
+    }
+
+    /// Looks up a bundled locale by its language code: "en", "es" or "de".
+    pub fn bundled(code: &str) -> Option<&'static Locale> {
+        let load = |data: &str| Locale::from_toml(data).unwrap();
+        match code.trim().to_lowercase().as_str() {
+            "en" => Some(Self::english()),
+            "es" => {
+                Some(SPANISH.get_or_init(|| load(include_str!("../resources/locales/es.toml"))))
+            }
+            "de" => Some(GERMAN.get_or_init(|| load(include_str!("../resources/locales/de.toml")))),
+            _ => None,
+        }
+    }
+
+    /// The bundled locale for `code`, or English if there is no code.
+    pub fn lookup(code: Option<&str>) -> Result<&'static Locale, LocaleError> {
+        match code {
+            Some(code) => Self::bundled(code).ok_or_else(|| LocaleError::Unknown(code.to_string())),
+            None => Ok(Self::english()),
+        }
+    }
+
+    /// Like [`Locale::lookup`], but English for an unknown code, for options whose locale the
+    /// caller has already checked.
+    pub(crate) fn resolve(code: Option<&str>) -> &'static Locale {
+        Self::lookup(code).unwrap_or_else(|_| Self::english())
+    }
+
+    /// Loads a locale. Its units are keyed by the bundled canonical unit they spell, e.g.
+    /// `[units.tbsp]`.
+    pub fn from_toml(data: &str) -> Result<Self, LocaleError> {
+        let data: LocaleData = toml::from_str(data).map_err(LocaleError::Parse)?;
+        let bundled = UnitRegistry::bundled();
+        if let Some(unit) = data
+            .units
+            .keys()
+            .find(|unit| bundled.canonical(unit) != Some(unit.as_str()))
+        {
+            return Err(LocaleError::UnknownUnit(unit.clone()));
+        }
+        let mut registry = bundled.clone();
+        add_units(&data.code, &data.units, &mut registry);
+        let plurals = data.plurals;
+        Ok(Locale {
+            code: data.code,
+            decimal_comma: data.decimal_comma,
+            head_noun_first: data.head_noun_first,
+            articles: data.articles,
+            connectors: data.connectors,
+            descriptors: data.descriptors,
+            liquids: data.liquids,
+            range_words: data.range_words,
+            to_taste: data.to_taste,
+            lemmatizer: Arc::new(Lemmatizer::with_rules(
+                plurals.exceptions,
+                plurals.singular_rules,
+                plurals.plural_rules,
+                &plurals.suffix,
+            )),
+            units: data.units,
+            registry,
+        })
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Whether amounts are written with a decimal comma, e.g. "1,5".
+    pub fn decimal_comma(&self) -> bool {
+        self.decimal_comma
+    }
+
+    /// The bundled units plus this locale's spellings of them.
+    pub fn unit_registry(&self) -> &UnitRegistry {
+        &self.registry
+    }
+
+    /// Adds this locale's spellings of the bundled units to `registry`.
+    pub(crate) fn add_units(&self, registry: &mut UnitRegistry) {
+        add_units(&self.code, &self.units, registry);
+    }
+
+    pub(crate) fn lemmatizer(&self) -> &Arc<Lemmatizer> {
+        &self.lemmatizer
+    }
+
+    pub(crate) fn descriptors(&self) -> &[String] {
+        &self.descriptors
+    }
+
+    pub(crate) fn liquids(&self) -> &[String] {
+        &self.liquids
+    }
+
+    /// Whether `word` stands for "one", as in "a cup of milk".
+    pub(crate) fn is_article(&self, word: &str) -> bool {
+        self.articles.iter().any(|a| a.eq_ignore_ascii_case(word))
+    }
+
+    /// Whether `word` joins a unit to the name, as in "a cup of milk".
+    pub(crate) fn is_connector(&self, word: &str) -> bool {
+        self.connectors.iter().any(|c| c.eq_ignore_ascii_case(word))
+    }
+
+    /// Whether `word` joins the ends of a range, as in "2 to 3 cloves".
+    pub(crate) fn is_range_word(&self, word: &str) -> bool {
+        self.range_words
+            .iter()
+            .any(|w| w.eq_ignore_ascii_case(word))
+    }
+
+    /// Splits a to-taste phrase off the end of `text`, as in "salt to taste", returning the
+    /// rest of the text with the phrase.
+    pub(crate) fn strip_to_taste(&self, text: &str) -> Option<(String, &str)> {
+        let words = text.split_whitespace().collect::<Vec<_>>();
+        self.to_taste.iter().find_map(|phrase| {
+            let phrase_words = phrase.split_whitespace().collect::<Vec<_>>();
+            let rest = words.len().checked_sub(phrase_words.len())?;
+            words[rest..]
+                .iter()
+                .zip(&phrase_words)
+                .all(|(word, p)| word.to_lowercase() == p.to_lowercase())
+                .then(|| (words[..rest].join(" "), phrase.as_str()))
+        })
+    }
+
+    /// The phrase written for an ingredient used without a measured amount, e.g. "to taste".
+    pub(crate) fn to_taste_label(&self) -> &str {
+        self.to_taste.first().map_or("", String::as_str)
+    }
+
+    /// The display name of a bundled canonical unit, if this locale spells it differently.
+    pub(crate) fn unit_name(&self, canonical: &str, plural: bool) -> Option<&str> {
+        let unit = self.units.get(canonical)?;
+        match (plural, &unit.plural) {
+            (true, Some(plural)) => Some(plural),
+            _ => unit.name.as_deref(),
+        }
+    }
+
+    /// Pluralizes a name's head noun: its last word, or its first in languages such as Spanish
+    /// ("diente de ajo" -> "dientes de ajo"). Words that are plural already are left alone.
+    pub(crate) fn pluralize_name(&self, name: &str) -> String {
+        let words = name.split(' ').collect::<Vec<_>>();
+        let head = if self.head_noun_first {
+            0
+        } else {
+            words.len() - 1
+        };
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| match i == head {
+                true => self.pluralize_word(word),
+                false => word.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn pluralize_word(&self, word: &str) -> String {
+        if word.is_empty() || self.lemmatizer.lemmatize(word) != word.to_lowercase() {
+            word.to_string()
+        } else {
+            self.lemmatizer.pluralize(word)
+        }
+    }
+}
+
+/// Adds a locale's spellings of the bundled units to `registry`.
+fn add_units(code: &str, units: &HashMap<String, LocaleUnit>, registry: &mut UnitRegistry) {
+    for (canonical, unit) in units {
+        let aliases = unit
+            .name
+            .iter()
+            .chain(&unit.plural)
+            .chain(&unit.aliases)
+            .map(|alias| alias.to_lowercase())
+            .collect();
+        registry.add_aliases(&format!("{code}:{canonical}"), canonical, aliases);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spanish() -> &'static Locale {
+        Locale::bundled("es").unwrap()
+    }
+
+    fn german() -> &'static Locale {
+        Locale::bundled("de").unwrap()
+    }
+
+    #[test]
+    fn bundled_locales_load() {
+        assert_eq!(spanish().code(), "es");
+        assert_eq!(german().code(), "de");
+        assert_eq!(Locale::bundled("EN").unwrap().code(), "en");
+        assert!(Locale::bundled("fr").is_none());
+    }
+
+    #[test]
+    fn unknown_codes_are_errors() {
+        assert_eq!(Locale::lookup(None).unwrap().code(), "en");
+        assert_eq!(Locale::lookup(Some("de")).unwrap().code(), "de");
+        let error = Locale::lookup(Some("fr")).unwrap_err();
+        assert!(matches!(error, LocaleError::Unknown(ref code) if code == "fr"));
+        assert_eq!(error.to_string(), "Unknown locale: fr");
+        assert_eq!(Locale::resolve(Some("fr")).code(), "en");
+    }
+
+    #[test]
+    fn locale_units_are_read_as_bundled_units() {
+        let registry = spanish().unit_registry();
+        assert_eq!(registry.canonical("cucharadas"), Some("tbsp"));
+        assert_eq!(registry.canonical("gramos"), Some("g"));
+        assert_eq!(german().unit_registry().canonical("el"), Some("tbsp"));
+        assert_eq!(UnitRegistry::bundled().canonical("cucharadas"), None);
+    }
+
+    #[test]
+    fn locale_units_have_display_names() {
+        assert_eq!(spanish().unit_name("cup", false), Some("taza"));
+        assert_eq!(spanish().unit_name("cup", true), Some("tazas"));
+        assert_eq!(spanish().unit_name("g", true), None);
+        assert_eq!(german().unit_name("tbsp", true), Some("EL"));
+    }
+
+    #[test]
+    fn spanish_plurals() {
+        let lemmatizer = spanish().lemmatizer();
+        assert_eq!(lemmatizer.lemmatize("limones"), "limón");
+        assert_eq!(lemmatizer.lemmatize("nueces"), "nuez");
+        assert_eq!(lemmatizer.lemmatize("tomates"), "tomate");
+        assert_eq!(lemmatizer.lemmatize("chiles"), "chile");
+        assert_eq!(lemmatizer.pluralize("limón"), "limones");
+        assert_eq!(lemmatizer.pluralize("huevo"), "huevos");
+        assert_eq!(spanish().pluralize_name("diente de ajo"), "dientes de ajo");
+    }
+
+    #[test]
+    fn german_plurals() {
+        let lemmatizer = german().lemmatizer();
+        assert_eq!(lemmatizer.lemmatize("Zwiebeln"), "zwiebel");
+        assert_eq!(lemmatizer.lemmatize("Karotten"), "karotte");
+        assert_eq!(lemmatizer.lemmatize("Eier"), "ei");
+        assert_eq!(lemmatizer.lemmatize("Kuchen"), "kuchen");
+        assert_eq!(lemmatizer.pluralize("ei"), "eier");
+        assert_eq!(german().pluralize_name("rote zwiebel"), "rote zwiebeln");
+    }
+
+    #[test]
+    fn loads_a_user_locale() {
+        let locale = Locale::from_toml(
+            r#"
+            code = "fr"
+            decimal_comma = true
+            [plurals]
+            singular_rules = [["s", ""]]
+            suffix = "s"
+            [units.tbsp]
+            name = "c. à soupe"
+            aliases = ["cuillère à soupe", "cuillères à soupe"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(locale.code(), "fr");
+        assert!(locale.decimal_comma());
+        assert_eq!(
+            locale.unit_registry().canonical("cuillères à soupe"),
+            Some("tbsp")
+        );
+        assert_eq!(locale.unit_name("tbsp", true), Some("c. à soupe"));
+    }
+
+    #[test]
+    fn user_locale_units_must_be_bundled_units() {
+        let result = Locale::from_toml(
+            r#"
+            code = "fr"
+            [plurals]
+            suffix = "s"
+            [units."cuillère"]
+            name = "cuillère"
+            "#,
+        );
+        assert!(matches!(result, Err(LocaleError::UnknownUnit(u)) if u == "cuillère"));
+    }
+}
@@ -0,0 +1,447 @@
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+use crate::{
+    category::CategoryTable,
+    conversion::{self, ConvertError},
+    cost::{self, CostEstimate, Price},
+    density::DensityTable,
+    descriptor::DescriptorList,
+    diagnostics::{self, MergeReport},
+    grocery_item::{GroceryItem, SourceQuantity},
+    ingredient::Ingredient,
+    ingredient_name::IngredientName,
+    ingredient_unit::{Quantity, Reading, UnitRegistry},
+    lemmatizer::Lemmatizer,
+    liquid::LiquidTable,
+    locale::Locale,
+    nutrition::{self, NutrientTable, NutritionReport},
+    options::{AmbiguousUnits, MergeOptions},
+    package::{self, PackageTable},
+    pantry::{self, CoveredItems},
+    parsed_ingredient::ParsedIngredient,
+    synonym::SynonymTable,
+};
+
+/// Reduces an ingredient name to the form synonyms are looked up in, without parenthesized
+/// notes or anything after a comma, and lemmatized. Descriptors are kept, so aliases such as
+/// "minced beef" aren't read as plain "beef".
+fn base_name(name: &str, lemmatizer: &Lemmatizer) -> String {
+    IngredientName(name.to_string())
+        .remove_parenthesized()
+        .remove_after_comma()
+        .lemmatize(lemmatizer)
+        .0
+}
+
+/// Normalizes names and then replaces synonyms by their canonical name, using the bundled
+/// descriptor list and synonym table plus the locale's and the caller's additions.
+pub(crate) struct NameNormalizer {
+    lemmatizer: Arc<Lemmatizer>,
+    descriptors: DescriptorList,
+    synonyms: SynonymTable,
+}
+
+impl NameNormalizer {
+    pub(crate) fn new(options: &MergeOptions, locale: &Locale) -> Self {
+        let lemmatizer = locale.lemmatizer().clone();
+        let mut descriptors = DescriptorList::new();
+        descriptors.extend(locale.descriptors().iter().cloned());
+        descriptors.extend(options.descriptors.iter().cloned());
+        let clean = |name: &str| base_name(name, &lemmatizer);
+        let mut synonyms = SynonymTable::new().map_names(clean);
+        synonyms.extend(
+            options
+                .synonyms
+                .iter()
+                .map(|(alias, name)| (clean(alias), clean(name))),
+        );
+        Self {
+            lemmatizer,
+            descriptors,
+            synonyms,
+        }
+    }
+
+    /// Normalizes a name, also returning the descriptors that were stripped from it.
+    pub(crate) fn normalize_with_notes(&self, name: &str) -> (String, Vec<String>) {
+        // Synonyms are replaced before descriptors are stripped, so an alias holding one still
+        // matches, and again after, for aliases a descriptor after them hid
+        let name = self
+            .synonyms
+            .canonicalize(&base_name(name, &self.lemmatizer));
+        let (name, stripped) = self.descriptors.strip(&name);
+        (self.synonyms.canonicalize(&name), stripped)
+    }
+
+    pub(crate) fn normalize(&self, name: &str) -> String {
+        self.normalize_with_notes(name).0
+    }
+}
+
+/// How many mergers [`Merger::shared`] keeps, for callers that alternate between option sets.
+const SHARED_MERGERS: usize = 8;
+
+/// Merges ingredient lists with units, names and tables that are loaded once and reused for
+/// every call, so repeated merges and long meal plans don't pay for them again.
+pub struct Merger {
+    options: MergeOptions,
+    registry: UnitRegistry,
+    names: NameNormalizer,
+    densities: DensityTable,
+    categories: CategoryTable,
+    liquids: LiquidTable,
+    packages: PackageTable,
+    /// Only needed for nutrition estimates, so built the first time one is made.
+    nutrients: OnceLock<NutrientTable>,
+}
+
+impl Merger {
+    /// A merger with the bundled units.
+    pub fn new(options: MergeOptions) -> Self {
+        Self::with_registry(options, UnitRegistry::bundled().clone())
+    }
+
+    /// A merger that reads quantities with `registry`, e.g. one loaded with the user's own units,
+    /// plus the spellings of the locale `options` names.
+    pub fn with_registry(options: MergeOptions, registry: UnitRegistry) -> Self {
+        let locale = Locale::resolve(options.locale.as_deref());
+        Self::with_locale(options, registry, locale)
+    }
+
+    /// A merger for ingredients written in `locale`, e.g. one loaded from the user's own TOML,
+    /// in place of the locale `options` names.
+    pub fn with_locale(options: MergeOptions, mut registry: UnitRegistry, locale: &Locale) -> Self {
+        locale.add_units(&mut registry);
+        let names = NameNormalizer::new(&options, locale);
+        let mut densities = DensityTable::new().map_names(|name| names.normalize(name));
+        densities.extend(
+            options
+                .densities
+                .iter()
+                .map(|(name, grams)| (names.normalize(name), *grams)),
+        );
+        let mut categories = CategoryTable::new().map_names(|name| names.normalize(name));
+        categories.extend(
+            options
+                .categories
+                .iter()
+                .map(|(name, category)| (names.normalize(name), category.clone())),
+        );
+        let mut liquids = LiquidTable::new().map_names(|name| names.normalize(name));
+        liquids.extend(
+            locale
+                .liquids()
+                .iter()
+                .chain(&options.liquids)
+                .map(|name| names.normalize(name)),
+        );
+        let mut packages = PackageTable::new().map_names(|name| names.normalize(name));
+        packages.extend(
+            options
+                .packages
+                .iter()
+                .map(|(name, sizes)| (names.normalize(name), sizes.clone())),
+        );
+        Self {
+            options,
+            registry,
+            names,
+            densities,
+            categories,
+            liquids,
+            packages,
+            nutrients: OnceLock::new(),
+        }
+    }
+
+    /// A merger for `options`, reusing one built by an earlier call with the same options, so
+    /// the free functions such as [`crate::merge_with_options`] don't rebuild the tables on every
+    /// call. The last few option sets used are kept.
+    pub(crate) fn shared(options: &MergeOptions) -> Arc<Merger> {
+        static SHARED: Mutex<Vec<Arc<Merger>>> = Mutex::new(Vec::new());
+        let find = |mergers: &[Arc<Merger>]| {
+            mergers
+                .iter()
+                .find(|merger| merger.options() == options)
+                .cloned()
+        };
+        let lock = || SHARED.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(merger) = find(&lock()) {
+            return merger;
+        }
+        // Built without holding the lock, so callers don't wait on each other's tables
+        let merger = Arc::new(Merger::new(options.clone()));
+        let mut mergers = lock();
+        if let Some(merger) = find(&mergers) {
+            return merger;
+        }
+        if mergers.len() == SHARED_MERGERS {
+            mergers.remove(0);
+        }
+        mergers.push(merger.clone());
+        merger
+    }
+
+    pub fn options(&self) -> &MergeOptions {
+        &self.options
+    }
+
+    pub fn registry(&self) -> &UnitRegistry {
+        &self.registry
+    }
+
+    pub(crate) fn names(&self) -> &NameNormalizer {
+        &self.names
+    }
+
+    pub(crate) fn densities(&self) -> &DensityTable {
+        &self.densities
+    }
+
+    pub(crate) fn packages(&self) -> &PackageTable {
+        &self.packages
+    }
+
+    pub(crate) fn quantity(&self, ingredient: &Ingredient) -> Quantity {
+        Quantity::with_registry(ingredient, &self.registry, self.reading(ingredient))
+    }
+
+    /// How to read the ingredient's unit if it spells both a volume and a weight, such as "oz".
+    pub(crate) fn reading(&self, ingredient: &Ingredient) -> Option<Reading> {
+        if !self.registry.is_ambiguous(&ingredient.unit) {
+            return None;
+        }
+        match self.options.ambiguous_units {
+            AmbiguousUnits::ByName => Some(
+                self.liquids
+                    .reading(&self.names.normalize(&ingredient.name)),
+            ),
+            AmbiguousUnits::Weight => Some(Reading::Mass),
+            AmbiguousUnits::Volume => Some(Reading::Volume),
+            AmbiguousUnits::KeepApart => None,
+        }
+    }
+
+    /// Reads an ingredient's quantity, or both ends of its range, under `name`, with the
+    /// ingredient as its only source.
+    pub(crate) fn parse(&self, name: &str, ingredient: &Ingredient) -> ParsedIngredient {
+        if ingredient.to_taste {
+            return ParsedIngredient {
+                sources: vec![ingredient.into()],
+                ..ParsedIngredient::new_to_taste(name)
+            };
+        }
+        let quantity = self.quantity(ingredient);
+        let interpreted_as = match &quantity {
+            Quantity::Known { original_unit, .. } if self.reading(ingredient).is_some() => {
+                Some(original_unit.clone())
+            }
+            _ => None,
+        };
+        let max = ingredient.amount_max.map(|max| {
+            self.quantity(&Ingredient {
+                amount: max,
+                ..ingredient.clone()
+            })
+        });
+        ParsedIngredient {
+            max,
+            sources: vec![SourceQuantity {
+                interpreted_as,
+                ..ingredient.into()
+            }],
+            ..ParsedIngredient::new(name, quantity)
+        }
+    }
+
+    /// Takes a list of ingredients and merges like ingredients to create a grocery list.
+    ///
+    /// # Arguments:
+    /// * `ingredients`: List of ingredients.
+    ///
+    /// # Returns:
+    /// * List of grocery items, with like ingredients merged. Weights and volumes of an
+    ///   ingredient with a known density are merged into one item that records the density used.
+    ///   Descriptors stripped from names, such as "chopped" or "large", and the ingredients' own
+    ///   notes are kept in the merged item's note. Each item lists the ingredients merged into
+    ///   it, with their source tags and original quantities, and the unit an ambiguous unit
+    ///   such as "oz" was read as. Ranges are added end by end, and
+    ///   an ingredient used to taste is folded into the measured item of the same name, or
+    ///   listed once if there is none.
+    pub fn merge(&self, ingredients: &[Ingredient]) -> Vec<GroceryItem> {
+        self.finish(self.combine(self.read(ingredients)))
+    }
+
+    /// Normalizes each ingredient's name and reads its quantity, ready to be combined.
+    pub(crate) fn read(&self, ingredients: &[Ingredient]) -> Vec<ParsedIngredient> {
+        ingredients
+            .iter()
+            .map(|i| {
+                let (normalized_name, mut notes) = self.names.normalize_with_notes(&i.name);
+                notes.extend(i.note.clone());
+                ParsedIngredient {
+                    notes,
+                    ..self.parse(&normalized_name, i)
+                }
+            })
+            .collect()
+    }
+
+    /// Adds up like ingredients. Ingredients that were already combined can be combined again
+    /// with more, so a list can grow without reading what is on it again.
+    pub(crate) fn combine(&self, mut ingredients: Vec<ParsedIngredient>) -> Vec<ParsedIngredient> {
+        ingredients.sort();
+        ingredients
+            .into_iter()
+            .fold(vec![], |mut acc: Vec<ParsedIngredient>, next| {
+                let last = acc.last_mut();
+                if let Some(last) = last
+                    && last.name == next.name
+                {
+                    let merged = if last.to_taste || next.to_taste {
+                        Ok(last.clone().merge_to_taste(next.clone()))
+                    } else if last.quantity.unit_key() == next.quantity.unit_key() {
+                        last.clone().merge(next.clone())
+                    } else if let Some(density) = self.densities.lookup(&last.name) {
+                        last.clone().merge_with_density(next.clone(), density)
+                    } else {
+                        acc.push(next);
+                        return acc;
+                    };
+                    match merged {
+                        Ok(merged) => {
+                            *last = merged;
+                        }
+                        Err(_) => {
+                            acc.push(next);
+                        }
+                    }
+                    return acc;
+                }
+                acc.push(next);
+                acc
+            })
+    }
+
+    /// Turns combined ingredients into grocery items in the options' units, one per ingredient.
+    pub(crate) fn finish(&self, ingredients: Vec<ParsedIngredient>) -> Vec<GroceryItem> {
+        ingredients
+            .into_iter()
+            .map(|i| {
+                let quantity = i
+                    .quantity
+                    .in_unit_system(self.options.unit_system)
+                    .with_display_unit(&self.options.display_unit);
+                ParsedIngredient {
+                    max: i.max.map(|max| max.with_unit_of(&quantity)),
+                    quantity,
+                    ..i
+                }
+            })
+            .map(|i| GroceryItem {
+                category: self.categories.categorize(&i.name),
+                ..i.into()
+            })
+            .collect::<Vec<GroceryItem>>()
+    }
+
+    /// Merges a list like [`Merger::merge`], also explaining why any ingredient was left on more
+    /// than one line, e.g. "8 oz" and "1 cup" of cream cheese with no known density.
+    pub fn merge_with_diagnostics(&self, ingredients: &[Ingredient]) -> MergeReport {
+        let items = self.merge(ingredients);
+        MergeReport {
+            diagnostics: diagnostics::diagnose(&items, self),
+            items,
+        }
+    }
+
+    /// Subtracts on-hand pantry quantities from a list this merger produced. See
+    /// [`crate::pantry::subtract_pantry`].
+    pub fn subtract_pantry(
+        &self,
+        items: Vec<GroceryItem>,
+        pantry: &[Ingredient],
+        covered: CoveredItems,
+    ) -> Vec<GroceryItem> {
+        pantry::subtract_with(self, items, pantry, covered)
+    }
+
+    /// Works out what to buy for each item of a list this merger produced. See
+    /// [`crate::package::round_to_packages`].
+    pub fn round_to_packages(&self, items: Vec<GroceryItem>) -> Vec<GroceryItem> {
+        package::round_with(self, items)
+    }
+
+    /// Estimates what a list this merger produced costs. See [`crate::cost::estimate_cost`].
+    pub fn estimate_cost(&self, items: &[GroceryItem], prices: &[Price]) -> CostEstimate {
+        cost::estimate_with(self, items, prices)
+    }
+
+    /// Converts an ingredient's amount to another unit. See [`crate::conversion::convert_unit`].
+    pub fn convert_unit(
+        &self,
+        ingredient: &Ingredient,
+        unit: &str,
+    ) -> Result<Ingredient, ConvertError> {
+        conversion::convert_with(self, ingredient, unit)
+    }
+
+    /// Estimates the nutrition of a recipe. See [`crate::nutrition::estimate_nutrition`].
+    pub fn estimate_nutrition(
+        &self,
+        ingredients: &[Ingredient],
+        servings: Option<f64>,
+    ) -> NutritionReport {
+        let table = self
+            .nutrients
+            .get_or_init(|| NutrientTable::new().map_names(|name| self.names.normalize(name)));
+        nutrition::estimate_with(self, table, ingredients, servings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_one_merger_across_calls() {
+        let merger = Merger::new(MergeOptions::default());
+        let first = merger.merge(&[Ingredient::new("flour", 1.0, "cup")]);
+        let second = merger.merge(&[
+            Ingredient::new("flour", 1.0, "cup"),
+            Ingredient::new("flour", 1.0, "cup"),
+        ]);
+        assert_eq!(first[0].ingredient.amount, 1.0);
+        assert_eq!(second[0].ingredient.amount, 2.0);
+    }
+
+    #[test]
+    fn merges_custom_units_from_its_registry() {
+        let registry = UnitRegistry::from_toml(
+            r#"
+            [knob]
+            canonical = "knob"
+            fuzzy = ["knobs"]
+            equals = "15 g"
+            "#,
+        )
+        .unwrap();
+        let merger = Merger::with_registry(MergeOptions::default(), registry);
+        let result = merger.merge(&[
+            Ingredient::new("butter", 2.0, "knobs"),
+            Ingredient::new("butter", 20.0, "g"),
+        ]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.unit, "g");
+        assert!((result[0].ingredient.amount - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keeps_custom_units_apart_without_its_registry() {
+        let result = Merger::new(MergeOptions::default()).merge(&[
+            Ingredient::new("butter", 2.0, "knobs"),
+            Ingredient::new("butter", 20.0, "g"),
+        ]);
+        assert_eq!(result.len(), 2);
+    }
+}
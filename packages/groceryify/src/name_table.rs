@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::ingredient_name::IngredientName;
+
+/// Entries keyed by normalized ingredient name, as the bundled density, category, package and
+/// other tables keep them. A name that has no entry of its own falls back to the shorter names
+/// it ends with.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct NameTable<T> {
+    entries: HashMap<String, T>,
+}
+
+impl<T> NameTable<T> {
+    /// Rewrites every name with `normalize`, so the table matches names that went through the
+    /// same normalization.
+    pub(crate) fn map_names<F: Fn(&str) -> String>(self, normalize: F) -> Self {
+        self.entries
+            .into_iter()
+            .map(|(name, entry)| (normalize(&name), entry))
+            .collect()
+    }
+
+    /// Adds or replaces entries. Names must already be normalized the way `merge` normalizes
+    /// ingredient names.
+    pub(crate) fn extend<I: IntoIterator<Item = (String, T)>>(&mut self, entries: I) {
+        self.entries.extend(entries);
+    }
+
+    /// Finds the entry for exactly this normalized name, for tables of specific products where
+    /// "peanut butter" must not be read as "butter".
+    pub(crate) fn get(&self, name: &str) -> Option<&T> {
+        self.entries.get(name)
+    }
+
+    /// Finds the entry for a normalized name, falling back to the longest of the shorter names it
+    /// ends with, so "red onion" uses "onion". Also returns the name that matched.
+    pub(crate) fn lookup(&self, name: &str) -> Option<(&str, &T)> {
+        IngredientName(name.to_string())
+            .suffixes()
+            .iter()
+            .find_map(|candidate| self.entries.get_key_value(candidate))
+            .map(|(name, entry)| (name.as_str(), entry))
+    }
+}
+
+impl<T> Default for NameTable<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<T> FromIterator<(String, T)> for NameTable<T> {
+    fn from_iter<I: IntoIterator<Item = (String, T)>>(entries: I) -> Self {
+        Self {
+            entries: entries.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> IntoIterator for NameTable<T> {
+    type Item = (String, T);
+    type IntoIter = std::collections::hash_map::IntoIter<String, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> NameTable<u32> {
+        [("onion".to_string(), 1), ("red onion".to_string(), 2)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn longest_suffix_wins() {
+        let table = table();
+        assert_eq!(table.lookup("red onion"), Some(("red onion", &2)));
+        assert_eq!(table.lookup("sweet red onion"), Some(("red onion", &2)));
+        assert_eq!(table.lookup("white onion"), Some(("onion", &1)));
+        assert_eq!(table.lookup("onion ring"), None);
+        assert_eq!(table.lookup(""), None);
+    }
+
+    #[test]
+    fn get_matches_whole_names_only() {
+        let table = table();
+        assert_eq!(table.get("red onion"), Some(&2));
+        assert_eq!(table.get("sweet red onion"), None);
+    }
+
+    #[test]
+    fn map_names_rewrites_keys() {
+        let table = table().map_names(str::to_uppercase);
+        assert_eq!(table.lookup("RED ONION"), Some(("RED ONION", &2)));
+        assert_eq!(table.lookup("red onion"), None);
+    }
+}
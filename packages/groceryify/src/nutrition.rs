@@ -0,0 +1,384 @@
+use std::{
+    collections::HashMap,
+    ops::{Add, Div},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    density::DensityConversion, ingredient::Ingredient, ingredient_unit::Quantity, merger::Merger,
+    name_table::NameTable, options::MergeOptions,
+};
+
+/// Calories and macronutrients of an amount of food.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Nutrients {
+    /// Kilocalories.
+    pub calories: f64,
+    /// Grams of protein.
+    pub protein: f64,
+    /// Grams of fat.
+    pub fat: f64,
+    /// Grams of carbohydrates.
+    pub carbohydrates: f64,
+}
+
+impl Nutrients {
+    /// The nutrients of `factor` times the amount.
+    pub fn scaled(self, factor: f64) -> Self {
+        Self {
+            calories: self.calories * factor,
+            protein: self.protein * factor,
+            fat: self.fat * factor,
+            carbohydrates: self.carbohydrates * factor,
+        }
+    }
+}
+
+impl Add for Nutrients {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            calories: self.calories + other.calories,
+            protein: self.protein + other.protein,
+            fat: self.fat + other.fat,
+            carbohydrates: self.carbohydrates + other.carbohydrates,
+        }
+    }
+}
+
+impl Div<f64> for Nutrients {
+    type Output = Self;
+
+    fn div(self, divisor: f64) -> Self {
+        self.scaled(1.0 / divisor)
+    }
+}
+
+/// A nutrient table entry: nutrients per 100 g, and what counted amounts and volumes weigh.
+#[derive(Clone, Debug, Deserialize)]
+struct NutrientEntry {
+    #[serde(flatten)]
+    per_100_grams: Nutrients,
+    /// Grams of one item counted without a unit.
+    each: Option<f64>,
+    /// Grams of one of a counted unit, e.g. a clove.
+    #[serde(default)]
+    units: HashMap<String, f64>,
+    /// Grams per US cup, for ingredients the density table doesn't cover.
+    grams_per_cup: Option<f64>,
+}
+
+/// Nutrients per 100 g, keyed by normalized ingredient name.
+#[derive(Clone, Debug)]
+pub struct NutrientTable {
+    entries: NameTable<NutrientEntry>,
+}
+
+impl NutrientTable {
+    pub fn new() -> Self {
+        let data = include_str!("../resources/nutrients.toml");
+        Self {
+            entries: toml::from_str(data).unwrap(),
+        }
+    }
+
+    /// Rewrites every name with `normalize`.
+    pub fn map_names<F: Fn(&str) -> String>(self, normalize: F) -> Self {
+        Self {
+            entries: self.entries.map_names(normalize),
+        }
+    }
+
+    /// Finds the nutrients per 100 g for a normalized name. Only the whole name matches, so
+    /// "almond milk" isn't counted as dairy "milk".
+    pub fn per_100_grams(&self, name: &str) -> Option<Nutrients> {
+        self.entries.get(name).map(|entry| entry.per_100_grams)
+    }
+}
+
+impl Default for NutrientTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The nutrients of one ingredient line.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IngredientNutrition {
+    /// The ingredient's name, as it was written.
+    pub name: String,
+    /// The nutrient table entry that was used, e.g. "onion" for "chopped onions".
+    pub matched_name: String,
+    pub grams: f64,
+    pub nutrients: Nutrients,
+}
+
+/// Why an ingredient was left out of a nutrition estimate.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnmatchedReason {
+    /// The nutrient table has no entry for the ingredient.
+    UnknownIngredient,
+    /// The ingredient is known, but its amount can't be weighed, e.g. a volume without a density
+    /// or a count without a weight per item.
+    NoWeight,
+}
+
+/// An ingredient left out of a nutrition estimate.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmatchedIngredient {
+    /// The ingredient's name, as it was written.
+    pub name: String,
+    pub reason: UnmatchedReason,
+}
+
+/// The estimated nutrition of a recipe.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NutritionReport {
+    /// The ingredients that were matched, in recipe order.
+    pub ingredients: Vec<IngredientNutrition>,
+    /// The total of the matched ingredients.
+    pub total: Nutrients,
+    /// `total` divided by the servings, if there are any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_serving: Option<Nutrients>,
+    /// Every ingredient left out of `total`.
+    pub unmatched: Vec<UnmatchedIngredient>,
+}
+
+/// Estimates the calories and macronutrients of a recipe from the bundled nutrient table.
+///
+/// Names are normalized like [`crate::merge`] normalizes ingredient names before they are looked
+/// up, and each amount is weighed in grams: weights directly, volumes through the density
+/// table or the nutrient table's own weight per cup, and counts through the weight of one item
+/// or unit. Ranges count their middle, and ingredients used to taste count as nothing.
+///
+/// # Arguments:
+/// * `ingredients`: The recipe's ingredients.
+/// * `servings`: How many servings the recipe makes, such as its yield.
+/// * `options`: Options for names, densities, locale and ambiguous units.
+///
+/// # Returns:
+/// * The nutrients of each matched ingredient, their total and the total per serving, plus every
+///   ingredient that couldn't be matched or weighed.
+pub fn estimate_nutrition(
+    ingredients: &[Ingredient],
+    servings: Option<f64>,
+    options: &MergeOptions,
+) -> NutritionReport {
+    Merger::shared(options).estimate_nutrition(ingredients, servings)
+}
+
+pub(crate) fn estimate_with(
+    merger: &Merger,
+    table: &NutrientTable,
+    ingredients: &[Ingredient],
+    servings: Option<f64>,
+) -> NutritionReport {
+    let mut matched = vec![];
+    let mut unmatched = vec![];
+    for ingredient in ingredients {
+        let normalized = merger.names().normalize(&ingredient.name);
+        let Some(entry) = table.entries.get(&normalized) else {
+            unmatched.push(UnmatchedIngredient {
+                name: ingredient.name.clone(),
+                reason: UnmatchedReason::UnknownIngredient,
+            });
+            continue;
+        };
+        let grams = if ingredient.to_taste {
+            Some(0.0)
+        } else {
+            let amount = match ingredient.amount_max {
+                Some(max) => (ingredient.amount + max) / 2.0,
+                None => ingredient.amount,
+            };
+            let quantity = merger.quantity(&Ingredient {
+                amount,
+                ..ingredient.clone()
+            });
+            grams(merger, &normalized, entry, &quantity)
+        };
+        match grams {
+            Some(grams) => matched.push(IngredientNutrition {
+                name: ingredient.name.clone(),
+                matched_name: normalized,
+                grams,
+                nutrients: entry.per_100_grams.scaled(grams / 100.0),
+            }),
+            None => unmatched.push(UnmatchedIngredient {
+                name: ingredient.name.clone(),
+                reason: UnmatchedReason::NoWeight,
+            }),
+        }
+    }
+    let total = matched
+        .iter()
+        .fold(Nutrients::default(), |total, i| total + i.nutrients);
+    NutritionReport {
+        ingredients: matched,
+        total,
+        per_serving: servings.filter(|s| *s > 0.0).map(|s| total / s),
+        unmatched,
+    }
+}
+
+/// Weighs a quantity of an ingredient in grams, or `None` if there's no way to.
+fn grams(merger: &Merger, name: &str, entry: &NutrientEntry, quantity: &Quantity) -> Option<f64> {
+    match quantity {
+        Quantity::Known {
+            amount, unit_key, ..
+        } => match unit_key.as_str() {
+            "kg" => Some(amount * 1000.0),
+            "l" => {
+                let density = merger.densities().lookup(name).or_else(|| {
+                    entry.grams_per_cup.map(|grams_per_cup| DensityConversion {
+                        matched_name: name.to_string(),
+                        grams_per_cup,
+                    })
+                })?;
+                Some(amount * density.kg_per_litre() * 1000.0)
+            }
+            unit => entry.units.get(unit).map(|grams| amount * grams),
+        },
+        Quantity::Custom { amount, unit } if unit.is_empty() => {
+            entry.each.map(|each| amount * each)
+        }
+        Quantity::Custom { amount, unit } => entry
+            .units
+            .get(&unit.to_lowercase())
+            .map(|grams| amount * grams),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(ingredients: &[Ingredient], servings: Option<f64>) -> NutritionReport {
+        estimate_nutrition(ingredients, servings, &MergeOptions::default())
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 0.5
+    }
+
+    #[test]
+    fn bundled_table_parses() {
+        let table = NutrientTable::new();
+        assert_eq!(table.per_100_grams("butter").unwrap().calories, 717.0);
+        assert!(table.per_100_grams("saffron").is_none());
+    }
+
+    #[test]
+    fn ingredients_are_matched_by_their_whole_name() {
+        let report = estimate(
+            &[
+                Ingredient::new("chopped onions", 1.0, "cup"),
+                Ingredient::new("almond milk", 1.0, "cup"),
+            ],
+            None,
+        );
+        assert_eq!(report.ingredients.len(), 1);
+        assert_eq!(report.ingredients[0].matched_name, "onion");
+        assert_eq!(report.unmatched.len(), 1);
+        assert_eq!(report.unmatched[0].name, "almond milk");
+        assert_eq!(
+            report.unmatched[0].reason,
+            UnmatchedReason::UnknownIngredient
+        );
+    }
+
+    #[test]
+    fn weights_are_used_as_they_are() {
+        let report = estimate(&[Ingredient::new("butter", 100.0, "g")], None);
+        assert!(close(report.total.calories, 717.0));
+        assert!(close(report.total.fat, 81.1));
+    }
+
+    #[test]
+    fn volumes_are_weighed_through_densities() {
+        // 1 cup of sugar weighs 200 g
+        let report = estimate(&[Ingredient::new("sugar", 1.0, "cup")], None);
+        assert!(close(report.ingredients[0].grams, 200.0));
+        assert!(close(report.total.calories, 774.0));
+    }
+
+    #[test]
+    fn volumes_fall_back_to_the_nutrient_table_weight_per_cup() {
+        let report = estimate(&[Ingredient::new("spinach", 2.0, "cups")], None);
+        assert!(close(report.ingredients[0].grams, 60.0));
+    }
+
+    #[test]
+    fn counts_are_weighed_per_item_and_unit() {
+        let report = estimate(
+            &[
+                Ingredient::new("large eggs", 2.0, ""),
+                Ingredient::new("garlic", 3.0, "cloves"),
+            ],
+            None,
+        );
+        assert!(close(report.ingredients[0].grams, 100.0));
+        assert_eq!(report.ingredients[0].matched_name, "egg");
+        assert!(close(report.ingredients[1].grams, 9.0));
+        assert!(report.unmatched.is_empty());
+    }
+
+    #[test]
+    fn totals_are_divided_into_servings() {
+        let report = estimate(
+            &[
+                Ingredient::new("flour", 200.0, "g"),
+                Ingredient::new("butter", 100.0, "g"),
+            ],
+            Some(4.0),
+        );
+        let per_serving = report.per_serving.unwrap();
+        assert!(close(per_serving.calories, (728.0 + 717.0) / 4.0));
+        assert!(
+            estimate(&[Ingredient::new("flour", 1.0, "cup")], Some(0.0))
+                .per_serving
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn unmatched_ingredients_are_reported() {
+        let report = estimate(
+            &[
+                Ingredient::new("saffron", 1.0, "g"),
+                Ingredient::new("tofu", 1.0, "handful"),
+                Ingredient::new_to_taste("salt"),
+            ],
+            None,
+        );
+        assert_eq!(
+            report.unmatched,
+            vec![
+                UnmatchedIngredient {
+                    name: "saffron".to_string(),
+                    reason: UnmatchedReason::UnknownIngredient,
+                },
+                UnmatchedIngredient {
+                    name: "tofu".to_string(),
+                    reason: UnmatchedReason::NoWeight,
+                },
+            ]
+        );
+        assert_eq!(report.ingredients.len(), 1);
+        assert_eq!(report.total.calories, 0.0);
+    }
+
+    #[test]
+    fn ranges_count_their_middle() {
+        let report = estimate(&[Ingredient::new("eggs", 2.0, "").with_max(4.0)], None);
+        assert!(close(report.ingredients[0].grams, 150.0));
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    locale::{Locale, LocaleError},
+    package::PackageSize,
+};
+
+/// How a merged quantity picks the unit it is displayed in.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DisplayUnit {
+    /// The largest unit that still gives a readable amount within the same system, e.g.
+    /// tsp -> tbsp -> cup, g -> kg or oz -> lb.
+    #[default]
+    BestFit,
+    /// The unit of the first ingredient that was merged.
+    Original,
+    /// A specific unit. Quantities of another dimension keep their original unit.
+    Fixed(String),
+}
+
+/// The system of measurement merged volumes and weights are expressed in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnitSystem {
+    /// Keep each quantity in the system of the unit it was written in.
+    #[default]
+    Original,
+    /// Millilitres, litres, grams and kilograms.
+    Metric,
+    /// Teaspoons, tablespoons, cups, ounces and pounds.
+    UsCustomary,
+}
+
+/// How a unit that spells both a volume and a weight, such as "oz", is read.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AmbiguousUnits {
+    /// As a volume for liquids, so "8 oz milk" is fluid ounces, and as a weight otherwise.
+    #[default]
+    ByName,
+    /// Always as a weight.
+    Weight,
+    /// Always as a volume.
+    Volume,
+    /// As neither, so such lines only merge with the same unit.
+    KeepApart,
+}
+
+/// Options that change how [`crate::merge_with_options`] combines and presents ingredients.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MergeOptions {
+    pub display_unit: DisplayUnit,
+    /// Applied before `display_unit`, so best-fit picks among the units of this system.
+    pub unit_system: UnitSystem,
+    /// Extra ingredient densities in grams per US cup, added to (or replacing entries of) the
+    /// bundled density table. Names are normalized the same way ingredient names are.
+    pub densities: HashMap<String, f64>,
+    /// Extra store sections by ingredient name, added to (or replacing entries of) the bundled
+    /// category table.
+    pub categories: HashMap<String, String>,
+    /// Extra synonyms, mapping a name to the canonical name it is merged under, added to (or
+    /// replacing entries of) the bundled synonym table.
+    pub synonyms: HashMap<String, String>,
+    /// Extra single-word descriptors, such as "heaping", stripped from names before merging in
+    /// addition to the bundled ones.
+    pub descriptors: Vec<String>,
+    pub ambiguous_units: AmbiguousUnits,
+    /// Extra ingredients read as liquids by [`AmbiguousUnits::ByName`], in addition to the
+    /// bundled ones. Names are normalized the same way ingredient names are.
+    pub liquids: Vec<String>,
+    /// Sizes ingredients are sold in, by ingredient name, replacing the bundled sizes of that
+    /// name. Used by [`crate::package::round_to_packages`].
+    pub packages: HashMap<String, Vec<PackageSize>>,
+    /// Code of the bundled locale the ingredients are written in, such as "es" or "de", for its
+    /// unit spellings, plurals and descriptors. English when unset; check an unknown code with
+    /// [`MergeOptions::locale`], as merging falls back to English for one.
+    pub locale: Option<String>,
+}
+
+impl MergeOptions {
+    /// The locale the ingredients are written in, or [`LocaleError::Unknown`] if no bundled
+    /// locale has the code.
+    pub fn locale(&self) -> Result<&'static Locale, LocaleError> {
+        Locale::lookup(self.locale.as_deref())
+    }
+}
@@ -0,0 +1,381 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grocery_item::GroceryItem, ingredient::Ingredient, merger::Merger, name_table::NameTable,
+    options::MergeOptions,
+};
+
+/// Slack for float noise when counting packages, so 12 eggs fill exactly one dozen.
+const FILL_TOLERANCE: f64 = 1e-9;
+
+/// A size an ingredient is sold in, e.g. a "dozen" of 12 eggs or a "stick" of butter.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageSize {
+    pub name: String,
+    /// The name of more than one package; the name with an "s" when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plural: Option<String>,
+    pub amount: f64,
+    /// The unit of `amount`, empty for whole items such as eggs.
+    #[serde(default)]
+    pub unit: String,
+}
+
+impl PackageSize {
+    fn name_for(&self, count: u32) -> String {
+        match (&self.plural, count) {
+            (_, 1) => self.name.clone(),
+            (Some(plural), _) => plural.clone(),
+            (None, _) => format!("{}s", self.name),
+        }
+    }
+}
+
+/// Some number of packages of one size, e.g. "2 sticks".
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageCount {
+    pub count: u32,
+    /// The package's name, agreeing with the count.
+    pub name: String,
+}
+
+/// What to buy for a grocery item: whole packages holding at least the amount needed.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Purchase {
+    /// The packages, largest first.
+    pub packages: Vec<PackageCount>,
+    /// How much the packages hold altogether, in the item's unit.
+    pub amount: f64,
+}
+
+impl fmt::Display for Purchase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let packages = self
+            .packages
+            .iter()
+            .map(|p| format!("{} {}", p.count, p.name))
+            .collect::<Vec<_>>();
+        write!(f, "{}", packages.join(" + "))
+    }
+}
+
+/// Sizes ingredients are sold in, keyed by normalized ingredient name.
+#[derive(Clone, Debug)]
+pub struct PackageTable {
+    sizes: NameTable<Vec<PackageSize>>,
+}
+
+impl PackageTable {
+    pub fn new() -> Self {
+        let data = include_str!("../resources/packages.toml");
+        Self {
+            sizes: toml::from_str(data).unwrap(),
+        }
+    }
+
+    /// Rewrites every name with `normalize`.
+    pub fn map_names<F: Fn(&str) -> String>(self, normalize: F) -> Self {
+        Self {
+            sizes: self.sizes.map_names(normalize),
+        }
+    }
+
+    /// Adds or replaces entries under normalized names. An entry with no usable sizes stops the
+    /// name from being rounded.
+    pub fn extend<I: IntoIterator<Item = (String, Vec<PackageSize>)>>(&mut self, entries: I) {
+        self.sizes.extend(entries.into_iter().map(|(name, sizes)| {
+            let sizes = sizes
+                .into_iter()
+                .filter(|size| size.amount.is_finite() && size.amount > 0.0)
+                .collect();
+            (name, sizes)
+        }));
+    }
+
+    /// Finds the sizes for a normalized name. Only the whole name matches, since sizes are
+    /// product specific: "peanut butter" isn't sold in sticks like "butter".
+    pub fn lookup(&self, name: &str) -> Option<&[PackageSize]> {
+        self.sizes.get(name).map(Vec::as_slice)
+    }
+}
+
+impl Default for PackageTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Works out what to buy for each item of a merged grocery list, from the sizes its ingredient
+/// is sold in.
+///
+/// Package names are matched like [`crate::merge`] normalizes ingredient names, and sizes are
+/// converted to the item's unit, between weights and volumes where a density is known. Each item
+/// gets the mix of sizes that leaves the least over, then the fewest packages, e.g. "1 block
+/// (1 lb) + 2 sticks" for 1.3 lb of butter. Ranges are bought up to their upper end.
+///
+/// # Arguments:
+/// * `items`: Output of [`crate::merge_with_options`], possibly after pantry subtraction.
+/// * `options`: The options the list was merged with, for their densities and package sizes.
+///
+/// # Returns:
+/// * The list with the same needed amounts. Items sold in a known size that fits their unit
+///   record what to buy; items used to taste or with nothing left to buy don't.
+pub fn round_to_packages(items: Vec<GroceryItem>, options: &MergeOptions) -> Vec<GroceryItem> {
+    Merger::shared(options).round_to_packages(items)
+}
+
+pub(crate) fn round_with(merger: &Merger, items: Vec<GroceryItem>) -> Vec<GroceryItem> {
+    items
+        .into_iter()
+        .map(|item| GroceryItem {
+            purchase: purchase(merger, &item.ingredient),
+            ..item
+        })
+        .collect()
+}
+
+fn purchase(merger: &Merger, ingredient: &Ingredient) -> Option<Purchase> {
+    let upper = ingredient.amount_max.unwrap_or(ingredient.amount);
+    if ingredient.to_taste || upper <= 0.0 {
+        return None;
+    }
+    let sizes = merger.packages().lookup(&ingredient.name)?;
+    let kg_per_litre = merger
+        .densities()
+        .lookup(&ingredient.name)
+        .map(|d| d.kg_per_litre());
+    let needed = merger.quantity(&Ingredient {
+        amount: upper,
+        ..ingredient.clone()
+    });
+    // Sizes in the units of the needed amount, largest first
+    let mut sizes = sizes
+        .iter()
+        .filter_map(|size| {
+            let quantity = merger.quantity(&Ingredient {
+                amount: size.amount,
+                unit: size.unit.clone(),
+                ..ingredient.clone()
+            });
+            Some((size, quantity.amount_like(&needed, kg_per_litre)?))
+        })
+        .filter(|(_, amount)| *amount > 0.0)
+        .collect::<Vec<_>>();
+    if sizes.is_empty() {
+        return None;
+    }
+    sizes.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    let amounts = sizes.iter().map(|(_, amount)| *amount).collect::<Vec<_>>();
+    let mix = cheapest_mix(needed.amount(), &amounts)?;
+    Some(Purchase {
+        packages: sizes
+            .iter()
+            .zip(&mix.counts)
+            .filter(|(_, count)| **count > 0)
+            .map(|((size, _), count)| PackageCount {
+                count: *count,
+                name: size.name_for(*count),
+            })
+            .collect(),
+        amount: upper * mix.held / needed.amount(),
+    })
+}
+
+/// How many packages of each size to buy, and how much they hold.
+struct Mix {
+    counts: Vec<u32>,
+    held: f64,
+    packages: u32,
+}
+
+/// Finds the counts of each size, largest first, that hold at least `needed` with the least left
+/// over, then in the fewest packages. `None` when the counts would overflow.
+fn cheapest_mix(needed: f64, sizes: &[f64]) -> Option<Mix> {
+    let needed = needed.max(0.0);
+    let Some(largest) = sizes.first() else {
+        return Some(search_mix(needed, sizes));
+    };
+    // Fill all but the last couple of the largest size up front, so the search is bounded by the
+    // sizes rather than the amount
+    let prefilled = (needed / largest - 2.0).floor().max(0.0);
+    if prefilled > u32::MAX as f64 {
+        return None;
+    }
+    let prefilled = prefilled as u32;
+    let mut mix = search_mix(needed - prefilled as f64 * largest, sizes);
+    mix.counts[0] = mix.counts[0].checked_add(prefilled)?;
+    mix.packages = mix.packages.checked_add(prefilled)?;
+    mix.held += prefilled as f64 * largest;
+    Some(mix)
+}
+
+/// Tries every count of every size, so `needed` should be at most a few of the largest size.
+fn search_mix(needed: f64, sizes: &[f64]) -> Mix {
+    let needed = needed.max(0.0);
+    let fill = |size: f64| (needed / size - FILL_TOLERANCE).ceil().max(0.0) as u32;
+    match sizes {
+        [] => Mix {
+            counts: vec![],
+            held: 0.0,
+            packages: 0,
+        },
+        [size] => Mix {
+            counts: vec![fill(*size)],
+            held: fill(*size) as f64 * size,
+            packages: fill(*size),
+        },
+        [size, smaller @ ..] => (0..=fill(*size))
+            .map(|count| {
+                let rest = search_mix(needed - count as f64 * size, smaller);
+                Mix {
+                    counts: [vec![count], rest.counts].concat(),
+                    held: count as f64 * size + rest.held,
+                    packages: count + rest.packages,
+                }
+            })
+            .min_by(|a, b| {
+                if (a.held - b.held).abs() <= needed * FILL_TOLERANCE {
+                    a.packages.cmp(&b.packages)
+                } else {
+                    a.held.total_cmp(&b.held)
+                }
+            })
+            .unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::merge_with_options;
+
+    fn round(ingredients: &[Ingredient]) -> Vec<GroceryItem> {
+        let options = MergeOptions::default();
+        round_to_packages(merge_with_options(ingredients, &options), &options)
+    }
+
+    fn bought(item: &GroceryItem) -> String {
+        item.purchase.as_ref().unwrap().to_string()
+    }
+
+    #[test]
+    fn bundled_table_parses() {
+        let table = PackageTable::new();
+        assert_eq!(table.lookup("egg").unwrap()[0].name, "dozen");
+        assert!(table.lookup("saffron").is_none());
+    }
+
+    #[test]
+    fn only_whole_names_are_rounded() {
+        let result = round(&[
+            Ingredient::new("large eggs", 7.0, ""),
+            Ingredient::new("peanut butter", 1.0, "cup"),
+            Ingredient::new("almond milk", 1.0, "cup"),
+        ]);
+        // "large" is a descriptor, so these are plain eggs
+        let eggs = result.iter().find(|i| i.ingredient.name == "egg").unwrap();
+        assert_eq!(bought(eggs), "1 dozen");
+        for name in ["peanut butter", "almond milk"] {
+            let item = result.iter().find(|i| i.ingredient.name == name).unwrap();
+            assert!(item.purchase.is_none(), "{name}");
+        }
+    }
+
+    #[test]
+    fn counted_items_round_up_to_whole_packages() {
+        let result = round(&[Ingredient::new("eggs", 7.0, "")]);
+        assert_eq!(result[0].ingredient.amount, 7.0);
+        assert_eq!(bought(&result[0]), "1 dozen");
+        assert_eq!(result[0].purchase.as_ref().unwrap().amount, 12.0);
+    }
+
+    #[test]
+    fn sizes_mix_to_leave_the_least_over() {
+        let result = round(&[Ingredient::new("butter", 1.3, "lb")]);
+        assert_eq!(bought(&result[0]), "1 block (1 lb) + 2 sticks");
+        // Sticks are a volume, so they weigh about a quarter pound through butter's density
+        let purchase = result[0].purchase.as_ref().unwrap();
+        assert!((purchase.amount - 1.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn exact_amounts_fill_whole_packages() {
+        let result = round(&[Ingredient::new("eggs", 12.0, "")]);
+        assert_eq!(bought(&result[0]), "1 dozen");
+        let result = round(&[Ingredient::new("butter", 1.0, "cup")]);
+        assert_eq!(bought(&result[0]), "2 sticks");
+    }
+
+    #[test]
+    fn sizes_convert_through_densities() {
+        // 10 cups of flour at 120 g per cup is about 2.6 lb
+        let result = round(&[Ingredient::new("flour", 10.0, "cup")]);
+        assert_eq!(bought(&result[0]), "2 bags (2 lb)");
+    }
+
+    #[test]
+    fn ranges_are_bought_to_their_upper_end() {
+        let result = round(&[Ingredient::new("eggs", 5.0, "").with_max(8.0)]);
+        assert_eq!(bought(&result[0]), "1 dozen");
+    }
+
+    #[test]
+    fn unsold_and_unmeasured_items_are_left_alone() {
+        let result = round(&[
+            Ingredient::new("saffron", 1.0, "g"),
+            Ingredient::new_to_taste("eggs"),
+        ]);
+        assert!(result.iter().all(|item| item.purchase.is_none()));
+    }
+
+    #[test]
+    fn callers_can_replace_sizes() {
+        let options = MergeOptions {
+            packages: HashMap::from([(
+                "eggs".to_string(),
+                vec![PackageSize {
+                    name: "flat".to_string(),
+                    plural: None,
+                    amount: 30.0,
+                    unit: String::new(),
+                }],
+            )]),
+            ..Default::default()
+        };
+        let merged = merge_with_options(&[Ingredient::new("egg", 40.0, "")], &options);
+        let result = round_to_packages(merged, &options);
+        assert_eq!(bought(&result[0]), "2 flats");
+        assert_eq!(result[0].purchase.as_ref().unwrap().amount, 60.0);
+    }
+
+    #[test]
+    fn mixes_prefer_fewer_packages_on_ties() {
+        let mix = cheapest_mix(12.0, &[12.0, 6.0]).unwrap();
+        assert_eq!(mix.counts, vec![1, 0]);
+        let mix = cheapest_mix(18.0, &[12.0, 6.0]).unwrap();
+        assert_eq!(mix.counts, vec![1, 1]);
+    }
+
+    #[test]
+    fn large_amounts_fill_the_largest_size_first() {
+        let mix = cheapest_mix(1_000_003.0, &[10.0, 3.0]).unwrap();
+        assert_eq!(mix.counts, vec![100_000, 1]);
+        assert_eq!(mix.held, 1_000_003.0);
+        let result = round(&[Ingredient::new("milk", 1_000_000.0, "cup")]);
+        assert_eq!(bought(&result[0]), "62500 gallons");
+    }
+
+    #[test]
+    fn amounts_too_large_to_count_are_not_rounded() {
+        assert!(cheapest_mix(1e12, &[1.0]).is_none());
+        let result = round(&[Ingredient::new("milk", 1e12, "cup")]);
+        assert!(result[0].purchase.is_none());
+    }
+}
@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grocery_item::GroceryItem, ingredient::Ingredient, merger::Merger, options::MergeOptions,
+    parsed_ingredient::ParsedIngredient,
+};
+
+/// Relative amount left to buy below which an item counts as fully covered, for float noise.
+const COVERED_TOLERANCE: f64 = 1e-9;
+
+/// What happens to grocery items the pantry fully covers.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CoveredItems {
+    /// Leave them off the list.
+    #[default]
+    Drop,
+    /// Keep them with nothing left to buy, flagged as covered.
+    Flag,
+}
+
+/// How much of a grocery item was found in the pantry.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PantryDeduction {
+    /// The amount taken from the pantry, in the item's unit.
+    pub amount: f64,
+    /// Whether the pantry covers the whole item, leaving nothing to buy.
+    pub covered: bool,
+}
+
+/// Subtracts on-hand pantry quantities from a merged grocery list.
+///
+/// Pantry names are normalized like [`crate::merge`] normalizes ingredient names, and quantities
+/// are converted between units, and between weights and volumes where a density is known. Each
+/// pantry quantity is used up at most once across the list. Ranges are bought up to their upper
+/// end, so stock is taken from that, and any stock at all covers an item used to taste.
+///
+/// # Arguments:
+/// * `items`: Output of [`crate::merge_with_options`].
+/// * `pantry`: What is already at home.
+/// * `covered`: Whether fully covered items are dropped or kept and flagged.
+/// * `options`: The options the list was merged with, for their densities.
+///
+/// # Returns:
+/// * The list with what is left to buy. Items the pantry reduced record the deduction.
+pub fn subtract_pantry(
+    items: Vec<GroceryItem>,
+    pantry: &[Ingredient],
+    covered: CoveredItems,
+    options: &MergeOptions,
+) -> Vec<GroceryItem> {
+    Merger::shared(options).subtract_pantry(items, pantry, covered)
+}
+
+pub(crate) fn subtract_with(
+    merger: &Merger,
+    items: Vec<GroceryItem>,
+    pantry: &[Ingredient],
+    covered: CoveredItems,
+) -> Vec<GroceryItem> {
+    let mut on_hand = pantry
+        .iter()
+        .map(|i| merger.parse(&merger.names().normalize(&i.name), i))
+        .collect::<Vec<_>>();
+
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let name = &item.ingredient.name;
+            if item.ingredient.to_taste {
+                let in_stock = on_hand
+                    .iter()
+                    .any(|s| &s.name == name && (s.to_taste || s.quantity.amount() > 0.0));
+                return match (in_stock, covered) {
+                    (false, _) => Some(item),
+                    (true, CoveredItems::Drop) => None,
+                    (true, CoveredItems::Flag) => Some(GroceryItem {
+                        pantry: Some(PantryDeduction {
+                            amount: 0.0,
+                            covered: true,
+                        }),
+                        ..item
+                    }),
+                };
+            }
+            let kg_per_litre = merger.densities().lookup(name).map(|d| d.kg_per_litre());
+            let upper = item.ingredient.amount_max.unwrap_or(item.ingredient.amount);
+            let needed = merger.quantity(&Ingredient {
+                amount: upper,
+                ..item.ingredient.clone()
+            });
+            let mut remaining = needed.clone();
+            let mut deducted = false;
+            for stock in on_hand.iter_mut().filter(|s| &s.name == name) {
+                if stock.quantity.amount() <= 0.0 || remaining.amount() <= 0.0 {
+                    continue;
+                }
+                let (Ok(left_to_buy), Ok(left_in_stock)) = (
+                    remaining
+                        .clone()
+                        .try_subtract(stock.quantity.clone(), kg_per_litre),
+                    stock
+                        .quantity
+                        .clone()
+                        .try_subtract(remaining.clone(), kg_per_litre),
+                ) else {
+                    continue;
+                };
+                remaining = left_to_buy.at_least_zero();
+                stock.quantity = left_in_stock.at_least_zero();
+                deducted = true;
+            }
+            if !deducted {
+                return Some(item);
+            }
+
+            let is_covered = remaining.amount() <= needed.amount() * COVERED_TOLERANCE;
+            if is_covered && covered == CoveredItems::Drop {
+                return None;
+            }
+            let left: Ingredient = ParsedIngredient::new(name, remaining).into();
+            let left_of_upper = if is_covered { 0.0 } else { left.amount };
+            let taken = upper - left_of_upper;
+            Some(GroceryItem {
+                pantry: Some(PantryDeduction {
+                    amount: taken,
+                    covered: is_covered,
+                }),
+                ingredient: Ingredient {
+                    amount: (item.ingredient.amount - taken).max(0.0),
+                    amount_max: item
+                        .ingredient
+                        .amount_max
+                        .filter(|_| !is_covered)
+                        .map(|_| left_of_upper),
+                    ..item.ingredient
+                },
+                ..item
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::merge_with_options;
+
+    fn ing(name: &str, amount: f64, unit: &str) -> Ingredient {
+        Ingredient::new(name, amount, unit)
+    }
+
+    fn subtract(groceries: &[Ingredient], pantry: &[Ingredient]) -> Vec<GroceryItem> {
+        let options = MergeOptions::default();
+        subtract_pantry(
+            merge_with_options(groceries, &options),
+            pantry,
+            CoveredItems::Drop,
+            &options,
+        )
+    }
+
+    #[test]
+    fn partially_covered_items_keep_the_rest() {
+        let result = subtract(&[ing("milk", 2.0, "cup")], &[ing("milk", 1.0, "cup")]);
+        assert_eq!(result.len(), 1);
+        assert!((result[0].ingredient.amount - 1.0).abs() < 1e-9);
+        let deduction = result[0].pantry.as_ref().unwrap();
+        assert!((deduction.amount - 1.0).abs() < 1e-9);
+        assert!(!deduction.covered);
+    }
+
+    #[test]
+    fn covered_items_are_dropped() {
+        let result = subtract(
+            &[ing("egg", 2.0, ""), ing("onion", 1.0, "")],
+            &[ing("eggs", 6.0, "")],
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.name, "onion");
+        assert!(result[0].pantry.is_none());
+    }
+
+    #[test]
+    fn covered_items_can_be_flagged() {
+        let options = MergeOptions::default();
+        let result = subtract_pantry(
+            merge_with_options(&[ing("egg", 2.0, "")], &options),
+            &[ing("eggs", 6.0, "")],
+            CoveredItems::Flag,
+            &options,
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.amount, 0.0);
+        let deduction = result[0].pantry.as_ref().unwrap();
+        assert_eq!(deduction.amount, 2.0);
+        assert!(deduction.covered);
+    }
+
+    #[test]
+    fn converts_units_and_normalizes_names() {
+        let result = subtract(
+            &[ing("sugar", 1.0, "cup")],
+            &[ing("Sugar, granulated", 8.0, "tbsp")],
+        );
+        assert!((result[0].ingredient.amount - 0.5).abs() < 1e-9);
+        assert_eq!(result[0].ingredient.unit, "cup");
+    }
+
+    #[test]
+    fn converts_weights_and_volumes_through_density() {
+        let result = subtract(&[ing("flour", 2.0, "cup")], &[ing("flour", 120.0, "g")]);
+        assert!((result[0].ingredient.amount - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn only_measurable_lines_are_reduced() {
+        // The custom "handful" can't be taken from cups, so only the cup line is covered
+        let result = subtract(
+            &[ing("rice", 1.0, "cup"), ing("rice", 1.0, "handful")],
+            &[ing("rice", 3.0, "cup")],
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.unit, "handful");
+    }
+
+    #[test]
+    fn incompatible_pantry_units_are_ignored() {
+        let result = subtract(&[ing("saffron", 1.0, "g")], &[ing("saffron", 1.0, "tsp")]);
+        assert_eq!(result[0].ingredient.amount, 1.0);
+        assert!(result[0].pantry.is_none());
+    }
+
+    #[test]
+    fn ranges_are_taken_from_their_upper_end() {
+        let result = subtract(
+            &[ing("garlic", 2.0, "clove").with_max(4.0)],
+            &[ing("garlic", 3.0, "clove")],
+        );
+        assert_eq!(result[0].ingredient.amount, 0.0);
+        assert_eq!(result[0].ingredient.amount_max, Some(1.0));
+        assert_eq!(result[0].pantry.as_ref().unwrap().amount, 3.0);
+        let result = subtract(
+            &[ing("garlic", 2.0, "clove").with_max(4.0)],
+            &[ing("garlic", 4.0, "clove")],
+        );
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn any_stock_covers_to_taste_items() {
+        let result = subtract(
+            &[
+                Ingredient::new_to_taste("salt"),
+                Ingredient::new_to_taste("pepper"),
+            ],
+            &[ing("salt", 10.0, "g"), ing("pepper", 0.0, "g")],
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].ingredient.name, "pepper");
+        let result = subtract(
+            &[Ingredient::new_to_taste("salt")],
+            &[Ingredient::new_to_taste("salt")],
+        );
+        assert!(result.is_empty());
+    }
+}
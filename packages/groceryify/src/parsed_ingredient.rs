@@ -1,11 +1,21 @@
 use std::cmp::Ordering;
 
-use crate::ingredient_unit::Quantity;
+use crate::{density::DensityConversion, grocery_item::SourceQuantity, ingredient_unit::Quantity};
 
 #[derive(Debug, Clone)]
 pub struct ParsedIngredient {
     pub name: String,
     pub quantity: Quantity,
+    /// Upper end of a range, whose lower end is `quantity`.
+    pub max: Option<Quantity>,
+    /// Set for ingredients used without a measured amount, whose quantity is not used.
+    pub to_taste: bool,
+    /// Set once weights and volumes of this ingredient have been added through a density.
+    pub density: Option<DensityConversion>,
+    /// Descriptors and notes of every ingredient merged into this one, without repeats.
+    pub notes: Vec<String>,
+    /// The ingredients merged into this one, as they were written.
+    pub sources: Vec<SourceQuantity>,
 }
 
 impl ParsedIngredient {
@@ -13,15 +23,103 @@ impl ParsedIngredient {
         Self {
             name: name.to_string(),
             quantity,
+            max: None,
+            to_taste: false,
+            density: None,
+            notes: vec![],
+            sources: vec![],
+        }
+    }
+
+    /// An ingredient used without a measured amount, e.g. "salt to taste".
+    pub fn new_to_taste(name: &str) -> Self {
+        Self {
+            to_taste: true,
+            ..Self::new(
+                name,
+                Quantity::Custom {
+                    amount: 0.0,
+                    unit: String::new(),
+                },
+            )
         }
     }
 
     pub fn merge(self, other: Self) -> Result<Self, (Quantity, Quantity)> {
+        let max = add_ranges(&self, &other, |a, b| a.try_add(b))?;
         Ok(Self {
             name: self.name,
             quantity: self.quantity.try_add(other.quantity)?,
+            max,
+            to_taste: false,
+            density: self.density.or(other.density),
+            notes: merge_notes(self.notes, other.notes),
+            sources: [self.sources, other.sources].concat(),
         })
     }
+
+    /// Merges like [`ParsedIngredient::merge`], converting between volume and mass with
+    /// `density` when the two quantities are of different dimensions.
+    pub fn merge_with_density(
+        self,
+        other: Self,
+        density: DensityConversion,
+    ) -> Result<Self, (Quantity, Quantity)> {
+        if self.quantity.unit_key() == other.quantity.unit_key() {
+            return self.merge(other);
+        }
+        let kg_per_litre = density.kg_per_litre();
+        let max = add_ranges(&self, &other, |a, b| {
+            a.try_add_with_density(b, kg_per_litre)
+        })?;
+        Ok(Self {
+            name: self.name,
+            quantity: self
+                .quantity
+                .try_add_with_density(other.quantity, kg_per_litre)?,
+            max,
+            to_taste: false,
+            density: Some(density),
+            notes: merge_notes(self.notes, other.notes),
+            sources: [self.sources, other.sources].concat(),
+        })
+    }
+
+    /// Merges a like ingredient when either is used to taste. A measured ingredient keeps its
+    /// quantity, so "1 tsp salt" and "salt to taste" are bought as one line.
+    pub fn merge_to_taste(self, other: Self) -> Self {
+        let notes = merge_notes(self.notes.clone(), other.notes.clone());
+        let sources = [self.sources.clone(), other.sources.clone()].concat();
+        let measured = if self.to_taste { other } else { self };
+        Self {
+            notes,
+            sources,
+            ..measured
+        }
+    }
+}
+
+/// Adds two ingredients' ranges endpoint by endpoint, reading a single quantity as both ends.
+/// `None` if neither is a range.
+fn add_ranges(
+    a: &ParsedIngredient,
+    b: &ParsedIngredient,
+    add: impl Fn(Quantity, Quantity) -> Result<Quantity, (Quantity, Quantity)>,
+) -> Result<Option<Quantity>, (Quantity, Quantity)> {
+    if a.max.is_none() && b.max.is_none() {
+        return Ok(None);
+    }
+    let upper = |i: &ParsedIngredient| i.max.clone().unwrap_or_else(|| i.quantity.clone());
+    add(upper(a), upper(b)).map(Some)
+}
+
+fn merge_notes(mut notes: Vec<String>, other: Vec<String>) -> Vec<String> {
+    for note in other {
+        if !notes.contains(&note) {
+            notes.push(note);
+        }
+    }
+    notes
 }
 
 impl PartialEq for ParsedIngredient {
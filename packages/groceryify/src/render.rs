@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    category::group_by_category,
+    formatting::{FormatOptions, Formatted, format_items},
+    grocery_item::{GroceryItem, SourceQuantity},
+    ingredient::Ingredient,
+    locale::Locale,
+};
+
+/// The text formats a grocery list can be rendered in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RenderFormat {
+    /// One "[ ] 2 cups flour" line per item, for printing or pasting into notes.
+    #[default]
+    PlainText,
+    /// A Markdown task list, with a heading per store section when grouped.
+    Markdown,
+    /// One row per item, with a header row, for spreadsheets.
+    Csv,
+}
+
+impl RenderFormat {
+    /// The usual file extension for the format, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::PlainText => "txt",
+            Self::Markdown => "md",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// Controls how [`render`] lays out a grocery list.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RenderOptions {
+    pub format: RenderFormat,
+    /// Order items section by section, under a heading for each section. CSV has a category
+    /// column either way, and is only reordered.
+    pub group_by_category: bool,
+    /// List the ingredients merged into each item, with their source tags.
+    pub provenance: bool,
+    /// How amounts and units are written.
+    pub format_options: FormatOptions,
+}
+
+/// Renders a merged grocery list as text.
+///
+/// # Arguments:
+/// * `items`: Output of [`crate::merge_with_options`], possibly after pantry subtraction or
+///   package rounding.
+/// * `options`: The format, grouping and provenance to render with.
+///
+/// # Returns:
+/// * The rendered list, ending in a newline unless it is empty.
+pub fn render(items: &[GroceryItem], options: &RenderOptions) -> String {
+    let locale = Locale::resolve(options.format_options.locale.as_deref());
+    let formatted = format_items(items, &options.format_options);
+    let sections = if options.group_by_category {
+        group_by_category(formatted)
+            .into_iter()
+            .map(|group| (Some(group.category), group.items))
+            .collect()
+    } else {
+        vec![(None, formatted)]
+    };
+    match options.format {
+        RenderFormat::PlainText => render_text(&sections, options, locale),
+        RenderFormat::Markdown => render_markdown(&sections, options, locale),
+        RenderFormat::Csv => render_csv(&sections, options, locale),
+    }
+}
+
+type Section = (Option<String>, Vec<Formatted<GroceryItem>>);
+
+fn render_text(sections: &[Section], options: &RenderOptions, locale: &Locale) -> String {
+    let mut blocks = vec![];
+    for (category, items) in sections {
+        let mut lines = vec![];
+        if let Some(category) = category {
+            lines.push(heading(category));
+        }
+        for item in items {
+            lines.push(format!("[ ] {}", line(item)));
+            if options.provenance {
+                lines.extend(
+                    sources(&item.item, &options.format_options, locale)
+                        .into_iter()
+                        .map(|source| format!("    {source}")),
+                );
+            }
+        }
+        blocks.push(lines.join("\n"));
+    }
+    finish(blocks.join("\n\n"))
+}
+
+fn render_markdown(sections: &[Section], options: &RenderOptions, locale: &Locale) -> String {
+    let mut blocks = vec![];
+    for (category, items) in sections {
+        let mut lines = vec![];
+        if let Some(category) = category {
+            lines.push(format!("## {}\n", heading(category)));
+        }
+        for item in items {
+            lines.push(format!("- [ ] {}", escape_markdown(&line(item))));
+            if options.provenance {
+                lines.extend(
+                    sources(&item.item, &options.format_options, locale)
+                        .into_iter()
+                        .map(|source| format!("  - {}", escape_markdown(&source))),
+                );
+            }
+        }
+        blocks.push(lines.join("\n"));
+    }
+    finish(blocks.join("\n\n"))
+}
+
+fn render_csv(sections: &[Section], options: &RenderOptions, locale: &Locale) -> String {
+    let mut header = vec![
+        "name",
+        "amount",
+        "amount_max",
+        "unit",
+        "category",
+        "note",
+        "buy",
+        "display",
+    ];
+    if options.provenance {
+        header.push("sources");
+    }
+    let mut rows = vec![header.join(",")];
+    for item in sections.iter().flat_map(|(_, items)| items) {
+        let ingredient = &item.item.ingredient;
+        let mut row = vec![
+            ingredient.name.clone(),
+            if ingredient.to_taste {
+                String::new()
+            } else {
+                ingredient.amount.to_string()
+            },
+            ingredient
+                .amount_max
+                .map(|max| max.to_string())
+                .unwrap_or_default(),
+            ingredient.unit.clone(),
+            item.item.category.clone(),
+            ingredient.note.clone().unwrap_or_default(),
+            item.item
+                .purchase
+                .as_ref()
+                .map(|purchase| purchase.to_string())
+                .unwrap_or_default(),
+            item.display.clone(),
+        ];
+        if options.provenance {
+            row.push(sources(&item.item, &options.format_options, locale).join("; "));
+        }
+        rows.push(
+            row.iter()
+                .map(|field| escape_csv(field))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+    finish(rows.join("\n"))
+}
+
+/// An item's line, with what to buy when it was rounded to packages.
+fn line(item: &Formatted<GroceryItem>) -> String {
+    match &item.item.purchase {
+        Some(purchase) => format!("{} (buy {purchase})", item.display),
+        None => item.display.clone(),
+    }
+}
+
+/// Each ingredient merged into an item as it was written, e.g. "1 cup (pancakes)".
+fn sources(item: &GroceryItem, options: &FormatOptions, locale: &Locale) -> Vec<String> {
+    item.sources
+        .iter()
+        .map(|source| {
+            let quantity = source_quantity(source, options, locale);
+            match &source.source {
+                Some(tag) => format!("{quantity} ({tag})"),
+                None => quantity,
+            }
+        })
+        .collect()
+}
+
+fn source_quantity(source: &SourceQuantity, options: &FormatOptions, locale: &Locale) -> String {
+    if source.to_taste {
+        return locale.to_taste_label().to_string();
+    }
+    let ingredient = Ingredient {
+        amount_max: source.amount_max,
+        ..Ingredient::new("", source.amount, &source.unit)
+    };
+    let formatted = format_items(&[ingredient], options).remove(0);
+    [formatted.amount_display, formatted.unit_display]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A category as a heading, e.g. "Produce".
+fn heading(category: &str) -> String {
+    let mut chars = category.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Escapes characters that would otherwise start Markdown formatting inside a list item.
+fn escape_markdown(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            '\\' | '*' | '_' | '[' | ']' | '`' | '#' => vec!['\\', c],
+            c => vec![c],
+        })
+        .collect()
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn finish(text: String) -> String {
+    if text.is_empty() { text } else { text + "\n" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{merge_with_options, options::MergeOptions};
+
+    fn items() -> Vec<GroceryItem> {
+        merge_with_options(
+            &[
+                Ingredient::new("flour", 1.0, "cup").with_source("pancakes"),
+                Ingredient::new("flour", 0.5, "cup").with_source("cookies"),
+                Ingredient::new("onion", 2.0, ""),
+            ],
+            &MergeOptions::default(),
+        )
+    }
+
+    fn render_as(format: RenderFormat) -> RenderOptions {
+        RenderOptions {
+            format,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plain_text_has_checkboxes() {
+        let text = render(&items(), &render_as(RenderFormat::PlainText));
+        assert_eq!(text, "[ ] 1 ½ cups flour\n[ ] 2 onions\n");
+    }
+
+    #[test]
+    fn plain_text_groups_by_category() {
+        let options = RenderOptions {
+            group_by_category: true,
+            ..render_as(RenderFormat::PlainText)
+        };
+        let text = render(&items(), &options);
+        assert_eq!(
+            text,
+            "Produce\n[ ] 2 onions\n\nPantry\n[ ] 1 ½ cups flour\n"
+        );
+    }
+
+    #[test]
+    fn provenance_lists_sources() {
+        let options = RenderOptions {
+            provenance: true,
+            ..render_as(RenderFormat::PlainText)
+        };
+        let text = render(&items()[..1], &options);
+        assert_eq!(
+            text,
+            "[ ] 1 ½ cups flour\n    ½ cup (cookies)\n    1 cup (pancakes)\n"
+        );
+    }
+
+    #[test]
+    fn markdown_is_a_task_list_under_headings() {
+        let options = RenderOptions {
+            group_by_category: true,
+            provenance: true,
+            ..render_as(RenderFormat::Markdown)
+        };
+        let text = render(&items()[..1], &options);
+        assert_eq!(
+            text,
+            "## Pantry\n\n- [ ] 1 ½ cups flour\n  - ½ cup (cookies)\n  - 1 cup (pancakes)\n"
+        );
+    }
+
+    #[test]
+    fn csv_has_a_header_and_quotes_fields() {
+        let items = merge_with_options(
+            &[Ingredient {
+                note: Some("chopped, fine".to_string()),
+                ..Ingredient::new("onion", 2.0, "")
+            }],
+            &MergeOptions::default(),
+        );
+        let text = render(&items, &render_as(RenderFormat::Csv));
+        assert_eq!(
+            text,
+            "name,amount,amount_max,unit,category,note,buy,display\n\
+             onion,2,,,produce,\"chopped, fine\",,\"2 onions, chopped, fine\"\n"
+        );
+    }
+
+    #[test]
+    fn empty_lists_render_nothing() {
+        assert_eq!(render(&[], &render_as(RenderFormat::PlainText)), "");
+        assert_eq!(
+            render(&[], &render_as(RenderFormat::Csv)),
+            "name,amount,amount_max,unit,category,note,buy,display\n"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    formatting::{FormatOptions, round_amount},
+    ingredient::Ingredient,
+    ingredient_unit::Quantity,
+    parsed_ingredient::ParsedIngredient,
+};
+
+/// How much to scale a recipe by.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Scale {
+    /// Multiply every amount, e.g. 2.0 for a double batch.
+    Factor(f64),
+    /// Scale from the servings the recipe makes to the servings wanted.
+    Servings { original: f64, target: f64 },
+}
+
+impl Scale {
+    /// The factor amounts are multiplied by, or an error if it is not a positive finite number.
+    pub fn factor(&self) -> Result<f64, ScaleError> {
+        let factor = match *self {
+            Scale::Factor(factor) => factor,
+            Scale::Servings { original, target } => {
+                if !(original.is_finite() && original > 0.0) {
+                    return Err(ScaleError::InvalidServings(original));
+                }
+                if !(target.is_finite() && target > 0.0) {
+                    return Err(ScaleError::InvalidServings(target));
+                }
+                target / original
+            }
+        };
+        if factor.is_finite() && factor > 0.0 {
+            Ok(factor)
+        } else {
+            Err(ScaleError::InvalidFactor(factor))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ScaleError {
+    InvalidFactor(f64),
+    InvalidServings(f64),
+}
+
+impl std::fmt::Display for ScaleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFactor(factor) => {
+                write!(f, "Scale factor must be a positive number, got {factor}")
+            }
+            Self::InvalidServings(servings) => {
+                write!(f, "Servings must be a positive number, got {servings}")
+            }
+        }
+    }
+}
+
+impl Error for ScaleError {}
+
+/// Scales a recipe's ingredients, re-picking units so amounts stay readable (6 tsp -> 2 tbsp)
+/// and rounding them to kitchen fractions, or to short decimals for metric units.
+///
+/// Unlike [`crate::merge`], names and notes are left exactly as written. Both ends of a range
+/// are scaled, and ingredients used to taste are left as they are.
+///
+/// # Arguments:
+/// * `ingredients`: The recipe's ingredients.
+/// * `scale`: A factor, or the recipe's servings and the servings wanted.
+///
+/// # Returns:
+/// * The scaled ingredients in their original order, or an error if the scale is not positive.
+pub fn scale_ingredients(
+    ingredients: &[Ingredient],
+    scale: Scale,
+) -> Result<Vec<Ingredient>, ScaleError> {
+    let factor = scale.factor()?;
+    let rounding = FormatOptions::default();
+    Ok(ingredients
+        .iter()
+        .map(|i| {
+            if i.to_taste {
+                return i.clone();
+            }
+            let quantity = Quantity::new(i).scaled(factor);
+            let max = i.amount_max.map(|max| {
+                Quantity::new(&Ingredient::new(&i.name, max, &i.unit))
+                    .scaled(factor)
+                    .with_unit_of(&quantity)
+            });
+            let scaled: Ingredient = ParsedIngredient {
+                max,
+                ..ParsedIngredient::new(&i.name, quantity)
+            }
+            .into();
+            Ingredient {
+                amount: round_amount(scaled.amount, &scaled.unit, &rounding),
+                amount_max: scaled
+                    .amount_max
+                    .map(|max| round_amount(max, &scaled.unit, &rounding)),
+                note: i.note.clone(),
+                source: i.source.clone(),
+                ..scaled
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ing(name: &str, amount: f64, unit: &str) -> Ingredient {
+        Ingredient::new(name, amount, unit)
+    }
+
+    fn scale_one(ingredient: Ingredient, scale: Scale) -> Ingredient {
+        scale_ingredients(&[ingredient], scale).unwrap().remove(0)
+    }
+
+    #[test]
+    fn doubles_by_factor() {
+        let i = scale_one(ing("flour", 1.5, "cup"), Scale::Factor(2.0));
+        assert_eq!(i.amount, 3.0);
+        assert_eq!(i.unit, "cup");
+    }
+
+    #[test]
+    fn scales_both_ends_of_a_range() {
+        let i = scale_one(
+            ing("garlic", 2.0, "clove").with_max(3.0),
+            Scale::Factor(2.0),
+        );
+        assert_eq!(i.amount, 4.0);
+        assert_eq!(i.amount_max, Some(6.0));
+        // Both ends stay in one unit, even where 3 tsp alone would read as 1 tbsp
+        let i = scale_one(ing("salt", 1.0, "tsp").with_max(1.5), Scale::Factor(2.0));
+        assert_eq!((i.amount, i.unit.as_str()), (2.0, "tsp"));
+        assert_eq!(i.amount_max, Some(3.0));
+    }
+
+    #[test]
+    fn leaves_to_taste_items_alone() {
+        let i = scale_one(Ingredient::new_to_taste("salt"), Scale::Factor(3.0));
+        assert!(i.to_taste);
+        assert_eq!(i.amount, 0.0);
+    }
+
+    #[test]
+    fn scales_by_servings() {
+        let scale = Scale::Servings {
+            original: 4.0,
+            target: 2.0,
+        };
+        let i = scale_one(ing("milk", 1.0, "cup"), scale);
+        assert_eq!(i.amount, 0.5);
+    }
+
+    #[test]
+    fn repicks_units_after_scaling() {
+        let i = scale_one(ing("salt", 3.0, "tsp"), Scale::Factor(2.0));
+        assert_eq!(i.unit, "tbsp");
+        assert_eq!(i.amount, 2.0);
+        let i = scale_one(ing("oil", 1.0, "cup"), Scale::Factor(0.125));
+        assert_eq!(i.unit, "tbsp");
+        assert_eq!(i.amount, 2.0);
+    }
+
+    #[test]
+    fn rounds_to_kitchen_fractions() {
+        let i = scale_one(ing("sugar", 1.0, "cup"), Scale::Factor(1.0 / 3.0));
+        assert!((i.amount - 1.0 / 3.0).abs() < 1e-12);
+        let i = scale_one(ing("egg", 3.0, ""), Scale::Factor(0.5));
+        assert_eq!(i.amount, 1.5);
+    }
+
+    #[test]
+    fn rounds_metric_units_to_decimals() {
+        let i = scale_one(ing("butter", 100.0, "g"), Scale::Factor(1.0 / 3.0));
+        assert_eq!(i.amount, 33.33);
+        assert_eq!(i.unit, "g");
+    }
+
+    #[test]
+    fn keeps_names_notes_and_order() {
+        let mut garlic = ing("Garlic Cloves", 2.0, "");
+        garlic.note = Some("minced".to_string());
+        let scaled =
+            scale_ingredients(&[garlic, ing("onions", 1.0, "")], Scale::Factor(2.0)).unwrap();
+        assert_eq!(scaled[0].name, "Garlic Cloves");
+        assert_eq!(scaled[0].note.as_deref(), Some("minced"));
+        assert_eq!(scaled[1].name, "onions");
+    }
+
+    #[test]
+    fn custom_units_are_scaled_as_is() {
+        let i = scale_one(ing("parsley", 1.0, "handful"), Scale::Factor(3.0));
+        assert_eq!(i.amount, 3.0);
+        assert_eq!(i.unit, "handful");
+    }
+
+    #[test]
+    fn rejects_non_positive_scales() {
+        assert_eq!(
+            scale_ingredients(&[], Scale::Factor(0.0)).unwrap_err(),
+            ScaleError::InvalidFactor(0.0)
+        );
+        let scale = Scale::Servings {
+            original: 0.0,
+            target: 4.0,
+        };
+        assert_eq!(
+            scale_ingredients(&[], scale).unwrap_err(),
+            ScaleError::InvalidServings(0.0)
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use serde::Deserialize;
+
+use crate::name_table::NameTable;
+
+#[derive(Deserialize)]
+struct Synonym {
+    name: String,
+    aliases: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct Synonyms {
+    synonym: Vec<Synonym>,
+}
+
+/// Canonical ingredient names, keyed by the normalized names that mean the same thing.
+#[derive(Clone, Debug)]
+pub struct SynonymTable {
+    canonical: NameTable<String>,
+}
+
+impl SynonymTable {
+    pub fn new() -> Self {
+        let data = include_str!("../resources/synonyms.toml");
+        let synonyms: Synonyms = toml::from_str(data).unwrap();
+        let canonical = synonyms
+            .synonym
+            .into_iter()
+            .flat_map(|s| {
+                s.aliases
+                    .into_iter()
+                    .map(move |alias| (alias, s.name.clone()))
+            })
+            .collect();
+        Self { canonical }
+    }
+
+    /// Rewrites every alias and canonical name with `normalize`, dropping aliases that become
+    /// their canonical name.
+    pub fn map_names<F: Fn(&str) -> String>(self, normalize: F) -> Self {
+        let canonical = self
+            .canonical
+            .into_iter()
+            .map(|(alias, name)| (normalize(&alias), normalize(&name)))
+            .filter(|(alias, name)| alias != name)
+            .collect();
+        Self { canonical }
+    }
+
+    /// Adds or replaces entries. Aliases and canonical names must already be normalized.
+    pub fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, entries: I) {
+        self.canonical.extend(
+            entries
+                .into_iter()
+                .filter(|(alias, name)| !alias.is_empty() && !name.is_empty() && alias != name),
+        );
+    }
+
+    /// Replaces the longest alias a normalized name ends with by its canonical name, so
+    /// "chopped scallion" becomes "chopped green onion". Names without an alias, or that already
+    /// end with the canonical name, are unchanged: with "onion" meaning "yellow onion", "yellow
+    /// onion" stays as it is.
+    pub fn canonicalize(&self, name: &str) -> String {
+        let Some((alias, canonical)) = self.canonical.lookup(name) else {
+            return name.to_string();
+        };
+        let words = name.split_whitespace().collect::<Vec<_>>();
+        let canonical_words = canonical.split_whitespace().collect::<Vec<_>>();
+        if words.ends_with(&canonical_words) {
+            return name.to_string();
+        }
+        let start = words.len() - alias.split_whitespace().count();
+        words[..start]
+            .iter()
+            .copied()
+            .chain([canonical.as_str()])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Default for SynonymTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table_parses() {
+        let table = SynonymTable::new();
+        assert_eq!(table.canonicalize("scallion"), "green onion");
+        assert_eq!(table.canonicalize("icing sugar"), "powdered sugar");
+    }
+
+    #[test]
+    fn leaves_unknown_names_alone() {
+        assert_eq!(SynonymTable::new().canonicalize("flour"), "flour");
+    }
+
+    #[test]
+    fn replaces_an_alias_at_the_end_of_a_longer_name() {
+        let table = SynonymTable::new();
+        assert_eq!(
+            table.canonicalize("chopped scallion"),
+            "chopped green onion"
+        );
+    }
+
+    #[test]
+    fn leaves_names_ending_with_the_canonical_name_alone() {
+        let mut table = SynonymTable::new();
+        table.extend([("onion".to_string(), "yellow onion".to_string())]);
+        assert_eq!(table.canonicalize("onion"), "yellow onion");
+        assert_eq!(table.canonicalize("yellow onion"), "yellow onion");
+        assert_eq!(
+            table.canonicalize("sweet yellow onion"),
+            "sweet yellow onion"
+        );
+    }
+
+    #[test]
+    fn only_matches_at_the_end_of_a_name() {
+        // "rocket" is an alias, but here it describes the salad
+        assert_eq!(
+            SynonymTable::new().canonicalize("rocket salad"),
+            "rocket salad"
+        );
+    }
+
+    #[test]
+    fn extend_adds_and_replaces_entries() {
+        let mut table = SynonymTable::new();
+        table.extend([
+            ("spring onion".to_string(), "scallion".to_string()),
+            ("aubergine".to_string(), "brinjal".to_string()),
+        ]);
+        assert_eq!(table.canonicalize("spring onion"), "scallion");
+        assert_eq!(table.canonicalize("aubergine"), "brinjal");
+    }
+
+    #[test]
+    fn extend_ignores_empty_and_self_referencing_entries() {
+        let mut table = SynonymTable::new();
+        table.extend([
+            ("".to_string(), "flour".to_string()),
+            ("sugar".to_string(), "".to_string()),
+            ("salt".to_string(), "salt".to_string()),
+        ]);
+        assert_eq!(table.canonicalize("sugar"), "sugar");
+        assert_eq!(table.canonicalize("salt"), "salt");
+    }
+
+    #[test]
+    fn map_names_normalizes_aliases_and_canonical_names() {
+        let table = SynonymTable::new().map_names(|name| name.to_uppercase());
+        assert_eq!(table.canonicalize("SCALLION"), "GREEN ONION");
+    }
+}
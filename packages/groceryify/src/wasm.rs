@@ -1,12 +1,613 @@
-use crate::ingredient::Ingredient;
+#[cfg(feature = "wasm")]
+use crate::{
+    ParseIngredientError,
+    conversion::ConvertError,
+    formatting::{FormatOptions, format_ingredients},
+    grocery_item::GroceryItem,
+    ingredient::Ingredient,
+    locale::{Locale, LocaleError},
+    merger::Merger,
+    options::MergeOptions,
+    render::RenderOptions,
+    scaling::{Scale, ScaleError},
+};
+use serde::Serialize;
+#[cfg(feature = "wasm")]
+use serde::de::DeserializeOwned;
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+// The shapes serde gives the crate's types, for the signatures below. Options are all optional,
+// falling back to their defaults. The tests below check them against what serde writes and reads.
+#[cfg_attr(feature = "wasm", wasm_bindgen(typescript_custom_section))]
+const TYPES: &str = r#"
+export interface Ingredient {
+  name: string;
+  amount: number;
+  unit: string;
+  amountMax?: number;
+  toTaste?: boolean;
+  note?: string;
+  source?: string;
+}
+
+export interface SourceQuantity {
+  source?: string;
+  amount: number;
+  unit: string;
+  amountMax?: number;
+  toTaste?: boolean;
+  interpretedAs?: string;
+}
+
+export interface DensityConversion {
+  matchedName: string;
+  gramsPerCup: number;
+}
+
+export interface PantryDeduction {
+  amount: number;
+  covered: boolean;
+}
+
+export interface PackageSize {
+  name: string;
+  plural?: string;
+  amount: number;
+  unit?: string;
+}
+
+export interface PackageCount {
+  count: number;
+  name: string;
+}
+
+export interface Purchase {
+  packages: PackageCount[];
+  amount: number;
+}
+
+export interface GroceryItem extends Ingredient {
+  densityConversion?: DensityConversion;
+  pantry?: PantryDeduction;
+  purchase?: Purchase;
+  category: string;
+  sources: SourceQuantity[];
+}
+
+export type Formatted<T> = T & {
+  amountDisplay: string;
+  unitDisplay: string;
+  display: string;
+};
+
+export type DisplayUnit = "bestFit" | "original" | { fixed: string };
+export type UnitSystem = "original" | "metric" | "usCustomary";
+export type AmbiguousUnits = "byName" | "weight" | "volume" | "keepApart";
+
+export interface MergeOptions {
+  displayUnit?: DisplayUnit;
+  unitSystem?: UnitSystem;
+  densities?: Record<string, number>;
+  categories?: Record<string, string>;
+  synonyms?: Record<string, string>;
+  descriptors?: string[];
+  ambiguousUnits?: AmbiguousUnits;
+  liquids?: string[];
+  packages?: Record<string, PackageSize[]>;
+  locale?: string;
+}
+
+export interface FormatOptions {
+  maxDenominator?: number;
+  decimalPlaces?: number;
+  unicodeFractions?: boolean;
+  locale?: string;
+}
+
+export type Scale = { factor: number } | { servings: { original: number; target: number } };
+
+export type RenderFormat = "plainText" | "markdown" | "csv";
+
+export interface RenderOptions {
+  format?: RenderFormat;
+  groupByCategory?: boolean;
+  provenance?: boolean;
+  formatOptions?: FormatOptions;
+}
+
+export type GroceryifyErrorKind =
+  | "invalidInput"
+  | "unknownLocale"
+  | "parse"
+  | "scale"
+  | "convert";
+
+/** What every function here throws. */
+export interface GroceryifyError {
+  kind: GroceryifyErrorKind;
+  message: string;
+}
+"#;
+
+/// The kind of error thrown to JavaScript, so callers can react without matching on messages.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ErrorKind {
+    /// An argument didn't have the shape of its TypeScript type.
+    InvalidInput,
+    UnknownLocale,
+    Parse,
+    Scale,
+    Convert,
+}
+
+/// An error thrown to JavaScript as a `GroceryifyError` object.
+#[derive(Serialize)]
+pub struct WasmError {
+    kind: ErrorKind,
+    message: String,
+}
+
+impl WasmError {
+    fn new(kind: ErrorKind, message: impl ToString) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<WasmError> for JsValue {
+    fn from(error: WasmError) -> Self {
+        // Serializing two strings can't fail, but fall back to the message alone if it somehow does
+        serde_wasm_bindgen::to_value(&error).unwrap_or_else(|_| JsValue::from_str(&error.message))
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<ParseIngredientError> for WasmError {
+    fn from(error: ParseIngredientError) -> Self {
+        Self::new(ErrorKind::Parse, error)
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<ScaleError> for WasmError {
+    fn from(error: ScaleError) -> Self {
+        Self::new(ErrorKind::Scale, error)
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<LocaleError> for WasmError {
+    fn from(error: LocaleError) -> Self {
+        Self::new(ErrorKind::UnknownLocale, error)
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<ConvertError> for WasmError {
+    fn from(error: ConvertError) -> Self {
+        Self::new(ErrorKind::Convert, error)
+    }
+}
+
+/// Reads an argument, which may be `undefined` only if `T` is an `Option`.
+#[cfg(feature = "wasm")]
+fn from_js<T: DeserializeOwned>(val: JsValue) -> Result<T, WasmError> {
+    serde_wasm_bindgen::from_value(val).map_err(|e| WasmError::new(ErrorKind::InvalidInput, e))
+}
+
+/// Returns a result as plain objects and arrays, rather than the `Map`s serde-wasm-bindgen
+/// makes of maps by default.
+#[cfg(feature = "wasm")]
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, WasmError> {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| WasmError::new(ErrorKind::InvalidInput, e))
+}
+
+/// Merges like ingredients, written in the given locale, into a list of ingredients.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(unchecked_return_type = "Ingredient[]")]
+pub fn merge(
+    #[wasm_bindgen(unchecked_param_type = "Ingredient[]")] val: JsValue,
+    locale_code: Option<String>,
+) -> Result<JsValue, WasmError> {
+    let ingredients: Vec<Ingredient> = from_js(val)?;
+    let options = MergeOptions {
+        locale: Some(Locale::lookup(locale_code.as_deref())?.code().to_string()),
+        ..Default::default()
+    };
+    let result = crate::merge_with_options(&ingredients, &options)
+        .into_iter()
+        .map(|item| item.ingredient)
+        .collect::<Vec<_>>();
+    to_js(&result)
+}
+
+/// Merges like ingredients into grocery items, which record how each was merged.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = mergeWithOptions, unchecked_return_type = "GroceryItem[]")]
+pub fn merge_with_options(
+    #[wasm_bindgen(unchecked_param_type = "Ingredient[]")] val: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "MergeOptions | undefined")] options: JsValue,
+) -> Result<JsValue, WasmError> {
+    let ingredients: Vec<Ingredient> = from_js(val)?;
+    let options: Option<MergeOptions> = from_js(options)?;
+    let options = options.unwrap_or_default();
+    options.locale()?;
+    to_js(&crate::merge_with_options(&ingredients, &options))
+}
+
+/// Parses a line such as "2 cups flour, sifted" written in the given locale.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(unchecked_return_type = "Ingredient")]
+pub fn parse(line: &str, locale_code: Option<String>) -> Result<JsValue, WasmError> {
+    let ingredient = crate::parse_ingredient_in(line, Locale::lookup(locale_code.as_deref())?)?;
+    to_js(&ingredient)
+}
+
+/// Scales a recipe's ingredients by a factor or from one number of servings to another.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(unchecked_return_type = "Ingredient[]")]
+pub fn scale(
+    #[wasm_bindgen(unchecked_param_type = "Ingredient[]")] val: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "Scale")] scale: JsValue,
+) -> Result<JsValue, WasmError> {
+    let ingredients: Vec<Ingredient> = from_js(val)?;
+    let scale: Scale = from_js(scale)?;
+    to_js(&crate::scale_ingredients(&ingredients, scale)?)
+}
+
+/// Renders ingredients' amounts and units for display.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(unchecked_return_type = "Formatted<Ingredient>[]")]
+pub fn format(
+    #[wasm_bindgen(unchecked_param_type = "Ingredient[]")] val: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "FormatOptions | undefined")] options: JsValue,
+) -> Result<JsValue, WasmError> {
+    let ingredients: Vec<Ingredient> = from_js(val)?;
+    let options: Option<FormatOptions> = from_js(options)?;
+    let options = options.unwrap_or_default();
+    options.locale()?;
+    to_js(&format_ingredients(&ingredients, &options))
+}
+
+/// Converts an ingredient's amount to another unit, through its density where one is known.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = convertUnit, unchecked_return_type = "Ingredient")]
+pub fn convert_unit(
+    #[wasm_bindgen(unchecked_param_type = "Ingredient")] val: JsValue,
+    unit: &str,
+    #[wasm_bindgen(unchecked_param_type = "MergeOptions | undefined")] options: JsValue,
+) -> Result<JsValue, WasmError> {
+    let ingredient: Ingredient = from_js(val)?;
+    let options: Option<MergeOptions> = from_js(options)?;
+    let options = options.unwrap_or_default();
+    options.locale()?;
+    to_js(&Merger::shared(&options).convert_unit(&ingredient, unit)?)
+}
+
+/// Renders merged grocery items as plain text, Markdown or CSV.
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
-pub fn merge(val: JsValue) -> Result<JsValue, JsValue> {
-    let ingredients: Vec<Ingredient> =
-        serde_wasm_bindgen::from_value(val).map_err(|e| JsValue::from_str(&e.to_string()))?;
-    let result = crate::merge(&ingredients);
-    serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))
+pub fn render(
+    #[wasm_bindgen(unchecked_param_type = "GroceryItem[]")] val: JsValue,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<String, WasmError> {
+    let items: Vec<GroceryItem> = from_js(val)?;
+    let options: Option<RenderOptions> = from_js(options)?;
+    let options = options.unwrap_or_default();
+    options.format_options.locale()?;
+    Ok(crate::render::render(&items, &options))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        density::DensityConversion,
+        formatting::{FormatOptions, format_ingredients},
+        grocery_item::{GroceryItem, SourceQuantity},
+        ingredient::Ingredient,
+        options::{AmbiguousUnits, DisplayUnit, MergeOptions, UnitSystem},
+        package::{PackageCount, PackageSize, Purchase},
+        pantry::PantryDeduction,
+        render::{RenderFormat, RenderOptions},
+        scaling::Scale,
+    };
+
+    /// The fields of an object type in `TYPES`, found after `declaration`, with whether each is
+    /// optional. Includes the fields of the interface it extends.
+    fn fields(declaration: &str) -> BTreeMap<String, bool> {
+        let (_, rest) = TYPES
+            .split_once(declaration)
+            .unwrap_or_else(|| panic!("TYPES has no {declaration:?}"));
+        let (head, rest) = rest.split_once('{').unwrap();
+        let (body, _) = rest.split_once('}').unwrap();
+        let mut fields = match head.trim().strip_prefix("extends ") {
+            Some(parent) => interface(parent.trim()),
+            None => BTreeMap::new(),
+        };
+        for line in body.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (field, _) = line.split_once(':').unwrap();
+            match field.strip_suffix('?') {
+                Some(field) => fields.insert(field.to_string(), true),
+                None => fields.insert(field.to_string(), false),
+            };
+        }
+        fields
+    }
+
+    fn interface(name: &str) -> BTreeMap<String, bool> {
+        fields(&format!("export interface {name} "))
+    }
+
+    /// The members of a union type in `TYPES`: string literals with their quotes, and the key of
+    /// object members.
+    fn union(name: &str) -> BTreeSet<String> {
+        let (_, rest) = TYPES
+            .split_once(&format!("export type {name} ="))
+            .unwrap_or_else(|| panic!("TYPES has no type {name}"));
+        let (members, _) = rest.split_once(";\n").unwrap();
+        members
+            .split('|')
+            .map(str::trim)
+            .filter(|member| !member.is_empty())
+            .map(|member| match member.strip_prefix('{') {
+                Some(object) => object.split_once(':').unwrap().0.trim().to_string(),
+                None => member.to_string(),
+            })
+            .collect()
+    }
+
+    fn object<T: Serialize>(value: &T) -> serde_json::Map<String, Value> {
+        match serde_json::to_value(value).unwrap() {
+            Value::Object(object) => object,
+            other => panic!("{other} is not an object"),
+        }
+    }
+
+    /// Checks that the fields `full` is written with are those of the TypeScript type, that a
+    /// required field is written even for the `bare` value, and that an optional one may be left
+    /// out when reading it.
+    fn assert_shape<T: Serialize + DeserializeOwned>(
+        fields: BTreeMap<String, bool>,
+        full: &T,
+        bare: &T,
+    ) {
+        let written = object(full).keys().cloned().collect::<BTreeSet<_>>();
+        assert_eq!(written, fields.keys().cloned().collect());
+        let bare = object(bare);
+        for (field, optional) in fields {
+            if optional {
+                let mut without = bare.clone();
+                without.remove(&field);
+                assert!(
+                    serde_json::from_value::<T>(Value::Object(without)).is_ok(),
+                    "{field} is optional but can't be left out"
+                );
+            } else {
+                assert!(
+                    bare.contains_key(&field),
+                    "{field} is required but left out"
+                );
+            }
+        }
+    }
+
+    /// Checks that `variants`, which should be all of an enum's, are written as the members of
+    /// the TypeScript union.
+    fn assert_union<T: Serialize>(name: &str, variants: &[T]) {
+        let written = variants
+            .iter()
+            .map(|variant| match serde_json::to_value(variant).unwrap() {
+                Value::String(string) => format!("{string:?}"),
+                Value::Object(object) => object.keys().next().unwrap().clone(),
+                other => panic!("{other} is neither a string nor an object"),
+            })
+            .collect::<BTreeSet<_>>();
+        assert_eq!(written, union(name), "{name}");
+    }
+
+    fn ingredient() -> Ingredient {
+        Ingredient {
+            amount_max: Some(3.0),
+            to_taste: true,
+            note: Some("sifted".to_string()),
+            source: Some("bread".to_string()),
+            ..Ingredient::new("flour", 2.0, "cup")
+        }
+    }
+
+    fn package_size() -> PackageSize {
+        PackageSize {
+            name: "bag".to_string(),
+            plural: Some("bags".to_string()),
+            amount: 2.0,
+            unit: "kg".to_string(),
+        }
+    }
+
+    #[test]
+    fn ingredient_matches_its_typescript() {
+        assert_shape(
+            interface("Ingredient"),
+            &ingredient(),
+            &Ingredient::new("flour", 2.0, "cup"),
+        );
+    }
+
+    #[test]
+    fn grocery_item_matches_its_typescript() {
+        let full = GroceryItem {
+            ingredient: ingredient(),
+            density_conversion: Some(DensityConversion {
+                matched_name: "flour".to_string(),
+                grams_per_cup: 120.0,
+            }),
+            pantry: Some(PantryDeduction {
+                amount: 1.0,
+                covered: false,
+            }),
+            purchase: Some(Purchase {
+                packages: vec![PackageCount {
+                    count: 1,
+                    name: "bag".to_string(),
+                }],
+                amount: 2.0,
+            }),
+            category: "Baking".to_string(),
+            sources: vec![SourceQuantity {
+                interpreted_as: Some("fl oz".to_string()),
+                ..(&ingredient()).into()
+            }],
+        };
+        let bare = GroceryItem {
+            ingredient: Ingredient::new("flour", 2.0, "cup"),
+            density_conversion: None,
+            pantry: None,
+            purchase: None,
+            category: "Baking".to_string(),
+            sources: vec![],
+        };
+        assert_shape(interface("GroceryItem"), &full, &bare);
+        assert_shape(
+            interface("SourceQuantity"),
+            &full.sources[0],
+            &(&bare.ingredient).into(),
+        );
+        let conversion = full.density_conversion.as_ref().unwrap();
+        assert_shape(interface("DensityConversion"), conversion, conversion);
+        let pantry = full.pantry.as_ref().unwrap();
+        assert_shape(interface("PantryDeduction"), pantry, pantry);
+        let purchase = full.purchase.as_ref().unwrap();
+        assert_shape(interface("Purchase"), purchase, purchase);
+        assert_shape(
+            interface("PackageCount"),
+            &purchase.packages[0],
+            &purchase.packages[0],
+        );
+    }
+
+    #[test]
+    fn formatted_ingredient_matches_its_typescript() {
+        let options = FormatOptions::default();
+        let mut fields = fields("export type Formatted<T> = T & ");
+        fields.extend(interface("Ingredient"));
+        assert_shape(
+            fields,
+            &format_ingredients(&[ingredient()], &options)[0],
+            &format_ingredients(&[Ingredient::new("flour", 2.0, "cup")], &options)[0],
+        );
+    }
+
+    #[test]
+    fn options_match_their_typescript() {
+        let merge_options = MergeOptions {
+            locale: Some("es".to_string()),
+            ..Default::default()
+        };
+        assert_shape(interface("MergeOptions"), &merge_options, &merge_options);
+        let format_options = FormatOptions {
+            locale: Some("es".to_string()),
+            ..Default::default()
+        };
+        assert_shape(interface("FormatOptions"), &format_options, &format_options);
+        let render_options = RenderOptions::default();
+        assert_shape(interface("RenderOptions"), &render_options, &render_options);
+        let bare = PackageSize {
+            plural: None,
+            ..package_size()
+        };
+        assert_shape(interface("PackageSize"), &package_size(), &bare);
+        // Every option may be left out
+        let options = [
+            interface("MergeOptions"),
+            interface("FormatOptions"),
+            interface("RenderOptions"),
+        ];
+        assert!(
+            options
+                .iter()
+                .flat_map(|fields| fields.values())
+                .all(|optional| *optional)
+        );
+    }
+
+    #[test]
+    fn errors_match_their_typescript() {
+        let written = object(&WasmError::new(ErrorKind::Parse, "Invalid amount in: x"));
+        let fields = interface("GroceryifyError");
+        assert_eq!(
+            written.keys().collect::<Vec<_>>(),
+            fields.keys().collect::<Vec<_>>()
+        );
+        assert!(fields.values().all(|optional| !optional));
+    }
+
+    #[test]
+    fn enums_match_their_typescript() {
+        assert_union(
+            "DisplayUnit",
+            &[
+                DisplayUnit::BestFit,
+                DisplayUnit::Original,
+                DisplayUnit::Fixed("g".to_string()),
+            ],
+        );
+        assert_union(
+            "UnitSystem",
+            &[
+                UnitSystem::Original,
+                UnitSystem::Metric,
+                UnitSystem::UsCustomary,
+            ],
+        );
+        assert_union(
+            "AmbiguousUnits",
+            &[
+                AmbiguousUnits::ByName,
+                AmbiguousUnits::Weight,
+                AmbiguousUnits::Volume,
+                AmbiguousUnits::KeepApart,
+            ],
+        );
+        assert_union(
+            "Scale",
+            &[
+                Scale::Factor(2.0),
+                Scale::Servings {
+                    original: 4.0,
+                    target: 6.0,
+                },
+            ],
+        );
+        assert_union(
+            "RenderFormat",
+            &[
+                RenderFormat::PlainText,
+                RenderFormat::Markdown,
+                RenderFormat::Csv,
+            ],
+        );
+        assert_union(
+            "GroceryifyErrorKind",
+            &[
+                ErrorKind::InvalidInput,
+                ErrorKind::UnknownLocale,
+                ErrorKind::Parse,
+                ErrorKind::Scale,
+                ErrorKind::Convert,
+            ],
+        );
+    }
 }
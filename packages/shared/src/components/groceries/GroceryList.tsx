@@ -1,26 +1,63 @@
+import { Fragment } from 'react';
 import { Ingredient } from '@/types/Recipe';
 import IngredientDisplay from '../recipe/IngredientDisplay';
 
+interface GroceryListProps {
+  groceries: Ingredient[];
+  grouped?: boolean;
+}
+
 /**
  * GroceryList component.
  *
  * Renders a list of grocery ingredients using the `IngredientDisplay` component.
+ * When `grouped` is set, the groceries are expected in store-section order and a
+ * heading is shown at the start of each section. Items that list the recipes
+ * they are needed for show those recipes underneath, items rounded to package
+ * sizes show what to buy, priced items show their estimated cost, and items
+ * that couldn't be merged with another line of the same ingredient show why.
  *
- * @param {{ groceries: Ingredient[] }} props - Component props.
+ * @param {GroceryListProps} props - Component props.
  * @param {Ingredient[]} props.groceries - Array of ingredients to display.
+ * @param {boolean} [props.grouped] - Whether to show store-section headings.
  * @returns {JSX.Element} An unordered list of ingredients.
  *
  * @example
  * // <GroceryList groceries={[{ id: '1', name: 'Tomato', amount: 2, unit: 'count' }]} />
  */
-const GroceryList: React.FC<{ groceries: Ingredient[] }> = ({
-  groceries
-}: {
-  groceries: Ingredient[];
-}) => (
+const GroceryList: React.FC<GroceryListProps> = ({
+  groceries,
+  grouped = false
+}: GroceryListProps) => (
   <ul>
     {groceries.map((g, i) => (
-      <IngredientDisplay key={i} ingredient={g} />
+      <Fragment key={i}>
+        {grouped && g.category !== groceries[i - 1]?.category && (
+          <li className="list-none font-bold capitalize mt-2">
+            {g.category ?? 'other'}
+          </li>
+        )}
+        <IngredientDisplay ingredient={g} />
+        {g.recipes && g.recipes.length > 0 && (
+          <li className="list-none text-xs text-gray-500 ml-4">
+            For{' '}
+            {[...new Set(g.recipes.map((r) => r.recipeTitle))].join(', ')}
+          </li>
+        )}
+        {g.cost !== undefined && (
+          <li className="list-none text-xs text-gray-500 ml-4">
+            About {g.cost.toFixed(2)}
+          </li>
+        )}
+        {g.buy && (
+          <li className="list-none text-xs text-gray-500 ml-4">Buy {g.buy}</li>
+        )}
+        {g.hints?.map((hint, j) => (
+          <li key={j} className="list-none text-xs text-amber-600 ml-4">
+            {hint}
+          </li>
+        ))}
+      </Fragment>
     ))}
   </ul>
 );
@@ -2,17 +2,58 @@ import Input from '@/components/forms/Input';
 import Button from '@/components/forms/Button';
 import ResponsiveForm from '@/components/forms/ResponsiveForm';
 import GroceryList from '@/components/groceries/GroceryList';
-import { GroceriesFetcher } from '@/hooks/fetchers/useGroceries';
+import {
+  GroceriesFetcher,
+  GroceriesSaveFormat
+} from '@/hooks/fetchers/useGroceries';
 import { today } from '@/utils/dateUtils';
+import getSelectStyles from '@/utils/styles/getSelectStyles';
+import Select from 'react-select';
 
-interface GroceriesProps extends GroceriesFetcher {
+const UNIT_SYSTEM_OPTIONS = [
+  { label: 'As written', value: 'original' },
+  { label: 'Metric', value: 'metric' },
+  { label: 'US customary', value: 'usCustomary' }
+];
+
+const AMBIGUOUS_UNIT_OPTIONS = [
+  { label: 'By ingredient', value: 'byName' },
+  { label: 'Weight', value: 'weight' },
+  { label: 'Fluid', value: 'volume' },
+  { label: 'Keep apart', value: 'keepApart' }
+];
+
+const SAVE_FORMATS: { label: string; value: GroceriesSaveFormat }[] = [
+  { label: 'Save as text', value: 'plainText' },
+  { label: 'Save as Markdown', value: 'markdown' },
+  { label: 'Save as CSV', value: 'csv' }
+];
+
+interface GroceriesProps
+  extends Omit<GroceriesFetcher, 'handleSaveGroceries'> {
+  handleSaveGroceries?: GroceriesFetcher['handleSaveGroceries'];
   handlePrint: (e: React.MouseEvent<HTMLButtonElement>) => void;
+  showUnitSystem?: boolean;
+  showGroupByCategory?: boolean;
+  showSubtractPantry?: boolean;
+  showRoundToPackages?: boolean;
+  showSave?: boolean;
+  isDark?: boolean;
 }
 
 const Groceries = ({
   groceries,
+  grouped,
+  totalCost,
   handleGroceriesRequest,
-  handlePrint
+  handlePrint,
+  handleSaveGroceries,
+  showUnitSystem = false,
+  showGroupByCategory = false,
+  showSubtractPantry = false,
+  showRoundToPackages = false,
+  showSave = false,
+  isDark = false
 }: GroceriesProps) => (
   <ResponsiveForm onSubmit={handleGroceriesRequest}>
     <h1 className="font-bold text-2xl mb-4">Grocery List</h1>
@@ -35,10 +76,70 @@ const Groceries = ({
           defaultValue={today()}
         />
       </label>
+      {showUnitSystem && (
+        <label className="flex flex-row w-full items-center gap-2">
+          <span className="whitespace-nowrap">Units:</span>
+          <Select
+            name="unitSystem"
+            styles={getSelectStyles(isDark)}
+            options={UNIT_SYSTEM_OPTIONS}
+            defaultValue={UNIT_SYSTEM_OPTIONS[0]}
+            className="w-full"
+          />
+        </label>
+      )}
+      {showUnitSystem && (
+        <label className="flex flex-row w-full items-center gap-2">
+          <span className="whitespace-nowrap">Ounces:</span>
+          <Select
+            name="ambiguousUnits"
+            styles={getSelectStyles(isDark)}
+            options={AMBIGUOUS_UNIT_OPTIONS}
+            defaultValue={AMBIGUOUS_UNIT_OPTIONS[0]}
+            className="w-full"
+          />
+        </label>
+      )}
+      {showGroupByCategory && (
+        <label className="flex flex-row items-center gap-2 whitespace-nowrap">
+          <input type="checkbox" name="groupByCategory" />
+          By aisle
+        </label>
+      )}
+      {showSubtractPantry && (
+        <label className="flex flex-row items-center gap-2 whitespace-nowrap">
+          <input type="checkbox" name="subtractPantryItems" />
+          Minus pantry
+        </label>
+      )}
+      {showRoundToPackages && (
+        <label className="flex flex-row items-center gap-2 whitespace-nowrap">
+          <input type="checkbox" name="roundToPackages" />
+          Packages
+        </label>
+      )}
       <Button>Get</Button>
     </div>
-    <GroceryList groceries={groceries} />
+    <GroceryList groceries={groceries} grouped={grouped} />
+    {totalCost !== undefined && (
+      <div className="font-bold mt-2">
+        Estimated total: {totalCost.toFixed(2)}
+      </div>
+    )}
     {groceries ? <Button onClick={handlePrint}>Print</Button> : <></>}
+    {showSave && handleSaveGroceries && groceries.length > 0 && (
+      <div className="flex flex-row gap-2 mt-2">
+        {SAVE_FORMATS.map(({ label, value }) => (
+          <Button
+            key={value}
+            type="button"
+            onClick={() => handleSaveGroceries(value)}
+          >
+            {label}
+          </Button>
+        ))}
+      </div>
+    )}
     <div className="text-xs text-gray-500 mt-4">
       Grocery list generation depends on WordNet. WordNet is a registered
       trademark of Princeton University. CookyCardz is neither associated with
@@ -87,7 +87,8 @@ export const decimalToFraction = (num: number): string => {
  *
  * Rendering rules:
  * - If `ingredient.amount` === 0, the amount is omitted.
- * - Amounts are converted to fractions via `decimalToFraction`.
+ * - Pre-rendered `amountDisplay` and `unitDisplay` are used when present.
+ * - Otherwise amounts are converted to fractions via `decimalToFraction`.
  * - Units equal to 'count' are not displayed next to the amount.
  *
  * @param {{ ingredient: Ingredient }} props - Component props
@@ -96,8 +97,12 @@ export const decimalToFraction = (num: number): string => {
 const IngredientDisplay = ({ ingredient }: { ingredient: Ingredient }) => (
   <li>
     <strong>
-      {ingredient?.amount === 0 ? '' : decimalToFraction(ingredient?.amount)}{' '}
-      {ingredient?.unit === 'count' ? '' : ingredient?.unit}
+      {ingredient?.amount === 0
+        ? ''
+        : (ingredient?.amountDisplay ?? decimalToFraction(ingredient?.amount))}{' '}
+      {ingredient?.unit === 'count'
+        ? ''
+        : (ingredient?.unitDisplay ?? ingredient?.unit)}
     </strong>{' '}
     {ingredient?.name}
   </li>
@@ -3,11 +3,16 @@ import { Ingredient } from '@/types/Recipe';
 import RequestFn from '@/types/RequestFn';
 import { useState } from 'react';
 
+export type GroceriesSaveFormat = 'plainText' | 'markdown' | 'csv';
+
 export interface GroceriesFetcher {
   groceries: Ingredient[];
+  grouped: boolean;
+  totalCost?: number;
   handleGroceriesRequest: (
     e: React.FormEvent<HTMLFormElement>
   ) => Promise<void>;
+  handleSaveGroceries: (format: GroceriesSaveFormat) => Promise<void>;
 }
 
 /**
@@ -17,13 +22,20 @@ export interface GroceriesFetcher {
  * @param addNotification - Function to add a notification
  * @returns an object containing the following properties:
  * - groceries: An array of Ingredient objects representing the user's groceries.
+ * - grouped: Whether the groceries are ordered by store section rather than by name.
+ * - totalCost: The estimated cost of the priced groceries, if the backend estimates costs.
  * - handleGroceriesRequest: A function that handles the request for groceries.
+ * - handleSaveGroceries: A function that saves the last requested list to a file, with the
+ *   recipes each line came from, on backends that support it.
  */
 const useGroceries = (
   request: RequestFn,
   addNotification: (message: string, type: NotificationKind) => void
 ): GroceriesFetcher => {
   const [groceries, setGroceries] = useState<Ingredient[]>([]);
+  const [grouped, setGrouped] = useState(false);
+  const [totalCost, setTotalCost] = useState<number | undefined>(undefined);
+  const [lastParams, setLastParams] = useState<URLSearchParams | null>(null);
 
   const handleGroceriesRequest = async (
     e: React.FormEvent<HTMLFormElement>
@@ -32,30 +44,64 @@ const useGroceries = (
     const formData = new FormData(e.currentTarget);
     const fromDate = formData.get('fromDate') as string;
     const toDate = formData.get('toDate') as string;
+    const unitSystem = formData.get('unitSystem') as string | null;
+    const ambiguousUnits = formData.get('ambiguousUnits') as string | null;
     if (new Date(fromDate) > new Date(toDate)) {
       addNotification('From date must be before to date.', 'error');
       return;
     }
-    const resp = await request(
-      `/api/groceries?fromDate=${fromDate}&toDate=${toDate}`,
-      'GET'
-    );
+    const groupByCategory = formData.get('groupByCategory') === 'on';
+    const subtractPantryItems = formData.get('subtractPantryItems') === 'on';
+    const roundToPackages = formData.get('roundToPackages') === 'on';
+    const params = new URLSearchParams({ fromDate, toDate });
+    if (unitSystem) params.set('unitSystem', unitSystem);
+    if (ambiguousUnits) params.set('ambiguousUnits', ambiguousUnits);
+    if (groupByCategory) params.set('groupByCategory', 'true');
+    if (subtractPantryItems) params.set('subtractPantryItems', 'true');
+    if (roundToPackages) params.set('roundToPackages', 'true');
+    const resp = await request(`/api/groceries?${params}`, 'GET');
     if (!resp.ok) {
       const json = await resp.json();
       addNotification(json.error.message, 'error');
       return;
     }
     const json = await resp.json();
+    setLastParams(params);
+    // Grouped lists arrive in store order, which sorting by name would undo
+    setGrouped(groupByCategory);
+    setTotalCost(json.totalCost);
     setGroceries(
-      json.data.sort((a: Ingredient, b: Ingredient) =>
-        a.name.localeCompare(b.name)
-      )
+      groupByCategory
+        ? json.data
+        : json.data.sort((a: Ingredient, b: Ingredient) =>
+            a.name.localeCompare(b.name)
+          )
     );
   };
 
+  const handleSaveGroceries = async (format: GroceriesSaveFormat) => {
+    if (!lastParams) {
+      addNotification('Get a grocery list before saving it.', 'error');
+      return;
+    }
+    const params = new URLSearchParams(lastParams);
+    params.set('format', format);
+    params.set('provenance', 'true');
+    const resp = await request(`/api/groceries/save?${params}`, 'GET');
+    const json = await resp.json();
+    if (!resp.ok) {
+      addNotification(json.error.message, 'error');
+      return;
+    }
+    addNotification(json.data.message, 'success');
+  };
+
   return {
     groceries,
-    handleGroceriesRequest
+    grouped,
+    totalCost,
+    handleGroceriesRequest,
+    handleSaveGroceries
   };
 };
 
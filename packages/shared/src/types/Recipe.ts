@@ -7,6 +7,23 @@
 
 import { Color } from '@/utils/styles/colorUtils';
 
+/**
+ * Represents a scheduled recipe that a grocery item was needed for
+ */
+export interface GrocerySource {
+  recipeId: number;
+  recipeTitle: string;
+  scheduledDate?: string;
+  amount: number;
+  unit: string;
+  /** Upper end of a range such as "2–3 cloves". */
+  amountMax?: number;
+  /** Set for items without a measured amount, such as "salt to taste". */
+  toTaste?: boolean;
+  /** The unit an ambiguous one such as "oz" was read as, e.g. "fl oz". */
+  interpretedAs?: string;
+}
+
 /**
  * Represents an ingredient
  */
@@ -15,6 +32,24 @@ export interface Ingredient {
   name: string;
   amount: number;
   unit: string;
+  /** Upper end of a range such as "2–3 cloves", whose lower end is `amount`. */
+  amountMax?: number;
+  /** Set for ingredients without a measured amount, such as "salt to taste". */
+  toTaste?: boolean;
+  /** Rendered amount (e.g. "1 ½"), present on formatted grocery items. */
+  amountDisplay?: string;
+  /** Unit pluralized to agree with the amount, present on formatted grocery items. */
+  unitDisplay?: string;
+  /** Store section, present on merged grocery items (e.g. "produce"). */
+  category?: string;
+  /** Scheduled recipes the item is needed for, present on desktop grocery items. */
+  recipes?: GrocerySource[];
+  /** Why the item couldn't be merged with other lines of the same ingredient. */
+  hints?: string[];
+  /** The packages to buy, e.g. "1 dozen", when the list is rounded to package sizes. */
+  buy?: string;
+  /** Estimated cost from the price book, present on desktop grocery items. */
+  cost?: number;
 }
 
 /**